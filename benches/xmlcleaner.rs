@@ -0,0 +1,147 @@
+// times cleaning and dirtying test_data/testcase against the regex cleaner
+// the tokenizer replaced, kept here as it was. run with
+// cargo bench --bench xmlcleaner
+
+use std::collections::HashSet;
+use std::fs;
+use std::time::{Duration, Instant};
+
+use fancy_regex::{ Captures, Match, Regex, Replacer };
+use lazy_static::lazy_static;
+
+use missioneditor2::playmission::{ clean, dirty };
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+// how many times each is run, with the average reported
+const RUNS: u32 = 20;
+
+lazy_static! {
+    static ref OBJECTS: HashSet<&'static str> = {
+        let mut m = HashSet::new();
+        m.insert("ACTIVEPROP");
+        m.insert("CHARACTER");
+        m.insert("DOOR");
+        m.insert("GAME");
+        m.insert("LOCATION");
+        m.insert("MEDIA");
+        m.insert("PICKUP");
+        m.insert("PLAYER");
+        m.insert("PROP");
+        m.insert("PROPERTIES");
+        m.insert("PROPERTY");
+        m.insert("RULE");
+        m.insert("SPECIALEFFECT");
+        m.insert("TRIGGER");
+        m.insert("USERDATA");
+        m
+    };
+}
+
+// returns position of first byte of matching tag for first found opening tag
+fn find_matching_tag(s: &str) -> Result<Option<Match<'_>>> {
+    let mut depth = 0;
+    let tag = Regex::new(r"<(.)(.*?)>").unwrap();
+    let option = tag.captures_iter(s).find(|captures| {
+        if let Ok(captures) = captures {
+            if captures.get(1).unwrap().as_str() == "/" {
+                depth -= 1;
+                depth == 0
+            } else {
+                depth += 1;
+                false
+            }
+        } else {
+            true
+        }
+    });
+
+    Ok(option.transpose()?.map(|captures| captures.get(0).unwrap()))
+}
+
+// the regex clean, replacing missionmaker namespace syntax with xml elements
+fn regex_clean<T: Into<String>>(s: T) -> Result<String> {
+
+    let opening_tag = Regex::new(r"<\w+: (\w+) >").unwrap();
+    let mut s = s.into();
+    let mut result: String = String::from("");
+
+    while let Some(captures) = opening_tag.captures(&s)? {
+
+        let open = captures.get(0).unwrap();
+        let name = captures.get(1).unwrap().as_str();
+        let new_open = &*format!("<{name}>");
+        let new_close = &*format!("</{name}>");
+
+        if let Some(end) = find_matching_tag(&s)? {
+            result.push_str(&s[..open.start()]);
+            result.push_str(new_open);
+            result.push_str(&regex_clean(&s[open.end()..end.start()])?);
+            result.push_str(new_close);
+            s = s[end.end()..].to_string()
+        } else {
+            return Err(format!("no matching tag for {}", open.as_str()).into())
+        }
+
+    };
+
+    result.push_str(&s);
+
+    Ok(result)
+
+}
+
+// replace overlapping regex, assuming the replacement pattern will not
+// shift any characters before the index
+fn replace_overlapping<T: Into<String>, R: Replacer>(s: T, re: &str, mut replacement: R) -> Result<String> {
+    let re = Regex::new(re).unwrap();
+    let mut s = s.into();
+    let mut result = String::from("");
+    while let Some(mtch) = re.find(s.as_ref())? {
+        let i = mtch.start();
+        result.push_str(&s[0..i]);
+        s = re.replace(&s[i..], replacement.by_ref()).into();
+    }
+    result.push_str(&s);
+    Ok(result)
+}
+
+// the regex dirty, replacing xml elements with missionmaker namespace syntax
+fn regex_dirty<T: Into<String>>(s: T) -> Result<String> {
+
+    let legal_tag = r"(?s)<(\w+)>(.*?)</\1>";
+    let illegal_tag = |captures: &Captures| {
+        let subtype = captures.get(1).unwrap().as_str();
+        let contents = captures.get(2).unwrap().as_str();
+        let tag = if OBJECTS.contains(subtype) { "OBJECT" } else { "ATTR" };
+        format!("<{tag}: {subtype} >{contents}</{tag}>")
+    };
+    replace_overlapping(s, legal_tag, illegal_tag)
+
+}
+
+// runs f over and over, returning the last output and the average time
+fn time<T>(mut f: impl FnMut() -> T) -> (T, Duration) {
+    let now = Instant::now();
+    let mut output = f();
+    for _ in 1..RUNS {
+        output = f();
+    }
+    (output, now.elapsed() / RUNS)
+}
+
+fn main() {
+
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_data/testcase/Test.mission");
+    let mission = fs::read_to_string(path).unwrap();
+    println!("testcase ({} bytes), average of {RUNS} runs", mission.len());
+
+    let (cleaned, clean_time) = time(|| clean(&mission).unwrap());
+    let (_, dirty_time) = time(|| dirty(&cleaned).unwrap());
+    println!("tokenizer: clean {clean_time:?}, dirty {dirty_time:?}");
+
+    let (cleaned, clean_time) = time(|| regex_clean(mission.as_str()).unwrap());
+    let (_, dirty_time) = time(|| regex_dirty(cleaned.as_str()).unwrap());
+    println!("regex:     clean {clean_time:?}, dirty {dirty_time:?}");
+
+}
@@ -0,0 +1,128 @@
+use js_sys::{Array, Promise, Uint8Array};
+use uuid::Uuid;
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{IdbDatabase, IdbObjectStore, IdbRequest, IdbTransactionMode};
+
+use super::{ AutosaveError as Error, Result, Session, Storage };
+
+const DATABASE: &str = "missioneditor2";
+const VERSION: u32 = 1;
+
+// sessions are kept as json, and snapshots as bytes, under the session id
+const SESSIONS: &str = "sessions";
+const SNAPSHOTS: &str = "snapshots";
+
+// keeps sessions in the browser's indexeddb
+pub struct IndexedDb {
+    db: IdbDatabase,
+}
+
+impl IndexedDb {
+
+    // opens the database, creating it on first use
+    pub async fn open() -> Result<Self> {
+
+        let factory = web_sys::window().expect("missing window")
+            .indexed_db().map_err(browser)?
+            .ok_or(Error::Browser("indexeddb unavailable".into()))?;
+        let request = factory.open_with_u32(DATABASE, VERSION).map_err(browser)?;
+
+        let upgrading = request.clone();
+        let on_upgrade = Closure::once_into_js(move |_: web_sys::Event| {
+            let db: IdbDatabase = upgrading.result().unwrap().unchecked_into();
+            db.create_object_store(SESSIONS).unwrap();
+            db.create_object_store(SNAPSHOTS).unwrap();
+        });
+        request.set_onupgradeneeded(Some(on_upgrade.unchecked_ref()));
+
+        let db = finished(&request).await?.unchecked_into();
+        Ok(Self { db })
+
+    }
+
+    // get stores by name, for reading or writing
+    fn stores(&self, mode: IdbTransactionMode) -> Result<(IdbObjectStore, IdbObjectStore)> {
+        let names = Array::of2(&SESSIONS.into(), &SNAPSHOTS.into());
+        let transaction = self.db.transaction_with_str_sequence_and_mode(&names, mode).map_err(browser)?;
+        Ok((
+            transaction.object_store(SESSIONS).map_err(browser)?,
+            transaction.object_store(SNAPSHOTS).map_err(browser)?,
+        ))
+    }
+
+}
+
+impl Storage for IndexedDb {
+
+    async fn sessions(&self) -> Result<Vec<Session>> {
+        let (sessions, _) = self.stores(IdbTransactionMode::Readonly)?;
+        let all: Array = finished(&sessions.get_all().map_err(browser)?).await?.unchecked_into();
+        all.iter()
+            .map(|v| Ok(serde_json::from_str(&v.as_string().unwrap_or_default())?))
+            .collect()
+    }
+
+    async fn write(&self, session: &Session, snapshot: &[u8]) -> Result<()> {
+        let (sessions, snapshots) = self.stores(IdbTransactionMode::Readwrite)?;
+        let key = JsValue::from_str(&session.id.to_string());
+        let session = JsValue::from_str(&serde_json::to_string(session)?);
+        let snapshot = Uint8Array::from(snapshot);
+        let session_put = sessions.put_with_key(&session, &key).map_err(browser)?;
+        let snapshot_put = snapshots.put_with_key(&snapshot, &key).map_err(browser)?;
+        finished(&session_put).await?;
+        finished(&snapshot_put).await?;
+        Ok(())
+    }
+
+    async fn read(&self, id: Uuid) -> Result<Vec<u8>> {
+        let (_, snapshots) = self.stores(IdbTransactionMode::Readonly)?;
+        let key = JsValue::from_str(&id.to_string());
+        let snapshot = finished(&snapshots.get(&key).map_err(browser)?).await?;
+        if snapshot.is_undefined() {
+            return Err(Error::NoSession(id))
+        }
+        Ok(Uint8Array::new(&snapshot).to_vec())
+    }
+
+    async fn remove(&self, id: Uuid) -> Result<()> {
+        let (sessions, snapshots) = self.stores(IdbTransactionMode::Readwrite)?;
+        let key = JsValue::from_str(&id.to_string());
+        let session_delete = sessions.delete(&key).map_err(browser)?;
+        let snapshot_delete = snapshots.delete(&key).map_err(browser)?;
+        finished(&session_delete).await?;
+        finished(&snapshot_delete).await?;
+        Ok(())
+    }
+
+}
+
+// waits for a request to succeed or fail, returning its result
+async fn finished(request: &IdbRequest) -> Result<JsValue> {
+
+    let promise = Promise::new(&mut |resolve, reject| {
+        let succeeded = request.clone();
+        let on_success = Closure::once_into_js(move |_: web_sys::Event| {
+            resolve.call1(&JsValue::NULL, &succeeded.result().unwrap_or_default()).unwrap();
+        });
+        let failed = request.clone();
+        let on_error = Closure::once_into_js(move |_: web_sys::Event| {
+            let error = failed.error().ok().flatten().map(JsValue::from).unwrap_or_default();
+            reject.call1(&JsValue::NULL, &error).unwrap();
+        });
+        request.set_onsuccess(Some(on_success.unchecked_ref()));
+        request.set_onerror(Some(on_error.unchecked_ref()));
+    });
+
+    JsFuture::from(promise).await.map_err(browser)
+
+}
+
+// describe an error thrown by the browser
+fn browser(e: JsValue) -> Error {
+    let message = e.dyn_ref::<js_sys::Error>()
+        .map(|e| String::from(e.message()))
+        .or_else(|| e.as_string())
+        .unwrap_or_else(|| format!("{e:?}"));
+    Error::Browser(message)
+}
@@ -0,0 +1,125 @@
+// keeps snapshots of the open mission while it is being edited, so that
+// work can be recovered if the tab closes before it was saved
+mod indexeddb;
+#[cfg(test)]
+mod native;
+
+use std::future::Future;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use uuid::Uuid;
+
+pub use indexeddb::IndexedDb;
+
+// how many sessions are kept before the oldest are forgotten
+const KEEP: usize = 5;
+
+// an editing session, from opening a mission until the tab is closed
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Session {
+    pub id: Uuid,
+    pub name: String,
+    // milliseconds since the unix epoch of the last autosave
+    pub timestamp: f64,
+}
+
+impl Session {
+
+    // create new for a freshly opened mission
+    pub fn new(name: impl Into<String>, timestamp: f64) -> Self {
+        Self { id: Uuid::new_v4(), name: name.into(), timestamp }
+    }
+
+}
+
+// somewhere snapshots can be kept between page loads
+pub trait Storage {
+
+    // get every stored session, in any order
+    fn sessions(&self) -> impl Future<Output = Result<Vec<Session>>>;
+
+    // store the snapshot for a session, replacing the one before it
+    fn write(&self, session: &Session, snapshot: &[u8]) -> impl Future<Output = Result<()>>;
+
+    // get the last snapshot stored for a session
+    fn read(&self, id: Uuid) -> impl Future<Output = Result<Vec<u8>>>;
+
+    // forget a session and its snapshot
+    fn remove(&self, id: Uuid) -> impl Future<Output = Result<()>>;
+
+}
+
+// stores a snapshot, then forgets all but the most recent sessions
+pub async fn store(storage: &impl Storage, session: &Session, snapshot: &[u8]) -> Result<()> {
+    storage.write(session, snapshot).await?;
+    for old in recent(storage).await?.into_iter().skip(KEEP) {
+        storage.remove(old.id).await?;
+    }
+    Ok(())
+}
+
+// get stored sessions, most recent first
+pub async fn recent(storage: &impl Storage) -> Result<Vec<Session>> {
+    let mut sessions = storage.sessions().await?;
+    sessions.sort_by(|a, b| b.timestamp.total_cmp(&a.timestamp));
+    Ok(sessions)
+}
+
+pub type Result<T> = std::result::Result<T, AutosaveError>;
+
+#[derive(Debug, Error)]
+pub enum AutosaveError {
+    #[error("browser storage failed: {0}")]
+    Browser(String),
+    #[error("no autosave stored for session {0}")]
+    NoSession(Uuid),
+    #[error("reader/writer failure")]
+    Io {
+        #[from]
+        source: std::io::Error,
+    },
+    #[error("failed reading or writing session")]
+    Json {
+        #[from]
+        source: serde_json::Error,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::native::Native;
+    use crate::utils::block_on;
+
+    #[test]
+    fn store_keeps_most_recent() {
+        let storage = Native::temporary();
+        let sessions: Vec<Session> = (0..KEEP + 2)
+            .map(|i| Session::new(format!("mission {i}"), i as f64))
+            .collect();
+
+        for session in &sessions {
+            block_on(store(&storage, session, session.name.as_bytes())).unwrap();
+        }
+
+        let found = block_on(recent(&storage)).unwrap();
+        let expected: Vec<Session> = sessions.iter().rev().take(KEEP).cloned().collect();
+        assert_eq!(expected, found);
+        assert_eq!(b"mission 6".to_vec(), block_on(storage.read(sessions[6].id)).unwrap());
+        assert!(matches!(block_on(storage.read(sessions[0].id)), Err(AutosaveError::NoSession(_))));
+    }
+
+    #[test]
+    fn store_replaces_session() {
+        let storage = Native::temporary();
+        let mut session = Session::new("mission", 1.0);
+
+        block_on(store(&storage, &session, b"first")).unwrap();
+        session.timestamp = 2.0;
+        block_on(store(&storage, &session, b"second")).unwrap();
+
+        assert_eq!(vec![session.clone()], block_on(recent(&storage)).unwrap());
+        assert_eq!(b"second".to_vec(), block_on(storage.read(session.id)).unwrap());
+    }
+}
@@ -0,0 +1,68 @@
+use std::{fs, io::ErrorKind, path::PathBuf};
+use uuid::Uuid;
+
+use super::{ AutosaveError as Error, Result, Session, Storage };
+
+// keeps sessions as files in a directory, for running outside a browser
+pub struct Native {
+    dir: PathBuf,
+}
+
+impl Native {
+
+    // create new over a directory, creating it if it is missing
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    // create new over an empty directory under the system temp dir
+    pub fn temporary() -> Self {
+        let dir = std::env::temp_dir().join(format!("missioneditor2-{}", Uuid::new_v4()));
+        Self::new(dir).unwrap()
+    }
+
+    fn session_path(&self, id: Uuid) -> PathBuf {
+        self.dir.join(format!("{id}.json"))
+    }
+
+    fn snapshot_path(&self, id: Uuid) -> PathBuf {
+        self.dir.join(format!("{id}.snapshot"))
+    }
+
+}
+
+impl Storage for Native {
+
+    async fn sessions(&self) -> Result<Vec<Session>> {
+        let mut sessions = vec![];
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|e| e == "json") {
+                sessions.push(serde_json::from_slice(&fs::read(path)?)?);
+            }
+        }
+        Ok(sessions)
+    }
+
+    async fn write(&self, session: &Session, snapshot: &[u8]) -> Result<()> {
+        fs::write(self.snapshot_path(session.id), snapshot)?;
+        fs::write(self.session_path(session.id), serde_json::to_vec(session)?)?;
+        Ok(())
+    }
+
+    async fn read(&self, id: Uuid) -> Result<Vec<u8>> {
+        fs::read(self.snapshot_path(id)).map_err(|e| match e.kind() {
+            ErrorKind::NotFound => Error::NoSession(id),
+            _ => e.into(),
+        })
+    }
+
+    async fn remove(&self, id: Uuid) -> Result<()> {
+        fs::remove_file(self.session_path(id))?;
+        fs::remove_file(self.snapshot_path(id))?;
+        Ok(())
+    }
+
+}
@@ -0,0 +1,166 @@
+// command line interface, for editing missions without the editor
+
+use std::{fs, path::Path, process::ExitCode};
+
+use missioneditor2::journal::{apply_to_all, Journal};
+use missioneditor2::playmission::error::chain;
+use missioneditor2::tea::TeaHandler;
+
+const USAGE: &str = "usage:
+  missioneditor2-cli script <script.rhai> <in.playmission> <out.playmission>
+  missioneditor2-cli merge <in.playmission> <other.playmission> <out.playmission>
+  missioneditor2-cli diff <before.playmission> <after.playmission>
+  missioneditor2-cli replay <journal.json> <out dir> <in.playmission>...";
+
+fn main() -> ExitCode {
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let result = match args[..] {
+        ["script", script_path, in_path, out_path] => script(script_path, in_path, out_path),
+        ["merge", in_path, other_path, out_path] => merge(in_path, other_path, out_path),
+        ["diff", before_path, after_path] => diff(before_path, after_path),
+        ["replay", journal_path, out_dir, ref in_paths @ ..] if !in_paths.is_empty() => replay(journal_path, out_dir, in_paths),
+        _ => Err(USAGE.into()),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        },
+    }
+
+}
+
+// runs a script over a mission, writing the edited mission out
+fn script(script_path: &str, in_path: &str, out_path: &str) -> Result<(), String> {
+
+    let source = fs::read_to_string(script_path).map_err(|e| format!("failed to read {script_path}: {e}"))?;
+    let buf = fs::read(in_path).map_err(|e| format!("failed to read {in_path}: {e}"))?;
+
+    let mut th = TeaHandler::from_buffer_lenient(buf).map_err(|e| chain(&e))?;
+    for warning in th.display_warnings() {
+        eprintln!("warning: {warning}");
+    }
+    for line in th.run_script(&source).map_err(|e| chain(&e))? {
+        println!("{line}");
+    }
+
+    let buf = th.serialize().map_err(|e| chain(&e))?;
+    fs::write(out_path, buf).map_err(|e| format!("failed to write {out_path}: {e}"))
+
+}
+
+// merges every object of another mission into a mission, reporting anything renamed
+fn merge(in_path: &str, other_path: &str, out_path: &str) -> Result<(), String> {
+
+    let buf = fs::read(in_path).map_err(|e| format!("failed to read {in_path}: {e}"))?;
+    let other = fs::read(other_path).map_err(|e| format!("failed to read {other_path}: {e}"))?;
+
+    let mut th = TeaHandler::from_buffer_lenient(buf).map_err(|e| chain(&e))?;
+    for warning in th.display_warnings() {
+        eprintln!("warning: {warning}");
+    }
+    for note in th.merge(&other).map_err(|e| chain(&e))? {
+        println!("{note}");
+    }
+
+    let buf = th.serialize().map_err(|e| chain(&e))?;
+    fs::write(out_path, buf).map_err(|e| format!("failed to write {out_path}: {e}"))
+
+}
+
+// prints what differs between two versions of a mission
+fn diff(before_path: &str, after_path: &str) -> Result<(), String> {
+
+    let before = fs::read(before_path).map_err(|e| format!("failed to read {before_path}: {e}"))?;
+    let after = fs::read(after_path).map_err(|e| format!("failed to read {after_path}: {e}"))?;
+
+    let th = TeaHandler::from_buffer_lenient(before).map_err(|e| chain(&e))?;
+    let diff = th.diff(&after).map_err(|e| chain(&e))?;
+    if diff.is_empty() {
+        println!("no differences");
+    } else {
+        print!("{diff}");
+    }
+    Ok(())
+
+}
+
+// applies an exported journal to each of many missions, writing each edited
+// mission into a directory under its own file name
+fn replay(journal_path: &str, out_dir: &str, in_paths: &[&str]) -> Result<(), String> {
+
+    let buf = fs::read(journal_path).map_err(|e| format!("failed to read {journal_path}: {e}"))?;
+    let journal = Journal::from_json(&buf).map_err(|e| chain(&e))?;
+
+    let mut missions = vec![];
+    for in_path in in_paths {
+        let buf = fs::read(in_path).map_err(|e| format!("failed to read {in_path}: {e}"))?;
+        missions.push((in_path.to_string(), buf));
+    }
+
+    let mut failed = 0;
+    for (in_path, edited) in apply_to_all(&journal, missions) {
+        let written = edited.map_err(|e| chain(&e)).and_then(|buf| {
+            let name = Path::new(&in_path).file_name().ok_or_else(|| format!("{in_path} is not a file"))?;
+            let out_path = Path::new(out_dir).join(name);
+            fs::write(&out_path, buf).map_err(|e| format!("failed to write {}: {e}", out_path.display()))
+        });
+        match written {
+            Ok(()) => println!("edited {in_path}"),
+            Err(e) => {
+                eprintln!("{in_path}: {e}");
+                failed += 1;
+            },
+        }
+    }
+
+    match failed {
+        0 => Ok(()),
+        _ => Err(format!("{failed} of {} missions could not be edited", in_paths.len())),
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use missioneditor2::tea::{Edit, Event, TeaHandler};
+    use missioneditor2::utils::{get_test_dir, zip_files};
+
+    #[test]
+    fn replay_writes_every_mission_it_could_edit() {
+        let dir = std::env::temp_dir().join(format!("missioneditor2-cli-replay-{}", std::process::id()));
+        let out_dir = dir.join("out");
+        fs::create_dir_all(&out_dir).unwrap();
+        let path = |name: &str| dir.join(name).to_str().unwrap().to_string();
+
+        let mut th = TeaHandler::from_buffer_lenient(zip_files(get_test_dir("testcase"))).unwrap();
+        let uuid = th.display_objects().into_iter().find(|(_, n)| n == "Wheeled Suitcase").unwrap().0;
+        th.event(Event::Edit(Edit::UpdateProperty { uuid, key: "Position X".into(), value: "1.0".into() }));
+        fs::write(path("journal.json"), th.journal().to_json().unwrap()).unwrap();
+
+        let renamed = get_test_dir("testcase").into_iter()
+            .map(|(name, buf)| match name.as_str() {
+                "Test.mission" => (name, String::from_utf8(buf).unwrap().replace("Wheeled Suitcase", "Suitcase").into_bytes()),
+                _ => (name, buf),
+            })
+            .collect();
+        fs::write(path("testcase.playmission"), zip_files(get_test_dir("testcase"))).unwrap();
+        fs::write(path("renamed.playmission"), zip_files(renamed)).unwrap();
+
+        let result = replay(&path("journal.json"), out_dir.to_str().unwrap(), &[&path("testcase.playmission"), &path("renamed.playmission")]);
+        assert_eq!(Err("1 of 2 missions could not be edited".to_string()), result);
+        assert!(!out_dir.join("renamed.playmission").exists());
+
+        let edited = TeaHandler::from_buffer_lenient(fs::read(out_dir.join("testcase.playmission")).unwrap()).unwrap();
+        let uuid = edited.display_objects().into_iter().find(|(_, n)| n == "Wheeled Suitcase").unwrap().0;
+        let x = edited.display_properties(uuid).unwrap().into_iter().find(|(k, _)| k == "Position X").unwrap().1;
+        assert_eq!("1.0", x.to_string());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,271 @@
+// copies objects out of one mission as text, to go through the system
+// clipboard and be pasted into another. the text is json holding each
+// object's markup, with every file they need zipped alongside
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{Cursor, Read, Write};
+
+use base64::prelude::*;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use uuid::Uuid;
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_futures::JsFuture;
+use zip::{write::FileOptions, ZipArchive, ZipWriter};
+
+use crate::merge::{self, name_in, Clash, Imported, Incoming, MergeError};
+use crate::playmission::{error::PlaymissionError, MissionObject, Object};
+
+// marks text as copied objects, rather than anything else on the clipboard
+const FORMAT: &str = "missioneditor2-clip";
+
+// rule properties naming the objects a rule connects
+const ENTITIES: [&str; 2] = ["Cause Entity", "Effect Entity"];
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Clip {
+    format: String,
+    mission: Option<String>,
+    objects: Vec<ClipObject>,
+    // base64 zip of every file below. any not owned by an object
+    // are mission files the objects need, i.e. defaults and meshes
+    archive: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct ClipObject {
+    markup: String,
+    files: Vec<String>,
+}
+
+// copies objects, along with any rules connecting only them and the mission
+pub fn copy(mission: &MissionObject, objects: &HashMap<Uuid, Object>, uuids: &[Uuid]) -> Result<String> {
+
+    let mut copied: Vec<&Object> = uuids.iter().filter_map(|uuid| objects.get(uuid)).collect();
+    let names: HashSet<String> = copied.iter().filter_map(|o| o.name()).collect();
+    let mission_name = name_in(mission.properties(), "Name");
+
+    let mut rules: Vec<&Object> = objects.values()
+        .filter(|o| o.r#type() == "RULE" && !uuids.contains(o.uuid()))
+        .filter(|o| {
+            let entities: Vec<String> = ENTITIES.iter().filter_map(|k| name_in(o.properties(), k)).collect();
+            entities.iter().any(|e| names.contains(e))
+                && entities.iter().all(|e| names.contains(e) || Some(e) == mission_name.as_ref())
+        })
+        .collect();
+    rules.sort_by_key(|o| (o.name(), *o.uuid()));
+    copied.extend(rules);
+
+    let mut files = BTreeMap::new();
+    let mut clip_objects = vec![];
+    for object in copied {
+        let (markup, owned) = object.to_fragment()?;
+        clip_objects.push(ClipObject { markup, files: owned.keys().cloned().collect() });
+        files.extend(owned);
+    }
+
+    let mut texts: Vec<String> = clip_objects.iter().map(|o| o.markup.clone()).collect();
+    texts.extend(files.values().filter_map(|buf| std::str::from_utf8(buf).ok().map(String::from)));
+    files.extend(needed_files(mission, texts));
+
+    let clip = Clip {
+        format: FORMAT.into(),
+        mission: mission_name,
+        objects: clip_objects,
+        archive: BASE64_STANDARD.encode(zip(files)?),
+    };
+    Ok(serde_json::to_string(&clip)?)
+
+}
+
+// gets the edits that paste copied objects into a mission. anything that
+// would clash is renamed as in a merge, except that shared files already
+// here are kept, since copies are usually pasted back into their mission
+pub fn paste(text: &str, mission: &MissionObject, objects: &HashMap<Uuid, Object>) -> Result<Imported> {
+
+    let clip: Clip = serde_json::from_str(text).ok()
+        .filter(|clip: &Clip| clip.format == FORMAT)
+        .ok_or(ClipboardError::NotAClip)?;
+    let mut archive = unzip(&BASE64_STANDARD.decode(&clip.archive)?)?;
+
+    let mut incoming_objects = vec![];
+    for clip_object in clip.objects {
+        let files = clip_object.files.iter()
+            .map(|k| Ok((k.clone(), archive.remove(k).ok_or_else(|| ClipboardError::MissingFile(k.clone()))?)))
+            .collect::<Result<_>>()?;
+        incoming_objects.push((clip_object.markup, files));
+    }
+
+    let incoming = Incoming { mission: clip.mission, objects: incoming_objects, files: archive };
+    Ok(merge::import(incoming, mission, objects, Clash::Keep)?)
+
+}
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = ["navigator", "clipboard"], js_name = writeText)]
+    fn write_text(text: &str) -> js_sys::Promise;
+    #[wasm_bindgen(js_namespace = ["navigator", "clipboard"], js_name = readText)]
+    fn read_text() -> js_sys::Promise;
+}
+
+// puts text on the system clipboard
+pub async fn write(text: &str) -> Result<()> {
+    JsFuture::from(write_text(text)).await.map_err(browser)?;
+    Ok(())
+}
+
+// gets text from the system clipboard. the browser may ask first
+pub async fn read() -> Result<String> {
+    let text = JsFuture::from(read_text()).await.map_err(browser)?;
+    text.as_string().ok_or(ClipboardError::NotAClip)
+}
+
+// describe an error thrown by the browser
+fn browser(e: JsValue) -> ClipboardError {
+    let message = e.dyn_ref::<js_sys::Error>()
+        .map(|e| String::from(e.message()))
+        .or_else(|| e.as_string())
+        .unwrap_or_else(|| format!("{e:?}"));
+    ClipboardError::Browser(message)
+}
+
+// finds mission files named in texts, then in those files, and so on, i.e.
+// a mesh named in a datafile and the textures named in the mesh. defaults
+// are always needed, but never named
+fn needed_files(mission: &MissionObject, mut texts: Vec<String>) -> BTreeMap<String, Vec<u8>> {
+
+    let mut needed: BTreeMap<String, Vec<u8>> = mission.files().iter()
+        .filter(|(k, _)| k.starts_with("Default."))
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+
+    while let Some(text) = texts.pop() {
+        for (k, v) in mission.files() {
+            if needed.contains_key(k) || !text.contains(k.as_str()) { continue }
+            if let Ok(s) = std::str::from_utf8(v) {
+                texts.push(s.into());
+            }
+            needed.insert(k.clone(), v.clone());
+        }
+    }
+    needed
+
+}
+
+fn zip(files: BTreeMap<String, Vec<u8>>) -> Result<Vec<u8>> {
+    let mut zip = ZipWriter::new(Cursor::new(vec![]));
+    for (name, buf) in files {
+        zip.start_file(name, FileOptions::default())?;
+        zip.write_all(&buf)?;
+    }
+    Ok(zip.finish()?.into_inner())
+}
+
+fn unzip(buf: &[u8]) -> Result<HashMap<String, Vec<u8>>> {
+    let mut zip = ZipArchive::new(Cursor::new(buf))?;
+    let mut files = HashMap::new();
+    for i in 0..zip.len() {
+        let mut file = zip.by_index(i)?;
+        let mut buf = vec![];
+        file.read_to_end(&mut buf)?;
+        files.insert(file.name().to_string(), buf);
+    }
+    Ok(files)
+}
+
+pub type Result<T> = std::result::Result<T, ClipboardError>;
+
+#[derive(Debug, Error)]
+pub enum ClipboardError {
+    #[error("clipboard doesn't hold copied objects")]
+    NotAClip,
+    #[error("clipboard failed: {0}")]
+    Browser(String),
+    #[error("copied objects are missing file {0}")]
+    MissingFile(String),
+    #[error("failed decoding copied files")]
+    Base64 {
+        #[from]
+        source: base64::DecodeError,
+    },
+    #[error("failed reading or writing copied files")]
+    Io {
+        #[from]
+        source: std::io::Error,
+    },
+    #[error("failed reading or writing copied files")]
+    Zip {
+        #[from]
+        source: zip::result::ZipError,
+    },
+    #[error("failed reading or writing copied objects")]
+    Json {
+        #[from]
+        source: serde_json::Error,
+    },
+    #[error("failed adding copied objects")]
+    Merge {
+        #[from]
+        source: MergeError,
+    },
+    #[error("playmission error")]
+    Playmission {
+        #[from]
+        source: PlaymissionError,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tea::{Event, TeaHandler};
+    use crate::utils::{testcase, uuid_of};
+
+    fn value(th: &TeaHandler, name: &str, k: &str) -> String {
+        th.display_properties(uuid_of(th, name)).unwrap().into_iter().find(|(n, _)| n == k).unwrap().1.to_string()
+    }
+
+    #[test]
+    fn copy_takes_connecting_rules_and_needed_files() {
+        let th = testcase();
+        let clip: Clip = serde_json::from_str(&th.copy(&[uuid_of(&th, "Television")]).unwrap()).unwrap();
+        assert_eq!(2, clip.objects.len());
+        assert!(clip.objects[1].markup.contains("Rule 1"));
+
+        let archive = unzip(&BASE64_STANDARD.decode(&clip.archive).unwrap()).unwrap();
+        for file in ["etelevision.aprop", "Default.aprop", "ETelevision.obj"] {
+            assert!(archive.contains_key(file), "{file} not copied");
+        }
+    }
+
+    #[test]
+    fn paste_renames_clashes() {
+        let mut th = testcase();
+        let before = th.display_objects().len();
+        let text = th.copy(&[uuid_of(&th, "Television"), uuid_of(&th, "Default Game Over Screen")]).unwrap();
+
+        th.event(Event::Paste { text });
+        assert_eq!(before + 4, th.display_objects().len());
+        assert_eq!("Television 2", value(&th, "Rule 2", "Effect Entity"));
+        assert_eq!("Default Game Over Screen 2", value(&th, "Default Game Over Rule 2", "Effect Entity"));
+        assert_eq!("My Game", value(&th, "Default Game Over Rule 2", "Cause Entity"));
+        assert_eq!("Industrial 2.tga", value(&th, "Default Game Over Screen 2", "Filename"));
+        assert_eq!(vec!["Industrial 2.tga".to_string()], th.display_files(uuid_of(&th, "Default Game Over Screen 2")).unwrap());
+
+        // and the pasted copies survive a save
+        let saved = TeaHandler::from_buffer_lenient(th.serialize().unwrap()).unwrap();
+        assert_eq!(th.display_warnings().len(), saved.display_warnings().len());
+        assert_eq!("Television 2", value(&saved, "Rule 2", "Effect Entity"));
+
+        th.event(Event::Undo);
+        assert_eq!(before, th.display_objects().len());
+    }
+
+    #[test]
+    fn paste_rejects_other_text() {
+        let mut th = testcase();
+        th.event(Event::Paste { text: "Wheeled Suitcase".into() });
+        assert_eq!(Some("failed to paste: clipboard doesn't hold copied objects"), th.display_status());
+    }
+}
@@ -0,0 +1,111 @@
+use crate::three::OrbitControls;
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+
+// shift-drag over the viewport to select everything inside a rectangle.
+// holding ctrl as well adds to the selection instead of replacing it
+pub struct BoxSelect {
+    state: Rc<RefCell<BoxState>>,
+}
+
+// finished box, in normalized device coordinates
+pub struct Area {
+    pub from: (f64, f64),
+    pub to: (f64, f64),
+    pub additive: bool,
+}
+
+struct BoxState {
+    start: Option<(i32, i32)>,
+    finished: Option<Area>,
+    overlay: web_sys::HtmlElement,
+    inside: web_sys::HtmlCanvasElement,
+    controls: OrbitControls,
+}
+
+impl BoxSelect {
+
+    pub fn new(inside: web_sys::HtmlCanvasElement, controls: OrbitControls) -> Self {
+
+        let document = web_sys::window().unwrap().document().unwrap();
+        let overlay: web_sys::HtmlElement = document.create_element("div").unwrap().dyn_into().unwrap();
+        overlay.set_class_name("box-select");
+        overlay.style().set_property("display", "none").unwrap();
+        document.body().unwrap().append_child(&overlay).unwrap();
+
+        let state = Rc::new(RefCell::new(BoxState { start: None, finished: None, overlay, inside: inside.clone(), controls }));
+
+        // captured before orbitcontrols sees it, so it can be switched off mid-drag
+        let s = Rc::clone(&state);
+        let down = Closure::<dyn FnMut(_)>::new(move |e: web_sys::PointerEvent| {
+            if e.shift_key() { s.borrow_mut().begin(&e) }
+        });
+        inside.add_event_listener_with_callback_and_bool("pointerdown", down.as_ref().unchecked_ref(), true).unwrap();
+        down.forget();
+
+        let s = Rc::clone(&state);
+        let moved = Closure::<dyn FnMut(_)>::new(move |e: web_sys::PointerEvent| {
+            s.borrow().drag(&e)
+        });
+        web_sys::window().unwrap().add_event_listener_with_callback("pointermove", moved.as_ref().unchecked_ref()).unwrap();
+        moved.forget();
+
+        let s = Rc::clone(&state);
+        let up = Closure::<dyn FnMut(_)>::new(move |e: web_sys::PointerEvent| {
+            s.borrow_mut().end(&e)
+        });
+        web_sys::window().unwrap().add_event_listener_with_callback("pointerup", up.as_ref().unchecked_ref()).unwrap();
+        up.forget();
+
+        Self { state }
+
+    }
+
+    // takes the last finished box, if there is one
+    pub fn take(&self) -> Option<Area> {
+        self.state.borrow_mut().finished.take()
+    }
+
+}
+
+impl BoxState {
+
+    fn begin(&mut self, e: &web_sys::PointerEvent) {
+        self.start = Some((e.client_x(), e.client_y()));
+        self.controls.set_enabled(false);
+        self.drag(e);
+        self.overlay.style().set_property("display", "block").unwrap();
+    }
+
+    fn drag(&self, e: &web_sys::PointerEvent) {
+        let Some((x, y)) = self.start else { return };
+        let style = self.overlay.style();
+        style.set_property("left", &format!("{}px", x.min(e.client_x()))).unwrap();
+        style.set_property("top", &format!("{}px", y.min(e.client_y()))).unwrap();
+        style.set_property("width", &format!("{}px", (x - e.client_x()).abs())).unwrap();
+        style.set_property("height", &format!("{}px", (y - e.client_y()).abs())).unwrap();
+    }
+
+    fn end(&mut self, e: &web_sys::PointerEvent) {
+        let Some(start) = self.start.take() else { return };
+        self.overlay.style().set_property("display", "none").unwrap();
+        self.controls.set_enabled(true);
+        self.finished = Some(Area {
+            from: self.to_device(start),
+            to: self.to_device((e.client_x(), e.client_y())),
+            additive: e.ctrl_key() || e.meta_key(),
+        });
+    }
+
+    // converts client coordinates to normalized device coordinates
+    fn to_device(&self, (x, y): (i32, i32)) -> (f64, f64) {
+        let rect = self.inside.get_bounding_client_rect();
+        (
+            (f64::from(x) - rect.left()) / rect.width() * 2.0 - 1.0,
+            (f64::from(y) - rect.top()) / rect.height() * (-2.0) + 1.0,
+        )
+    }
+
+}
@@ -0,0 +1,123 @@
+use dioxus::prelude::*;
+
+use crate::components::{File, FilePicker};
+use crate::diff::{Diff, FileChange, ValueChange};
+use crate::playmission::error::chain;
+use crate::tea::TeaHandler;
+
+// compares the mission against another version of it, i.e. a teammate's,
+// with this mission on the left and the other on the right
+#[component]
+pub fn DiffPanel() -> Element {
+
+    let tea = use_context::<Signal<Option<TeaHandler>>>();
+    let mut import = use_signal(|| File::None);
+    let mut compared: Signal<Option<Result<(String, Diff), String>>> = use_signal(|| None);
+
+    if matches!(*import.read(), File::Loaded{..}) {
+        let File::Loaded { name, data } = import.replace(File::None) else { unreachable!() };
+        let diff = tea.read().as_ref().map(|tea| tea.diff(&data).map(|diff| (name, diff)).map_err(|e| chain(&e)));
+        compared.set(diff);
+    }
+
+    rsx! {
+        div {
+            p {
+                class: "link",
+                "compare with"
+            }
+            FilePicker { signal: import }
+            match &*compared.read() {
+                Some(Ok((name, diff))) => rsx! {
+                    div {
+                        class: "panel",
+                        table {
+                            class: "w-full table-fixed",
+                            tr {
+                                th { "" }
+                                th { "this mission" }
+                                th { "{name}" }
+                            }
+                            for change in diff.mission.iter() {
+                                ValueRow { change: change.clone() }
+                            }
+                            for (file, change) in diff.files.iter() {
+                                FileRow { name: file.clone(), change: *change }
+                            }
+                            for key in diff.removed.iter() {
+                                tr {
+                                    td { "{key.r#type}" }
+                                    td { "{key.name}" }
+                                    td { "-" }
+                                }
+                            }
+                            for key in diff.added.iter() {
+                                tr {
+                                    td { "{key.r#type}" }
+                                    td { "-" }
+                                    td { "{key.name}" }
+                                }
+                            }
+                            for changed in diff.changed.iter() {
+                                tr {
+                                    td {
+                                        class: "group-heading",
+                                        colspan: 3,
+                                        "{changed.object}"
+                                    }
+                                }
+                                for change in changed.properties.iter().chain(changed.datafile.iter()) {
+                                    ValueRow { change: change.clone() }
+                                }
+                                for (file, change) in changed.files.iter() {
+                                    FileRow { name: file.clone(), change: *change }
+                                }
+                            }
+                        }
+                        if diff.is_empty() {
+                            p {
+                                "no differences"
+                            }
+                        }
+                    }
+                },
+                Some(Err(e)) => rsx! {
+                    p {
+                        "{e}"
+                    }
+                },
+                None => rsx! {},
+            }
+        }
+    }
+
+}
+
+// a value on either side
+#[component]
+fn ValueRow(change: ValueChange) -> Element {
+    rsx! {
+        tr {
+            td { "{change.key}" }
+            td { "{change.before.clone().unwrap_or(\"-\".into())}" }
+            td { "{change.after.clone().unwrap_or(\"-\".into())}" }
+        }
+    }
+}
+
+// a file on either side
+#[component]
+fn FileRow(name: String, change: FileChange) -> Element {
+    let (before, after) = match change {
+        FileChange::Added => ("-", "added"),
+        FileChange::Removed => ("present", "-"),
+        FileChange::Changed => ("present", "changed"),
+    };
+    rsx! {
+        tr {
+            td { "{name}" }
+            td { "{before}" }
+            td { "{after}" }
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use crate::playmission::Face;
+use crate::tea::{Edit, Event, TeaHandler};
+use crate::three::{Camera, Object3D, Raycaster, Scene, Vector2, Vector3};
+use crate::volumes::{across, along, Volume};
+use super::gizmo::same_key;
+use dioxus::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+use uuid::Uuid;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+
+// resize triggers and effects by dragging the handles on their faces, which
+// show once the object is selected. like the gizmo, each change is sent as
+// it happens, and the whole drag is undone as a single step
+pub struct FaceDrag {
+    raycaster: Raycaster,
+    state: Rc<RefCell<DragState>>,
+    // the object whose handles are showing
+    shown: Option<Object3D>,
+    // the object, face and volume as it was when the drag began
+    dragging: Option<(Uuid, Face, Volume)>,
+    // edits already sent during this drag, so unchanged ones aren't sent again
+    sent: Vec<Edit>,
+}
+
+#[derive(Default)]
+struct DragState {
+    // the face under the pointer, which a press starts dragging
+    hovered: Option<Face>,
+    began: Option<Face>,
+    held: bool,
+    ended: bool,
+}
+
+impl FaceDrag {
+
+    pub fn new() -> Self {
+
+        let state = Rc::new(RefCell::new(DragState::default()));
+        let window = web_sys::window().unwrap();
+
+        // caught on the way down, so that presses on handles never reach
+        // the orbit controls or the gizmo
+        let s = Rc::clone(&state);
+        let pressed = Closure::<dyn FnMut(_)>::new(move |e: web_sys::PointerEvent| {
+            let mut s = s.borrow_mut();
+            let Some(face) = s.hovered.filter(|_| e.button() == 0) else { return };
+            e.stop_propagation();
+            e.prevent_default();
+            s.began = Some(face);
+            s.held = true;
+        });
+        window.add_event_listener_with_callback_and_bool("pointerdown", pressed.as_ref().unchecked_ref(), true).unwrap();
+        pressed.forget();
+
+        let s = Rc::clone(&state);
+        let released = Closure::<dyn FnMut(_)>::new(move |_: web_sys::PointerEvent| {
+            let mut s = s.borrow_mut();
+            if s.held {
+                s.held = false;
+                s.ended = true;
+            }
+        });
+        window.add_event_listener_with_callback("pointerup", released.as_ref().unchecked_ref()).unwrap();
+        released.forget();
+
+        Self { raycaster: Raycaster::new(), state, shown: None, dragging: None, sent: vec![] }
+
+    }
+
+    // shows handles on the selected object and sends whatever the current
+    // drag has changed. returns whether a drag just ended, as letting go
+    // also clicks on whatever is under the pointer
+    pub fn update(&mut self, scene: &Scene, camera: &Camera, selected: Option<Uuid>, pointer: Option<(f64, f64)>, mut tea: Signal<Option<TeaHandler>>) -> bool {
+
+        let object = selected.and_then(|uuid| scene.get_object_by_name(&uuid.to_string()));
+        self.show(object);
+
+        let (began, held, ended) = {
+            let mut s = self.state.borrow_mut();
+            (s.began.take(), s.held, std::mem::take(&mut s.ended))
+        };
+        if let (Some(face), Some(uuid)) = (began, selected) {
+            let volume = tea.read().as_ref().and_then(|tea| tea.display_volume(uuid));
+            self.dragging = volume.map(|volume| (uuid, face, volume));
+            self.sent = vec![];
+            if self.dragging.is_some() {
+                tea.write().iter_mut().next().unwrap().begin();
+            }
+        }
+
+        let edits = match pointer {
+            Some(pointer) if held || ended => self.edits(&tea.read(), camera, pointer),
+            _ => vec![],
+        };
+        if !edits.is_empty() || (ended && self.dragging.is_some()) {
+            let mut tea = tea.write();
+            let Some(tea) = tea.as_mut() else { return ended };
+            for edit in edits {
+                tea.event(Event::Edit(edit));
+            }
+            if ended {
+                tea.commit();
+            }
+        }
+
+        let dragged = ended && self.dragging.take().is_some();
+        let hovered = if self.dragging.is_none() { pointer.and_then(|pointer| self.hover(camera, pointer)) } else { None };
+        self.state.borrow_mut().hovered = hovered;
+        dragged
+
+    }
+
+    // shows the handles of one object, hiding any others
+    fn show(&mut self, object: Option<Object3D>) {
+
+        let same = match (&object, &self.shown) {
+            (Some(a), Some(b)) => JsValue::from(a) == JsValue::from(b),
+            (a, b) => a.is_none() && b.is_none(),
+        };
+        if same { return }
+
+        for (shown, visible) in [(self.shown.take(), false), (object.clone(), true)] {
+            for handle in shown.iter().flat_map(handles) {
+                handle.set_visible(visible);
+            }
+        }
+        self.shown = object;
+
+    }
+
+    // get the face whose handle is under the pointer
+    fn hover(&self, camera: &Camera, (x, y): (f64, f64)) -> Option<Face> {
+        let handles = self.shown.iter().flat_map(handles).map(JsValue::from).collect();
+        self.raycaster.set_from_camera(&Vector2::new(x, y), camera);
+        let intersected = self.raycaster.intersect_objects(handles, false);
+        let hit = js_sys::Reflect::get(intersected.first()?, &JsValue::from_str("object")).ok()?;
+        Face::from_name(&hit.dyn_ref::<Object3D>()?.name())
+    }
+
+    // get edits for the values the drag has moved since they were last sent
+    fn edits(&mut self, tea: &Option<TeaHandler>, camera: &Camera, (x, y): (f64, f64)) -> Vec<Edit> {
+
+        let (Some((uuid, face, volume)), Some(tea)) = (self.dragging, tea.as_ref()) else { return vec![] };
+
+        // a ray from the camera through the pointer
+        let near = Vector3::new(x as f32, y as f32, -1.0).unproject(camera);
+        let far = Vector3::new(x as f32, y as f32, 1.0).unproject(camera);
+        let origin = [near.x(), near.y(), near.z()];
+        let direction = [far.x() - near.x(), far.y() - near.y(), far.z() - near.z()];
+
+        let centre = volume.centre();
+        let distance = match volume.normal(face) {
+            Some(normal) => along(centre, normal, origin, direction),
+            None => across(centre, origin, direction),
+        };
+        let Some(resized) = distance.and_then(|distance| tea.display_resized(uuid, &volume.resized(face, distance), face)) else { return vec![] };
+
+        let properties = resized.properties.into_iter()
+            .map(|(key, value)| Edit::UpdateProperty { uuid, key: key.into(), value });
+        let datafile = resized.datafile.into_iter()
+            .map(|(key, value)| Edit::UpdateDatafile { uuid, key: key.into(), value });
+        let mut edits = vec![];
+        for edit in properties.chain(datafile) {
+            if self.sent.contains(&edit) { continue }
+            self.sent.retain(|sent| !same_key(sent, &edit));
+            self.sent.push(edit.clone());
+            edits.push(edit);
+        }
+        edits
+
+    }
+
+}
+
+impl Default for FaceDrag {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// get the face handles of an object
+fn handles(object: &Object3D) -> Vec<Object3D> {
+    object.children()
+        .into_iter()
+        .filter_map(|child| child.dyn_into::<Object3D>().ok())
+        .filter(|child| Face::from_name(&child.name()).is_some())
+        .collect()
+}
@@ -0,0 +1,132 @@
+use dioxus::prelude::*;
+
+use crate::playmission::error::chain;
+use crate::replace::Replace;
+use crate::tea::{Event as TeaEvent, TeaHandler};
+
+// find and replace over the whole mission, previewing every value
+// that would change before replacing them all as one step
+#[component]
+pub fn FindReplace() -> Element {
+
+    let mut tea = use_context::<Signal<Option<TeaHandler>>>();
+    let mut replace = use_signal(Replace::default);
+    let mut previewing = use_signal(|| false);
+
+    let preview_closure = move |_| previewing.set(true);
+    let replace_closure = move |_| {
+        let replace = replace.read().clone();
+        tea.write().iter_mut().next().unwrap().event(TeaEvent::Replace { replace });
+        previewing.set(false);
+    };
+
+    // scopes are left empty for everything
+    let scope = |s: String| Some(s).filter(|s| !s.trim().is_empty());
+
+    let preview = if *previewing.read() {
+        tea.read().as_ref().map(|tea| tea.display_replacements(&replace.read()).map_err(|e| chain(&e)))
+    } else {
+        None
+    };
+
+    rsx! {
+        div {
+            input {
+                class: "text-field w-full",
+                placeholder: "find",
+                value: "{replace.read().find}",
+                oninput: move |e| { replace.write().find = e.value(); previewing.set(false) },
+            }
+            input {
+                class: "text-field w-full",
+                placeholder: "replace with",
+                value: "{replace.read().with}",
+                oninput: move |e| { replace.write().with = e.value(); previewing.set(false) },
+            }
+            input {
+                class: "text-field w-full",
+                placeholder: "only in key, i.e. Effect Method Parameter",
+                value: "{replace.read().key.clone().unwrap_or_default()}",
+                oninput: move |e| { replace.write().key = scope(e.value()); previewing.set(false) },
+            }
+            input {
+                class: "text-field w-full",
+                placeholder: "only in type, i.e. RULE",
+                value: "{replace.read().r#type.clone().unwrap_or_default()}",
+                oninput: move |e| { replace.write().r#type = scope(e.value()); previewing.set(false) },
+            }
+            input {
+                class: "text-field",
+                r#type: "checkbox",
+                name: "replace-case",
+                checked: replace.read().case_sensitive,
+                onchange: move |e| { replace.write().case_sensitive = e.value() == "true"; previewing.set(false) },
+            }
+            label {
+                class: "link",
+                r#for: "replace-case",
+                "case"
+            }
+            input {
+                class: "text-field",
+                r#type: "checkbox",
+                name: "replace-word",
+                checked: replace.read().whole_word,
+                onchange: move |e| { replace.write().whole_word = e.value() == "true"; previewing.set(false) },
+            }
+            label {
+                class: "link",
+                r#for: "replace-word",
+                "word"
+            }
+            input {
+                class: "text-field",
+                r#type: "checkbox",
+                name: "replace-regex",
+                checked: replace.read().regex,
+                onchange: move |e| { replace.write().regex = e.value() == "true"; previewing.set(false) },
+            }
+            label {
+                class: "link",
+                r#for: "replace-regex",
+                "regex"
+            }
+            br {}
+            a {
+                class: "link",
+                onclick: preview_closure,
+                "preview"
+            }
+            match preview {
+                Some(Ok(replacements)) => rsx! {
+                    div {
+                        class: "panel",
+                        for replacement in replacements.iter() {
+                            p {
+                                "{replacement.name}: {replacement.key}: {replacement.before} -> {replacement.after}"
+                            }
+                        }
+                        if replacements.is_empty() {
+                            p {
+                                "no matches"
+                            }
+                        } else {
+                            a {
+                                class: "link",
+                                onclick: replace_closure,
+                                "replace {replacements.len()}"
+                            }
+                        }
+                    }
+                },
+                Some(Err(e)) => rsx! {
+                    p {
+                        "{e}"
+                    }
+                },
+                None => rsx! {},
+            }
+        }
+    }
+
+}
@@ -0,0 +1,131 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use uuid::Uuid;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+
+use crate::three::{Box3, Camera, OrbitControls, OrthographicCamera, Scene, Vector3};
+use super::gizmo::typing;
+
+// press f to swing the camera round to the selected objects, moving what it
+// orbits to their middle and backing off until they all fit in view. on the
+// map, where backing off changes nothing, it zooms instead
+pub struct Framing {
+    requested: Rc<Cell<bool>>,
+    animation: Option<Animation>,
+}
+
+// a move of the orbit target and the camera's distance from it
+struct Animation {
+    start: f64,
+    from: [f32; 3],
+    to: [f32; 3],
+    from_distance: f32,
+    to_distance: f32,
+    // from the target towards the camera, kept for the whole move
+    direction: [f32; 3],
+    // from and to, for an orthographic camera
+    zoom: Option<(f64, f64)>,
+}
+
+impl Framing {
+
+    const DURATION: f64 = 400.0;
+    // matches the viewport's perspective camera
+    const FOV: f32 = 75.0;
+
+    pub fn new() -> Self {
+
+        let requested = Rc::new(Cell::new(false));
+        let r = Rc::clone(&requested);
+        let keydown = Closure::<dyn FnMut(_)>::new(move |e: web_sys::KeyboardEvent| {
+            if !typing(&e) && e.key() == "f" { r.set(true) }
+        });
+        web_sys::window().unwrap().add_event_listener_with_callback("keydown", keydown.as_ref().unchecked_ref()).unwrap();
+        keydown.forget();
+
+        Self { requested, animation: None }
+
+    }
+
+    // starts framing the selection if asked to, and moves the camera along
+    // if it is framing. call before updating the orbit controls
+    pub fn update(&mut self, scene: &Scene, selected: &[Uuid], camera: &Camera, orbit: &OrbitControls) {
+
+        if self.requested.replace(false) {
+            self.animation = self.begin(scene, selected, camera, orbit).or(self.animation.take());
+        }
+        let Some(animation) = &self.animation else { return };
+
+        let t = ((js_sys::Date::now() - animation.start) / Self::DURATION).min(1.0) as f32;
+        let eased = t * (2.0 - t);
+        let lerp = |a: f32, b: f32| a + (b - a) * eased;
+        let target = [0, 1, 2].map(|i| lerp(animation.from[i], animation.to[i]));
+        let distance = lerp(animation.from_distance, animation.to_distance);
+
+        orbit.target().set(target[0], target[1], target[2]);
+        let [dx, dy, dz] = animation.direction.map(|d| d * distance);
+        camera.position().set(target[0] + dx, target[1] + dy, target[2] + dz);
+        if let (Some((from, to)), Some(ortho)) = (animation.zoom, camera.dyn_ref::<OrthographicCamera>()) {
+            ortho.set_zoom(from + (to - from) * eased as f64);
+            ortho.update_projection_matrix();
+        }
+
+        if t >= 1.0 {
+            self.animation = None;
+        }
+
+    }
+
+    // plans a move to fit the selected objects in view, if any are shown
+    fn begin(&self, scene: &Scene, selected: &[Uuid], camera: &Camera, orbit: &OrbitControls) -> Option<Animation> {
+
+        let bounds = Box3::new();
+        for uuid in selected {
+            if let Some(object) = scene.get_object_by_name(&uuid.to_string()) {
+                bounds.expand_by_object(&object);
+            }
+        }
+        if bounds.is_empty() { return None }
+        let centre = bounds.get_center(&Vector3::new(0.0, 0.0, 0.0));
+        let size = bounds.get_size(&Vector3::new(0.0, 0.0, 0.0));
+
+        let (target, position) = (orbit.target(), camera.position());
+        let from = [target.x(), target.y(), target.z()];
+        let offset = [position.x() - from[0], position.y() - from[1], position.z() - from[2]];
+        let from_distance = offset.iter().map(|o| o * o).sum::<f32>().sqrt().max(f32::EPSILON);
+
+        // far enough back for a sphere around the bounds to fit the view, or
+        // zoomed in until the bounds fit the map as seen from above
+        let (to_distance, zoom) = match camera.dyn_ref::<OrthographicCamera>() {
+            Some(ortho) => {
+                let half = |s: f32| (s as f64 / 2.0).max(1.0) * 1.2;
+                let to = (ortho.top() / half(size.z())).min(ortho.right() / half(size.x()));
+                (from_distance, Some((ortho.zoom(), to)))
+            },
+            None => {
+                let radius = (size.x() * size.x() + size.y() * size.y() + size.z() * size.z()).sqrt() / 2.0;
+                ((radius / (Self::FOV.to_radians() / 2.0).sin()).max(2.0), None)
+            },
+        };
+
+        Some(Animation {
+            start: js_sys::Date::now(),
+            from,
+            to: [centre.x(), centre.y(), centre.z()],
+            from_distance,
+            to_distance,
+            direction: offset.map(|o| o / from_distance),
+            zoom,
+        })
+
+    }
+
+}
+
+impl Default for Framing {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,172 @@
+use crate::placement::{Placement, Snap};
+use crate::tea::{Edit, Event, TeaHandler};
+use crate::three::{Camera, Object3D, OrbitControls, Scene, TransformControls};
+use dioxus::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+use uuid::Uuid;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+
+// drag the selected object about with the three.js transform gizmo. w moves
+// and e rotates, as in the three.js examples. every property a drag changes
+// is sent as it changes, snapped as set, and the whole drag is undone as a single step
+pub struct Gizmo {
+    controls: TransformControls,
+    state: Rc<RefCell<DragState>>,
+    attached: Option<Uuid>,
+    // edits already sent during this drag, so unchanged ones aren't sent again
+    sent: Vec<Edit>,
+}
+
+#[derive(Default)]
+struct DragState {
+    began: bool,
+    ended: bool,
+}
+
+impl Gizmo {
+
+    pub fn new(camera: &Camera, inside: web_sys::HtmlCanvasElement, scene: &Scene, orbit: OrbitControls) -> Self {
+
+        let controls = TransformControls::new(camera, &inside);
+        scene.dyn_ref::<Object3D>().unwrap().add(controls.unchecked_ref());
+        let state = Rc::new(RefCell::new(DragState::default()));
+
+        // orbiting is switched off mid-drag, or both would move at once
+        let s = Rc::clone(&state);
+        let changed = Closure::<dyn FnMut(_)>::new(move |e: JsValue| {
+            let dragging = js_sys::Reflect::get(&e, &JsValue::from_str("value")).ok().and_then(|v| v.as_bool()).unwrap_or(false);
+            orbit.set_enabled(!dragging);
+            let mut s = s.borrow_mut();
+            if dragging { s.began = true } else { s.ended = true }
+        });
+        controls.add_event_listener("dragging-changed", changed.as_ref().unchecked_ref());
+        changed.forget();
+
+        let c = controls.clone();
+        let keydown = Closure::<dyn FnMut(_)>::new(move |e: web_sys::KeyboardEvent| {
+            if typing(&e) { return }
+            match e.key().as_str() {
+                "w" => c.set_mode("translate"),
+                "e" => c.set_mode("rotate"),
+                _ => {},
+            }
+        });
+        web_sys::window().unwrap().add_event_listener_with_callback("keydown", keydown.as_ref().unchecked_ref()).unwrap();
+        keydown.forget();
+
+        Self { controls, state, attached: None, sent: vec![] }
+
+    }
+
+    // keeps the gizmo on the selected object, if exactly one is selected
+    pub fn attach(&mut self, scene: &Scene, selected: Option<Uuid>) {
+
+        if self.controls.dragging() { return }
+        let rendered = selected.and_then(|uuid| Some((uuid, scene.get_object_by_name(&uuid.to_string())?)));
+        match rendered {
+            Some((uuid, object)) if self.attached != Some(uuid) => {
+                self.controls.attach(&object);
+                self.attached = Some(uuid);
+            },
+            None if self.attached.is_some() => {
+                self.controls.detach();
+                self.attached = None;
+            },
+            _ => {},
+        }
+
+    }
+
+    // moves the gizmo to another camera. looking straight down, there is
+    // no height to drag things along
+    pub fn set_camera(&self, camera: &Camera, top_down: bool) {
+        self.controls.set_camera(camera);
+        self.controls.set_show_y(!top_down);
+    }
+
+    // sends whatever the current drag has changed. returns whether a drag just
+    // ended, as letting go also clicks on whatever is under the pointer
+    pub fn update(&mut self, mut tea: Signal<Option<TeaHandler>>, snap: &Snap) -> bool {
+
+        let DragState { began, ended } = std::mem::take(&mut *self.state.borrow_mut());
+        if began {
+            self.sent = self.values(&tea.read(), snap);
+        }
+        let edits = if self.controls.dragging() || ended { self.edits(&tea.read(), snap) } else { vec![] };
+        if !began && !ended && edits.is_empty() {
+            return false
+        }
+
+        let mut tea = tea.write();
+        let Some(tea) = tea.as_mut() else { return ended };
+        if began {
+            tea.begin();
+        }
+        for edit in edits {
+            tea.event(Event::Edit(edit));
+        }
+        if ended {
+            tea.commit();
+        }
+        ended
+
+    }
+
+    // get edits for the values that have moved since they were last sent
+    fn edits(&mut self, tea: &Option<TeaHandler>, snap: &Snap) -> Vec<Edit> {
+
+        let mut edits = vec![];
+        for edit in self.values(tea, snap) {
+            if self.sent.contains(&edit) { continue }
+            self.sent.retain(|sent| !same_key(sent, &edit));
+            self.sent.push(edit.clone());
+            edits.push(edit);
+        }
+        edits
+
+    }
+
+    // get the position and orientation of the attached object as edits to its
+    // properties, and to any mission values moved along with it, after
+    // snapping, moving the object to wherever it snapped to
+    fn values(&self, tea: &Option<TeaHandler>, snap: &Snap) -> Vec<Edit> {
+
+        let (Some(uuid), Some(object), Some(tea)) = (self.attached, self.controls.object(), tea.as_ref()) else { return vec![] };
+        let (position, quaternion) = (object.position(), object.quaternion());
+        let placement = Placement {
+            position: [position.x(), position.y(), position.z()],
+            orientation: Some([quaternion.w(), quaternion.x(), quaternion.y(), quaternion.z()]),
+        };
+        let Some(placed) = tea.display_placement(uuid, placement, snap) else { return vec![] };
+
+        let [x, y, z] = placed.placement.position;
+        position.set(x, y, z);
+        if let Some([w, x, y, z]) = placed.placement.orientation {
+            quaternion.set(x, y, z, w);
+        }
+        let properties = placed.values.into_iter().map(|(key, value)| Edit::UpdateProperty { uuid, key: key.into(), value });
+        let mission = placed.mission.into_iter().map(|(key, value)| Edit::UpdateMission { key: key.into(), value });
+        properties.chain(mission).collect()
+
+    }
+
+}
+
+// whether keys are going into a text field, rather than the viewport
+pub(super) fn typing(e: &web_sys::KeyboardEvent) -> bool {
+    e.target()
+        .and_then(|t| t.dyn_into::<web_sys::HtmlElement>().ok())
+        .is_some_and(|t| matches!(t.tag_name().as_str(), "INPUT" | "TEXTAREA") || t.is_content_editable())
+}
+
+// whether two edits change the same value
+pub(super) fn same_key(a: &Edit, b: &Edit) -> bool {
+    match (a, b) {
+        (Edit::UpdateProperty { key: a, .. }, Edit::UpdateProperty { key: b, .. }) => a == b,
+        (Edit::UpdateDatafile { key: a, .. }, Edit::UpdateDatafile { key: b, .. }) => a == b,
+        (Edit::UpdateMission { key: a, .. }, Edit::UpdateMission { key: b, .. }) => a == b,
+        _ => false,
+    }
+}
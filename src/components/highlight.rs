@@ -0,0 +1,81 @@
+use uuid::Uuid;
+use wasm_bindgen::{JsCast, JsValue};
+
+use crate::three::{BoxGeometry, Mesh, MeshBasicMaterial, Object3D, Scene, Vector3};
+
+// outlines objects in the viewport with a wireframe box, i.e. search matches.
+// outlines are children of the objects, so they follow them around and are
+// scaled along with them
+pub struct Highlight {
+    color: (f32, f32, f32),
+    scale: f32,
+    outlines: Vec<(Object3D, Mesh)>,
+}
+
+impl Highlight {
+
+    pub fn new(r: f32, g: f32, b: f32) -> Self {
+        Self { color: (r, g, b), scale: 1.2, outlines: vec![] }
+    }
+
+    // sets how much bigger than their objects outlines are, i.e. so several
+    // highlights can be seen at once
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    // outlines exactly the objects given. does nothing if they are already
+    // outlined, so can be called every frame
+    pub fn set(&mut self, scene: &Scene, uuids: &[Uuid]) {
+
+        let objects: Vec<Object3D> = uuids.iter()
+            .filter_map(|uuid| scene.get_object_by_name(&uuid.to_string()))
+            .collect();
+        let unchanged = objects.len() == self.outlines.len()
+            && objects.iter().zip(&self.outlines).all(|(object, (outlined, _))| JsValue::from(object) == JsValue::from(outlined));
+        if unchanged { return }
+
+        for (outlined, outline) in self.outlines.drain(..) {
+            outlined.remove(outline.unchecked_ref());
+        }
+        for object in objects {
+            let outline = outline(&object, self.color, self.scale);
+            object.add(outline.unchecked_ref());
+            self.outlines.push((object, outline));
+        }
+
+    }
+
+}
+
+// a wireframe box around an object's own geometry, or around the default
+// orb if it has none
+fn outline(object: &Object3D, (r, g, b): (f32, f32, f32), scale: f32) -> Mesh {
+
+    let bounds = object.dyn_ref::<Mesh>()
+        .and_then(|mesh| {
+            let geometry = mesh.geometry();
+            geometry.compute_bounding_box();
+            geometry.bounding_box()
+        })
+        .filter(|bounds| !bounds.is_empty());
+    let (centre, size) = match bounds {
+        Some(bounds) => {
+            let centre = bounds.get_center(&Vector3::new(0.0, 0.0, 0.0));
+            let size = bounds.get_size(&Vector3::new(0.0, 0.0, 0.0));
+            ([centre.x(), centre.y(), centre.z()], [size.x(), size.y(), size.z()])
+        },
+        None => ([0.0; 3], [1.0; 3]),
+    };
+
+    let [x, y, z] = size.map(|s| s * scale);
+    let geo = BoxGeometry::new(x, y, z);
+    let mat = MeshBasicMaterial::new();
+    mat.color().set_rgb(r, g, b);
+    mat.set_wireframe(true);
+    let outline = Mesh::new(&geo, &mat);
+    outline.position().set(centre[0], centre[1], centre[2]);
+    outline
+
+}
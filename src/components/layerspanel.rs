@@ -0,0 +1,82 @@
+use dioxus::prelude::*;
+
+use crate::layers::{Flags, Layer};
+use crate::selection::Selection;
+use crate::tea::{Event as TeaEvent, TeaHandler};
+
+// hides and locks objects in the viewport, by type or, for whatever is
+// selected, one at a time
+#[component]
+pub fn LayersPanel() -> Element {
+
+    let tea = use_context::<Signal<Option<TeaHandler>>>();
+    let selected = use_context::<Signal<Selection>>();
+
+    let rows: Vec<(String, Layer, Flags)> = match &*tea.read() {
+        Some(tea) => {
+            let types = tea.display_types().into_iter()
+                .map(|r#type| (r#type.to_string(), Layer::Type(r#type.into())));
+            let names = tea.display_objects();
+            let objects = selected.read().uuids().iter()
+                .filter_map(|uuid| names.iter().find(|(u, _)| u == uuid))
+                .map(|(uuid, name)| (name.clone(), Layer::Object(*uuid)))
+                .collect::<Vec<_>>();
+            types.chain(objects)
+                .map(|(label, layer)| { let flags = tea.display_layers().get(&layer); (label, layer, flags) })
+                .collect()
+        },
+        None => vec![],
+    };
+
+    rsx! {
+        div {
+            class: "panel",
+            table {
+                class: "w-full",
+                tr {
+                    th { "layer" }
+                    th { "hide" }
+                    th { "lock" }
+                }
+                for (label, layer, flags) in rows {
+                    LayerRow { label, layer, flags }
+                }
+            }
+        }
+    }
+
+}
+
+// a type or object, with its flags
+#[component]
+fn LayerRow(label: String, layer: Layer, flags: Flags) -> Element {
+
+    let tea = use_context::<Signal<Option<TeaHandler>>>();
+    let hide_layer = layer.clone();
+
+    rsx! {
+        tr {
+            td { "{label}" }
+            td {
+                input {
+                    r#type: "checkbox",
+                    checked: flags.hidden,
+                    onchange: move |e| set_layer(tea, hide_layer.clone(), Flags { hidden: e.value() == "true", ..flags }),
+                }
+            }
+            td {
+                input {
+                    r#type: "checkbox",
+                    checked: flags.locked,
+                    onchange: move |e| set_layer(tea, layer.clone(), Flags { locked: e.value() == "true", ..flags }),
+                }
+            }
+        }
+    }
+
+}
+
+// sets flags for a layer, which autosaves them but doesn't go in the undo history
+fn set_layer(mut tea: Signal<Option<TeaHandler>>, layer: Layer, flags: Flags) {
+    tea.write().iter_mut().next().unwrap().event(TeaEvent::SetLayer { layer, flags });
+}
@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+
+use image::ImageFormat;
+use uuid::Uuid;
+use wasm_bindgen::{JsCast, JsValue};
+
+use crate::lighting::Preview;
+use crate::tea::TeaHandler;
+use crate::three::{DataTexture, Mesh, Object3D, Scene};
+
+// roughly previews how tiles are lit. tile models aren't loaded, so neither
+// are the uvs the game draws lightmaps with: each tile's first lightmap is
+// just stretched over its floor plan. lightmaps are decoded once per mission
+// and shared between tiles
+#[derive(Default)]
+pub struct LightingPreview {
+    textures: HashMap<String, Option<DataTexture>>,
+    lit: Vec<(Uuid, String, Object3D)>,
+    // the mission, preview and revision last lit for
+    shown: Option<(u32, Preview, u64)>,
+}
+
+impl LightingPreview {
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // lights exactly the tiles the preview picks. only looks again once the
+    // preview or the mission changes, so can be called every frame
+    pub fn set(&mut self, scene: &Scene, tea: &TeaHandler, preview: Preview, loaded: u32) {
+
+        let shown = (loaded, preview, tea.display_revision());
+        if self.shown == Some(shown) { return }
+        // a new mission has its own lightmaps, and its own floors
+        if self.shown.is_some_and(|(was, _, _)| was != loaded) {
+            self.textures.clear();
+            self.lit.clear();
+        }
+        self.shown = Some(shown);
+
+        let lit: Vec<(Uuid, String, Object3D)> = tea.display_lightmaps(preview).into_iter()
+            .filter_map(|(uuid, lightmaps)| {
+                let floor = scene.get_object_by_name(&uuid.to_string())?;
+                Some((uuid, lightmaps.into_iter().next()?, floor))
+            })
+            .collect();
+        let unchanged = lit.len() == self.lit.len()
+            && lit.iter().zip(&self.lit).all(|((a, a_name, a_floor), (b, b_name, b_floor))| {
+                a == b && a_name == b_name && JsValue::from(a_floor) == JsValue::from(b_floor)
+            });
+        if unchanged { return }
+
+        for (_, _, floor) in self.lit.drain(..) {
+            light(&floor, None);
+        }
+        for (_, name, floor) in &lit {
+            let texture = self.textures.entry(name.clone())
+                .or_insert_with(|| tea.display_mission_file(name).and_then(decode));
+            light(floor, texture.as_ref());
+        }
+        self.lit = lit;
+
+    }
+
+}
+
+// get a texture from a tga lightmap, drawn with the second set of uvs
+fn decode(buf: &[u8]) -> Option<DataTexture> {
+    let mut image = image::load_from_memory_with_format(buf, ImageFormat::Tga).ok()?.to_rgba8();
+    // images are decoded top row first, but textures are uploaded bottom row first
+    image::imageops::flip_vertical_in_place(&mut image);
+    let texture = DataTexture::new(&js_sys::Uint8Array::from(image.as_raw().as_slice()), image.width(), image.height());
+    texture.set_channel(1);
+    texture.set_needs_update(true);
+    Some(texture)
+}
+
+// lights a floor with a lightmap, or takes it away. floors are boxes, which
+// only come with one set of uvs, so that is copied to the second
+fn light(floor: &Object3D, lightmap: Option<&DataTexture>) {
+    let mesh = floor.unchecked_ref::<Mesh>();
+    if lightmap.is_some() {
+        let geometry = mesh.geometry();
+        geometry.set_attribute("uv1", &geometry.get_attribute("uv"));
+    }
+    let material = mesh.material();
+    material.set_light_map(lightmap);
+    // basic materials divide lightmaps by pi, so this draws them as they are
+    material.set_light_map_intensity(std::f32::consts::PI);
+    material.set_needs_update(true);
+}
@@ -0,0 +1,89 @@
+use wasm_bindgen::JsCast;
+
+use crate::map::{Icon, IconKind};
+use crate::three::{BoxGeometry, Camera, Mesh, MeshBasicMaterial, Object3D, OrbitControls, OrthographicCamera, PerspectiveCamera, Scene};
+use super::Gizmo;
+
+// the top-down map, seen through an orthographic camera looking straight down
+// at wherever the 3d view was looking. icons are children of the objects they
+// mark, so they follow them about, and are only there while the map is shown
+pub struct MapView {
+    camera: OrthographicCamera,
+    shown: bool,
+    drawn: Vec<Icon>,
+    icons: Vec<(Object3D, Mesh)>,
+}
+
+impl MapView {
+
+    // how many units of the mission fit between the middle and top of the map
+    const HALF_HEIGHT: f64 = 20.0;
+    const ALTITUDE: f32 = 100.0;
+
+    pub fn new(aspect: f64) -> Self {
+        let (half_width, half_height) = (Self::HALF_HEIGHT * aspect, Self::HALF_HEIGHT);
+        let camera = OrthographicCamera::new(-half_width, half_width, half_height, -half_height, 0.1, 1000.0);
+        camera.up().set(0.0, 0.0, -1.0);
+        Self { camera, shown: false, drawn: vec![], icons: vec![] }
+    }
+
+    // get whichever camera is in use
+    pub fn camera<'a>(&'a self, perspective: &'a PerspectiveCamera) -> &'a Camera {
+        if self.shown { &self.camera } else { perspective }
+    }
+
+    // switches between the map and the 3d view. the map can be panned and
+    // zoomed but not turned, and things on it are only dragged across the floor
+    pub fn show(&mut self, shown: bool, perspective: &PerspectiveCamera, orbit: &OrbitControls, gizmo: &Gizmo) {
+
+        if shown == self.shown { return }
+        self.shown = shown;
+        if shown {
+            let target = orbit.target();
+            self.camera.position().set(target.x(), Self::ALTITUDE, target.z());
+            self.camera.look_at(target.x(), target.y(), target.z());
+        }
+        orbit.set_object(self.camera(perspective));
+        orbit.set_enable_rotate(!shown);
+        gizmo.set_camera(self.camera(perspective), shown);
+
+    }
+
+    // draws exactly the icons given, or none while the map is hidden. does
+    // nothing if they are already drawn, so can be called every frame
+    pub fn set(&mut self, scene: &Scene, icons: Vec<Icon>) {
+
+        let icons = if self.shown { icons } else { vec![] };
+        if icons == self.drawn { return }
+
+        for (parent, mesh) in self.icons.drain(..) {
+            parent.remove(mesh.unchecked_ref());
+        }
+        for icon in &icons {
+            let Some(parent) = scene.get_object_by_name(&icon.uuid.to_string()) else { continue };
+            let mesh = icon_mesh(icon);
+            parent.add(mesh.unchecked_ref());
+            self.icons.push((parent, mesh));
+        }
+        self.drawn = icons;
+
+    }
+
+}
+
+// a flat marker just above whatever it marks, i.e. over the top of the
+// default orb, with tiles outlined rather than filled so their labels show
+fn icon_mesh(icon: &Icon) -> Mesh {
+    let (r, g, b) = icon.kind.color();
+    let mat = MeshBasicMaterial::new();
+    mat.color().set_rgb(r, g, b);
+    mat.set_wireframe(icon.kind == IconKind::Tile);
+    let mesh = Mesh::new(&BoxGeometry::new(icon.size.0, 0.05, icon.size.1), &mat);
+    let height = match icon.kind {
+        IconKind::Tile => 0.05,
+        IconKind::BlankingPlate => 0.1,
+        _ => 0.6,
+    };
+    mesh.position().set(icon.offset.0, height, icon.offset.1);
+    mesh
+}
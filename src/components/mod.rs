@@ -1,11 +1,37 @@
 mod picker;
+mod boxselect;
 mod filepicker;
+mod gizmo;
+mod facedrag;
+mod framing;
+mod findreplace;
+mod diffpanel;
+mod layerspanel;
+mod lightingpreview;
+mod snapsettings;
+mod highlight;
+mod mapview;
+mod playerview;
 mod viewport;
 mod rightpanel;
+mod rulelinks;
 mod selector;
 
 pub use picker::Picker;
+pub use boxselect::BoxSelect;
 pub use filepicker::{ FilePicker, File };
-pub use viewport::Viewport;
+pub use findreplace::FindReplace;
+pub use gizmo::Gizmo;
+pub use facedrag::FaceDrag;
+pub use framing::Framing;
+pub use diffpanel::DiffPanel;
+pub use layerspanel::LayersPanel;
+pub use lightingpreview::LightingPreview;
+pub use snapsettings::SnapSettings;
+pub use highlight::Highlight;
+pub use mapview::MapView;
+pub use playerview::PlayerView;
+pub use viewport::{ View, Viewport, ViewportSignals };
 pub use rightpanel::RightPanel;
+pub use rulelinks::RuleLinks;
 pub use selector::Selector;
\ No newline at end of file
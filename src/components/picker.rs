@@ -1,7 +1,8 @@
-use crate::three::{Object3D, PerspectiveCamera, Raycaster, Scene, Vector2};
+use crate::three::{Camera, Object3D, Raycaster, Scene, Vector2};
 use gloo_console::log;
 use uuid::Uuid;
 use std::cell::RefCell;
+use std::collections::HashSet;
 use std::rc::Rc;
 use wasm_bindgen::closure::Closure;
 use wasm_bindgen::{JsCast, JsValue};
@@ -9,46 +10,134 @@ use wasm_bindgen::{JsCast, JsValue};
 pub struct Picker {
     raycaster: Raycaster,
     pick_position: Rc<RefCell<PickPosition>>,
+    hover_position: Rc<RefCell<Option<(f64, f64)>>>,
+    // objects that can't be picked, i.e. hidden or locked ones
+    skipped: HashSet<Uuid>,
+}
+
+// object under a click, and whether the click should add to the selection
+pub struct Pick {
+    pub uuid: Uuid,
+    pub additive: bool,
 }
 
 impl Picker {
     pub fn new(inside: web_sys::HtmlCanvasElement) -> Self {
         Self {
             raycaster: Raycaster::new(),
-            pick_position: PickPosition::new(inside),
+            pick_position: PickPosition::new(inside.clone()),
+            hover_position: hover_position(inside),
+            skipped: HashSet::new(),
         }
     }
 
-    pub fn pick(&mut self, scene: &Scene, camera: &PerspectiveCamera) -> Option<Uuid> {
+    // sets which objects are passed over, as if they weren't there
+    pub fn skip(&mut self, uuids: impl IntoIterator<Item = Uuid>) {
+        self.skipped = uuids.into_iter().collect();
+    }
+
+    // get the objects that can be picked
+    fn pickable(&self, scene: &Scene) -> Vec<JsValue> {
+        scene
+            .dyn_ref::<Object3D>()
+            .expect("scene could not wrangle to o3d")
+            .children()
+            .into_iter()
+            .filter(|child| {
+                let name = child.dyn_ref::<Object3D>().map(Object3D::name).unwrap_or_default();
+                Uuid::parse_str(&name).map_or(true, |uuid| !self.skipped.contains(&uuid))
+            })
+            .collect()
+    }
+
+    pub fn pick(&mut self, scene: &Scene, camera: &Camera) -> Option<Pick> {
 
         if self.pick_position.borrow().is_clear() { return None; }
+        let additive = *self.pick_position.borrow().additive.borrow();
 
-        self.raycaster
-            .set_from_camera(&self.pick_position.borrow().to_vec(), camera);
+        let uuid = self.first_hit(scene, camera, &self.pick_position.borrow().to_vec());
+        self.pick_position.borrow_mut().clear();
+        Some(Pick { uuid: uuid?, additive })
+
+    }
+
+    // get where the pointer is over the viewport, in normalized device coordinates
+    pub fn pointer(&self) -> Option<(f64, f64)> {
+        *self.hover_position.borrow()
+    }
+
+    // finds the object under the pointer, if it is over the viewport
+    pub fn hover(&self, scene: &Scene, camera: &Camera) -> Option<Uuid> {
+        let (x, y) = (*self.hover_position.borrow())?;
+        self.first_hit(scene, camera, &Vector2::new(x, y))
+    }
 
-        let children = scene
+    // finds the nearest object along a ray from the camera through a point,
+    // in normalized device coordinates
+    fn first_hit(&self, scene: &Scene, camera: &Camera, at: &Vector2) -> Option<Uuid> {
+
+        self.raycaster.set_from_camera(at, camera);
+        let intersected = self.raycaster.intersect_objects(self.pickable(scene), false);
+        let intersected = js_sys::Reflect::get(intersected.first()?, &JsValue::from_str("object"))
+            .expect("intersected object did not contain 'object'");
+        let intersected_o3d = intersected
             .dyn_ref::<Object3D>()
-            .expect("scene could not wrangle to o3d")
-            .children();
+            .expect("intersected object 'object' was not object3d");
+        Uuid::parse_str(&intersected_o3d.name()).ok()
 
-        let intersected = self.raycaster.intersect_objects(children, false);
+    }
 
+    // drops a click waiting to be picked, i.e. one that ended a drag
+    pub fn cancel(&mut self) {
         self.pick_position.borrow_mut().clear();
+    }
 
-        if !intersected.is_empty() {
-            let intersected = js_sys::Reflect::get(&intersected[0], &JsValue::from_str("object"))
-                .expect("intersected object did not contain 'object'");
-            let intersected_o3d = intersected
-                .dyn_ref::<Object3D>()
-                .expect("intersected object 'object' was not object3d");
-            Some(Uuid::parse_str(&intersected_o3d.name()).unwrap())
-        } else {
-            None
-        }
+    // finds objects whose origin is inside a rectangle of normalized
+    // device coordinates, as seen from the camera
+    pub fn pick_box(&self, scene: &Scene, camera: &Camera, from: (f64, f64), to: (f64, f64)) -> Vec<Uuid> {
+
+        let (min_x, max_x) = (from.0.min(to.0), from.0.max(to.0));
+        let (min_y, max_y) = (from.1.min(to.1), from.1.max(to.1));
+
+        self.pickable(scene)
+            .into_iter()
+            .filter_map(|child| {
+                let o3d = child.dyn_into::<Object3D>().ok()?;
+                let projected = o3d.position().cloned().project(camera);
+                let (x, y) = (f64::from(projected.x()), f64::from(projected.y()));
+                let inside = (min_x..=max_x).contains(&x) && (min_y..=max_y).contains(&y) && projected.z() < 1.0;
+                inside.then(|| Uuid::parse_str(&o3d.name()).ok()).flatten()
+            })
+            .collect()
 
     }
 }
 
+// keeps track of the pointer over the viewport, in normalized device coordinates
+fn hover_position(inside: web_sys::HtmlCanvasElement) -> Rc<RefCell<Option<(f64, f64)>>> {
+
+    let cell = Rc::new(RefCell::new(None));
+
+    let c = Rc::clone(&cell);
+    let canvas = inside.clone();
+    let moved = Closure::<dyn FnMut(_)>::new(move |e: web_sys::MouseEvent| {
+        let rect = canvas.get_bounding_client_rect();
+        let x = (f64::from(e.client_x()) - rect.left()) / rect.width() * 2.0 - 1.0;
+        let y = (f64::from(e.client_y()) - rect.top()) / rect.height() * (-2.0) + 1.0;
+        *c.borrow_mut() = Some((x, y));
+    });
+    inside.add_event_listener_with_callback("mousemove", moved.as_ref().unchecked_ref()).unwrap();
+    moved.forget();
+
+    let c = Rc::clone(&cell);
+    let left = Closure::<dyn FnMut(_)>::new(move |_: web_sys::MouseEvent| *c.borrow_mut() = None);
+    inside.add_event_listener_with_callback("mouseleave", left.as_ref().unchecked_ref()).unwrap();
+    left.forget();
+
+    cell
+
+}
+
 struct Position {
     x: f64,
     y: f64,
@@ -57,6 +146,7 @@ struct Position {
 struct PickPosition {
     x: RefCell<f64>,
     y: RefCell<f64>,
+    additive: RefCell<bool>,
     inside: web_sys::HtmlCanvasElement,
 }
 
@@ -67,6 +157,7 @@ impl PickPosition {
         let mut new = Self {
             x: RefCell::new(0.0),
             y: RefCell::new(0.0),
+            additive: RefCell::new(false),
             inside,
         };
         new.clear();
@@ -74,6 +165,8 @@ impl PickPosition {
 
         let c = Rc::clone(&cell);
         let cl = Closure::<dyn FnMut(_)>::new(move |e: web_sys::MouseEvent| {
+            // shift-drags are box selects, see BoxSelect
+            if e.shift_key() { return }
             c.borrow_mut().set_from_event(&e);
         });
         web_sys::window()
@@ -110,6 +203,7 @@ impl PickPosition {
     }
 
     fn set_from_event(&self, event: &web_sys::MouseEvent) {
+        *self.additive.borrow_mut() = event.ctrl_key() || event.meta_key();
         let pos = self.get_canvas_rel_pos(event);
         self.set(
             pos.x / f64::from(self.inside.width()) * 2.0 - 1.0,
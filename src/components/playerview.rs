@@ -0,0 +1,56 @@
+use uuid::Uuid;
+use wasm_bindgen::{JsCast, JsValue};
+
+use crate::playmission::Player;
+use crate::three::{Camera, OrbitControls, PerspectiveCamera, Scene};
+use super::Gizmo;
+
+// looks through the camera at the player's eye, from wherever they start.
+// the player is re-rendered as they change, so their eye is found afresh
+// every frame, and while looking through it nothing orbits
+#[derive(Default)]
+pub struct PlayerView {
+    eye: Option<PerspectiveCamera>,
+}
+
+impl PlayerView {
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // get whichever camera is in use
+    pub fn camera<'a>(&'a self, other: &'a Camera) -> &'a Camera {
+        self.eye.as_ref().map_or(other, |eye| eye)
+    }
+
+    // get the player being looked through, to keep them out of the way
+    pub fn player(&self) -> Option<Uuid> {
+        let parent = self.eye.as_ref()?.parent()?;
+        Uuid::parse_str(&parent.name()).ok()
+    }
+
+    // switches between the player's view and another camera, going back to
+    // the other if there is no player to look through
+    pub fn show(&mut self, shown: bool, scene: &Scene, aspect: f64, other: &Camera, orbit: &OrbitControls, gizmo: &Gizmo) {
+
+        let eye = scene.get_object_by_name(Player::VIEW)
+            .filter(|_| shown)
+            .map(JsCast::unchecked_into::<PerspectiveCamera>);
+        let same = match (&eye, &self.eye) {
+            (Some(a), Some(b)) => AsRef::<JsValue>::as_ref(a) == AsRef::<JsValue>::as_ref(b),
+            (a, b) => a.is_none() && b.is_none(),
+        };
+        if same { return }
+
+        if let Some(eye) = &eye {
+            eye.set_aspect(aspect);
+            eye.update_projection_matrix();
+        }
+        orbit.set_enabled(eye.is_none());
+        self.eye = eye;
+        gizmo.set_camera(self.camera(other), false);
+
+    }
+
+}
@@ -0,0 +1,175 @@
+use uuid::Uuid;
+use wasm_bindgen::JsCast;
+
+use crate::links::{End, Link};
+use crate::three::{ArrowHelper, Camera, Object3D, Scene, Vector3};
+
+// draws rules as arrows from their cause to their effect, labelled with
+// their descriptions. entities with no place in the level, or whose name is
+// shared by several objects, are pinned in rows along the top of the
+// viewport, and hovering an arrow picks out its rule
+pub struct RuleLinks {
+    inside: web_sys::HtmlCanvasElement,
+    arrows: Vec<(ArrowHelper, web_sys::HtmlElement)>,
+    pins: Vec<web_sys::HtmlElement>,
+    hovered: Option<Uuid>,
+}
+
+impl RuleLinks {
+
+    const COLOR: u32 = 0xff9900;
+    const HOVERED_COLOR: u32 = 0xffffff;
+    // how close the pointer must be to an arrow to hover it, in pixels
+    const REACH: f64 = 8.0;
+    const PINS_PER_ROW: usize = 5;
+
+    pub fn new(inside: web_sys::HtmlCanvasElement) -> Self {
+        Self { inside, arrows: vec![], pins: vec![], hovered: None }
+    }
+
+    // draws exactly the links given. returns a rule when the pointer first
+    // moves over its arrow
+    pub fn update(&mut self, scene: &Scene, camera: &Camera, links: &[Link], pointer: Option<(f64, f64)>) -> Option<Uuid> {
+
+        let mut pinned: Vec<String> = vec![];
+        for link in links {
+            for pin in [&link.cause, &link.effect].into_iter().filter_map(pin_text) {
+                if !pinned.contains(&pin) { pinned.push(pin) }
+            }
+        }
+        self.resize(scene, links.len(), pinned.len());
+
+        let rect = self.inside.get_bounding_client_rect();
+        let to_screen = |[x, y]: [f64; 2]| (rect.left() + (x + 1.0) / 2.0 * rect.width(), rect.top() + (1.0 - y) / 2.0 * rect.height());
+
+        // pins sit in rows along the top, just in front of the camera
+        let pin_at = |i: usize| {
+            let (row, column) = (i / Self::PINS_PER_ROW, i % Self::PINS_PER_ROW);
+            [-0.9 + 0.4 * column as f64, 0.9 - 0.1 * row as f64]
+        };
+        for (i, (pin, name)) in self.pins.iter().zip(&pinned).enumerate() {
+            place(pin, to_screen(pin_at(i)), name);
+        }
+        let world = |end: &End| -> Option<[f32; 3]> {
+            let point = match end {
+                End::Object(uuid) => scene.get_object_by_name(&uuid.to_string()).map(|o| o.position())?,
+                End::Pinned(_) | End::Ambiguous(_) => {
+                    let pin = pin_text(end)?;
+                    let [x, y] = pin_at(pinned.iter().position(|p| *p == pin)?);
+                    Vector3::new(x as f32, y as f32, 0.5).unproject(camera)
+                },
+            };
+            Some([point.x(), point.y(), point.z()])
+        };
+        // with none for points behind the camera, which project back to
+        // front and would put labels in the wrong place
+        let ndc = |[x, y, z]: [f32; 3]| {
+            let projected = Vector3::new(x, y, z).project(camera);
+            (-1.0..=1.0).contains(&projected.z()).then(|| [f64::from(projected.x()), f64::from(projected.y())])
+        };
+
+        let mut nearest: Option<(f64, Uuid)> = None;
+        for ((arrow, label), link) in self.arrows.iter().zip(links) {
+
+            let (Some(from), Some(to)) = (world(&link.cause), world(&link.effect)) else {
+                arrow.unchecked_ref::<Object3D>().set_visible(false);
+                label.style().set_property("display", "none").unwrap();
+                continue
+            };
+            let offset = [to[0] - from[0], to[1] - from[1], to[2] - from[2]];
+            let length = offset.iter().map(|o| o * o).sum::<f32>().sqrt().max(f32::EPSILON);
+            let [dx, dy, dz] = offset.map(|o| o / length);
+            arrow.unchecked_ref::<Object3D>().set_visible(true);
+            arrow.unchecked_ref::<Object3D>().position().set(from[0], from[1], from[2]);
+            arrow.set_direction(&Vector3::new(dx, dy, dz));
+            arrow.set_length(length, (length * 0.2).min(0.5), 0.2);
+
+            let (Some(from), Some(to)) = (ndc(from), ndc(to)) else {
+                label.style().set_property("display", "none").unwrap();
+                continue
+            };
+            let (from, to) = (to_screen(from), to_screen(to));
+            place(label, ((from.0 + to.0) / 2.0, (from.1 + to.1) / 2.0), &link.description);
+            if let Some(pointer) = pointer.map(|(x, y)| to_screen([x, y])) {
+                let distance = distance_to_segment(pointer, from, to);
+                if distance < Self::REACH && nearest.is_none_or(|(d, _)| distance < d) {
+                    nearest = Some((distance, link.rule));
+                }
+            }
+
+        }
+
+        let hovered = nearest.map(|(_, rule)| rule);
+        for ((arrow, _), link) in self.arrows.iter().zip(links) {
+            arrow.set_color(if hovered == Some(link.rule) { Self::HOVERED_COLOR } else { Self::COLOR });
+        }
+        let changed = hovered != self.hovered;
+        self.hovered = hovered;
+        hovered.filter(|_| changed)
+
+    }
+
+    // keeps as many arrows and pins as there are to draw
+    fn resize(&mut self, scene: &Scene, arrows: usize, pins: usize) {
+
+        let scene = scene.dyn_ref::<Object3D>().unwrap();
+        while self.arrows.len() > arrows {
+            let (arrow, label) = self.arrows.pop().unwrap();
+            scene.remove(arrow.unchecked_ref());
+            label.remove();
+        }
+        while self.arrows.len() < arrows {
+            let arrow = ArrowHelper::new(&Vector3::new(1.0, 0.0, 0.0), &Vector3::new(0.0, 0.0, 0.0), 1.0, Self::COLOR);
+            scene.add(arrow.unchecked_ref());
+            self.arrows.push((arrow, label()));
+        }
+        while self.pins.len() > pins {
+            self.pins.pop().unwrap().remove();
+        }
+        while self.pins.len() < pins {
+            self.pins.push(label());
+        }
+
+    }
+
+}
+
+// get what a pinned end is labelled, i.e. its name, and none for ends
+// drawn where they are
+fn pin_text(end: &End) -> Option<String> {
+    match end {
+        End::Object(_) => None,
+        End::Pinned(name) => Some(name.clone()),
+        End::Ambiguous(name) => Some(format!("{name} (ambiguous)")),
+    }
+}
+
+// a floating label over the viewport
+fn label() -> web_sys::HtmlElement {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let label: web_sys::HtmlElement = document.create_element("div").unwrap().dyn_into().unwrap();
+    label.set_class_name("link-label");
+    document.body().unwrap().append_child(&label).unwrap();
+    label
+}
+
+// moves a label to be centred on a point of the page, with new text
+fn place(label: &web_sys::HtmlElement, (x, y): (f64, f64), text: &str) {
+    if label.text_content().as_deref() != Some(text) {
+        label.set_text_content(Some(text));
+    }
+    let style = label.style();
+    style.set_property("display", "block").unwrap();
+    style.set_property("left", &format!("{x}px")).unwrap();
+    style.set_property("top", &format!("{y}px")).unwrap();
+    style.set_property("transform", "translate(-50%, -50%)").unwrap();
+}
+
+// get how far a point is from a line between two others
+fn distance_to_segment(p: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (ab, ap) = ((b.0 - a.0, b.1 - a.1), (p.0 - a.0, p.1 - a.1));
+    let length = ab.0 * ab.0 + ab.1 * ab.1;
+    let t = if length > 0.0 { ((ap.0 * ab.0 + ap.1 * ab.1) / length).clamp(0.0, 1.0) } else { 0.0 };
+    let closest = (a.0 + ab.0 * t, a.1 + ab.1 * t);
+    ((p.0 - closest.0).powi(2) + (p.1 - closest.1).powi(2)).sqrt()
+}
@@ -0,0 +1,59 @@
+use dioxus::prelude::*;
+
+use crate::placement::Snap;
+
+// settings for snapping whatever the gizmo drags about
+#[component]
+pub fn SnapSettings() -> Element {
+
+    let mut snap = use_context::<Signal<Snap>>();
+
+    // the grid is left empty for none
+    let grid = |s: String| s.trim().parse::<f32>().ok().filter(|g| *g > 0.0);
+    let rotation = snap.read().rotation.map(|r| r.to_string()).unwrap_or_default();
+
+    rsx! {
+        div {
+            input {
+                class: "text-field w-full",
+                placeholder: "snap grid, i.e. 0.5",
+                value: "{snap.read().grid.map(|g| g.to_string()).unwrap_or_default()}",
+                oninput: move |e| snap.write().grid = grid(e.value()),
+            }
+            select {
+                class: "text-field",
+                value: "{rotation}",
+                onchange: move |e| snap.write().rotation = e.value().parse().ok(),
+                option { value: "", "free rotation" }
+                option { value: "45", "45° steps" }
+                option { value: "90", "90° steps" }
+            }
+            br {}
+            input {
+                class: "text-field",
+                r#type: "checkbox",
+                name: "snap-clamp",
+                checked: snap.read().clamp_to_tiles,
+                onchange: move |e| snap.write().clamp_to_tiles = e.value() == "true",
+            }
+            label {
+                class: "link",
+                r#for: "snap-clamp",
+                "keep props in tiles"
+            }
+            input {
+                class: "text-field",
+                r#type: "checkbox",
+                name: "snap-tiles",
+                checked: snap.read().tiles,
+                onchange: move |e| snap.write().tiles = e.value() == "true",
+            }
+            label {
+                class: "link",
+                r#for: "snap-tiles",
+                "snap tiles"
+            }
+        }
+    }
+
+}
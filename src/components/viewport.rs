@@ -1,14 +1,43 @@
 use dioxus::{html::canvas, prelude::*};
+use uuid::Uuid;
 use gloo_console::log;
 use gloo_timers::callback::Interval;
-use uuid::Uuid;
 use wasm_bindgen::JsCast;
 
 use crate::three::{ BoxGeometry, Mesh, MeshBasicMaterial, Object3D, OrbitControls, PerspectiveCamera, Scene, WebGLRenderer };
-use super::Picker;
+use crate::lighting::Preview;
+use crate::placement::Snap;
+use crate::selection::Selection;
+use crate::tea::TeaHandler;
+use super::{ BoxSelect, FaceDrag, Framing, Gizmo, Highlight, LightingPreview, MapView, Picker, PlayerView, RuleLinks };
+
+// what the viewport looks through
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum View {
+    Perspective,
+    Map,
+    Player,
+}
+
+// everything the viewport is driven by from the rest of the page
+#[derive(Clone, Copy, PartialEq)]
+pub struct ViewportSignals {
+    pub scene: Signal<Option<Scene>>,
+    pub selected: Signal<Selection>,
+    pub highlighted: Signal<Vec<Uuid>>,
+    pub view: Signal<View>,
+    // whether rules are drawn as arrows
+    pub links: Signal<bool>,
+    pub lighting: Signal<Preview>,
+    // counts missions loaded, so anything kept from the last can be let go
+    pub loaded: Signal<u32>,
+}
 
 #[component]
-pub fn Viewport(scene_signal: Signal<Option<Scene>>, selected_signal: Signal<Uuid>) -> Element {
+pub fn Viewport(signals: ViewportSignals) -> Element {
+
+    let tea = use_context::<Signal<Option<TeaHandler>>>();
+    let snap = use_context::<Signal<Snap>>();
 
     // fffuckkk offf https://stackoverflow.com/questions/34863788/how-to-check-if-an-element-has-been-loaded-on-a-page-before-running-a-script
     // was possible in 0.4.3 natively https://docs.rs/dioxus-hooks/0.4.3/dioxus_hooks/fn.use_effect.html
@@ -20,7 +49,7 @@ pub fn Viewport(scene_signal: Signal<Option<Scene>>, selected_signal: Signal<Uui
                 display: "none",
                 width: 0,
                 height: 0,
-                onload: move |_| { init(scene_signal, selected_signal); }
+                onload: move |_| { init(signals, tea, snap); }
             }
         }
     }
@@ -29,7 +58,17 @@ pub fn Viewport(scene_signal: Signal<Option<Scene>>, selected_signal: Signal<Uui
 
 // after the page has been rendered and we have a container,
 // load the actual [static-lifetime] viewport to it
-fn init(mut scene_signal: Signal<Option<Scene>>, mut selected_signal: Signal<Uuid>) {
+fn init(signals: ViewportSignals, tea: Signal<Option<TeaHandler>>, snap: Signal<Snap>) {
+
+    let ViewportSignals {
+        scene: mut scene_signal,
+        selected: mut selected_signal,
+        highlighted: highlighted_signal,
+        view: view_signal,
+        links: links_signal,
+        lighting: lighting_signal,
+        loaded: loaded_signal,
+    } = signals;
 
     let container = web_sys::window().unwrap()
         .document().unwrap()
@@ -48,6 +87,17 @@ fn init(mut scene_signal: Signal<Option<Scene>>, mut selected_signal: Signal<Uui
 
     let mut picker = Picker::new(ren.dom_element());
     let controls = OrbitControls::new(&cam, &ren.dom_element());
+    let box_select = BoxSelect::new(ren.dom_element(), controls.clone());
+    let mut gizmo = Gizmo::new(&cam, ren.dom_element(), &scene, controls.clone());
+    let mut face_drag = FaceDrag::new();
+    let mut search_highlight = Highlight::new(1.0, 1.0, 0.0);
+    let mut selection_highlight = Highlight::new(0.0, 0.6, 1.0).with_scale(1.35);
+    let mut hover_highlight = Highlight::new(1.0, 1.0, 1.0).with_scale(1.05);
+    let mut framing = Framing::new();
+    let mut map = MapView::new(win_width / win_height);
+    let mut rule_links = RuleLinks::new(ren.dom_element());
+    let mut player_view = PlayerView::new();
+    let mut lighting = LightingPreview::new();
 
     // TODO: fix unwrap... although tihs shiould never fail
     container.append_child(&ren.dom_element()).unwrap();
@@ -55,15 +105,63 @@ fn init(mut scene_signal: Signal<Option<Scene>>, mut selected_signal: Signal<Uui
 
     Interval::new(16, move || {
 
-        if let Some(selected) = picker.pick(scene_signal.write().iter_mut().next().expect("FAILED_ONE"), &cam) {
-            *selected_signal.write() = selected;
+        let view = *view_signal.read();
+        map.show(view == View::Map, &cam, &controls, &gizmo);
+        let icons = if view == View::Map { tea.read().as_ref().map(TeaHandler::display_map).unwrap_or_default() } else { vec![] };
+        map.set(scene_signal.write().iter_mut().next().expect("FAILED_ONE"), icons);
+        player_view.show(view == View::Player, scene_signal.write().iter_mut().next().expect("FAILED_ONE"), win_width / win_height, map.camera(&cam), &controls, &gizmo);
+        let camera = player_view.camera(map.camera(&cam));
+
+        // hidden objects can't be picked or moved either, as they can't be seen.
+        // nor can the player while looking through their eyes
+        let (mut hidden, locked) = tea.read().as_ref().map(|tea| (tea.display_hidden(), tea.display_locked())).unwrap_or_default();
+        hidden.extend(player_view.player());
+        hide(scene_signal.write().iter_mut().next().expect("FAILED_ONE"), &hidden);
+        picker.skip(hidden.iter().chain(&locked).copied());
+        let movable = selected_signal.read().single().filter(|uuid| !hidden.contains(uuid) && !locked.contains(uuid));
+
+        gizmo.attach(scene_signal.write().iter_mut().next().expect("FAILED_ONE"), movable);
+        if gizmo.update(tea, &snap.read()) {
+            picker.cancel();
+        }
+        if face_drag.update(scene_signal.write().iter_mut().next().expect("FAILED_ONE"), camera, movable, picker.pointer(), tea) {
+            picker.cancel();
+        }
+        if let Some(pick) = picker.pick(scene_signal.write().iter_mut().next().expect("FAILED_ONE"), camera) {
+            selected_signal.write().click(pick.uuid, pick.additive);
         }
+        if let Some(area) = box_select.take() {
+            let uuids = picker.pick_box(scene_signal.write().iter_mut().next().expect("FAILED_ONE"), camera, area.from, area.to);
+            selected_signal.write().select_all(uuids, area.additive);
+        }
+        search_highlight.set(scene_signal.write().iter_mut().next().expect("FAILED_ONE"), &highlighted_signal.read());
+        selection_highlight.set(scene_signal.write().iter_mut().next().expect("FAILED_ONE"), selected_signal.read().uuids());
+        let hovered = picker.hover(scene_signal.write().iter_mut().next().expect("FAILED_ONE"), camera);
+        hover_highlight.set(scene_signal.write().iter_mut().next().expect("FAILED_ONE"), &Vec::from_iter(hovered));
+        let links = if *links_signal.read() { tea.read().as_ref().map(TeaHandler::display_links).unwrap_or_default() } else { vec![] };
+        if let Some(rule) = rule_links.update(scene_signal.write().iter_mut().next().expect("FAILED_ONE"), camera, &links, picker.pointer()) {
+            selected_signal.write().click(rule, false);
+        }
+        if let Some(tea) = &*tea.read() {
+            lighting.set(scene_signal.write().iter_mut().next().expect("FAILED_ONE"), tea, *lighting_signal.read(), *loaded_signal.read());
+        }
+        framing.update(scene_signal.write().iter_mut().next().expect("FAILED_ONE"), selected_signal.read().uuids(), camera, &controls);
         controls.update();
-        ren.render(scene_signal.write().iter_mut().next().expect("FAILED_TWO"), &cam);
+        ren.render(scene_signal.write().iter_mut().next().expect("FAILED_TWO"), camera);
 
     })
     .forget();
 
     *scene_signal.write() = Some(scene);
 
+}
+
+// shows every object but those given
+fn hide(scene: &Scene, hidden: &[Uuid]) {
+    for child in scene.dyn_ref::<Object3D>().unwrap().children() {
+        let Ok(child) = child.dyn_into::<Object3D>() else { continue };
+        if let Ok(uuid) = Uuid::parse_str(&child.name()) {
+            child.set_visible(!hidden.contains(&uuid));
+        }
+    }
 }
\ No newline at end of file
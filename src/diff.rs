@@ -0,0 +1,229 @@
+// compares two versions of a mission. uuids are made up on every load, so
+// objects are matched by type and name, in order where several share both
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+use uuid::Uuid;
+
+use crate::playmission::{MissionObject, Object, Properties};
+
+// an object, as matched between versions
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ObjectKey {
+    pub r#type: String,
+    pub name: String,
+}
+
+// a value that differs, with none where it is missing on one side
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValueChange {
+    pub key: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FileChange {
+    Added,
+    Removed,
+    Changed,
+}
+
+// everything that differs in an object found in both versions
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectDiff {
+    pub object: ObjectKey,
+    pub properties: Vec<ValueChange>,
+    pub datafile: Vec<ValueChange>,
+    pub files: Vec<(String, FileChange)>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Diff {
+    pub mission: Vec<ValueChange>,
+    pub files: Vec<(String, FileChange)>,
+    pub added: Vec<ObjectKey>,
+    pub removed: Vec<ObjectKey>,
+    pub changed: Vec<ObjectDiff>,
+}
+
+// one version of a mission, as loaded
+pub type Version<'a> = (&'a MissionObject, &'a HashMap<Uuid, Object>);
+
+impl Diff {
+
+    // compares an earlier version of a mission against a later one
+    pub fn between(before: Version, after: Version) -> Self {
+
+        let mut diff = Diff {
+            mission: values(before.0.properties(), after.0.properties()),
+            files: files(before.0.files(), after.0.files()),
+            ..Default::default()
+        };
+
+        let mut after_objects = keyed(after);
+        for (key, object) in keyed(before) {
+            let Some(i) = after_objects.iter().position(|(k, _)| *k == key) else {
+                diff.removed.push(key);
+                continue
+            };
+            let (_, other) = after_objects.remove(i);
+            let changed = ObjectDiff {
+                object: key,
+                properties: values(object.properties(), other.properties()),
+                datafile: values(object.datafile(), other.datafile()),
+                files: files(object.files(), other.files()),
+            };
+            if !changed.is_empty() {
+                diff.changed.push(changed);
+            }
+        }
+        diff.added = after_objects.into_iter().map(|(key, _)| key).collect();
+        diff
+
+    }
+
+    // whether both versions are the same
+    pub fn is_empty(&self) -> bool {
+        self.mission.is_empty() && self.files.is_empty()
+            && self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+}
+
+impl ObjectDiff {
+
+    // whether the object is the same in both versions
+    pub fn is_empty(&self) -> bool {
+        self.properties.is_empty() && self.datafile.is_empty() && self.files.is_empty()
+    }
+
+}
+
+// get objects with their keys, in the order they are saved
+fn keyed<'a>((mission, objects): Version<'a>) -> Vec<(ObjectKey, &'a Object)> {
+    mission.order(objects).iter()
+        .map(|uuid| &objects[uuid])
+        .map(|o| (ObjectKey { r#type: o.r#type().into(), name: o.name().unwrap_or_default() }, o))
+        .collect()
+}
+
+// compares values by their text, sorted by key
+fn values(before: &Properties, after: &Properties) -> Vec<ValueChange> {
+    let keys: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    keys.into_iter()
+        .map(|key| ValueChange {
+            key: key.clone(),
+            before: before.get_value(key).ok().map(ToString::to_string),
+            after: after.get_value(key).ok().map(ToString::to_string),
+        })
+        .filter(|change| change.before != change.after)
+        .collect()
+}
+
+// compares files by their contents, sorted by name
+fn files(before: &HashMap<String, Vec<u8>>, after: &HashMap<String, Vec<u8>>) -> Vec<(String, FileChange)> {
+    let names: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    names.into_iter()
+        .filter_map(|name| match (before.get(name), after.get(name)) {
+            (Some(_), None) => Some((name.clone(), FileChange::Removed)),
+            (None, Some(_)) => Some((name.clone(), FileChange::Added)),
+            (Some(a), Some(b)) if a != b => Some((name.clone(), FileChange::Changed)),
+            _ => None,
+        })
+        .collect()
+}
+
+impl fmt::Display for ObjectKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} '{}'", self.r#type, self.name)
+    }
+}
+
+impl fmt::Display for ValueChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.before, &self.after) {
+            (Some(before), Some(after)) => write!(f, "{}: {before} -> {after}", self.key),
+            (Some(before), None) => write!(f, "{}: removed {before}", self.key),
+            (None, Some(after)) => write!(f, "{}: added {after}", self.key),
+            (None, None) => write!(f, "{}", self.key),
+        }
+    }
+}
+
+impl fmt::Display for FileChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FileChange::Added => "added",
+            FileChange::Removed => "removed",
+            FileChange::Changed => "changed",
+        })
+    }
+}
+
+// as a unified listing, like diff output, i.e. for the command line
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+
+        for change in &self.mission {
+            writeln!(f, "~ mission {change}")?;
+        }
+        for (name, change) in &self.files {
+            writeln!(f, "~ mission file {name} {change}")?;
+        }
+        for key in &self.removed {
+            writeln!(f, "- {key}")?;
+        }
+        for key in &self.added {
+            writeln!(f, "+ {key}")?;
+        }
+        for changed in &self.changed {
+            writeln!(f, "~ {}", changed.object)?;
+            for change in &changed.properties {
+                writeln!(f, "    {change}")?;
+            }
+            for change in &changed.datafile {
+                writeln!(f, "    datafile {change}")?;
+            }
+            for (name, change) in &changed.files {
+                writeln!(f, "    file {name} {change}")?;
+            }
+        }
+        Ok(())
+
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tea::{Edit, Event, TeaHandler};
+    use crate::utils::{get_test_dir, uuid_of, zip_files};
+
+    #[test]
+    fn objects_match_by_name_and_type() {
+        let buf = zip_files(get_test_dir("testcase"));
+        let mut th = TeaHandler::from_buffer_lenient(buf.clone()).unwrap();
+        assert!(th.diff(&buf).unwrap().is_empty());
+
+        let television = uuid_of(&th, "Television");
+        let suitcase = uuid_of(&th, "Wheeled Suitcase");
+        th.event(Event::Edit(Edit::Batch { edits: vec![
+            Edit::UpdateProperty { uuid: television, key: "Position X".into(), value: "2".into() },
+            Edit::UpdateProperty { uuid: suitcase, key: "Name".into(), value: "Suitcase".into() },
+            Edit::UpdateMissionFile { key: "notes.txt".into(), buffer: Some(b"hello".to_vec()) },
+        ]}));
+
+        // the file compared against is the later version, so the edits read backwards
+        let diff = th.diff(&buf).unwrap();
+        let prop = |name: &str| ObjectKey { r#type: "PROP".into(), name: name.into() };
+        assert_eq!(vec![prop("Wheeled Suitcase")], diff.added);
+        assert_eq!(vec![prop("Suitcase")], diff.removed);
+        assert_eq!(vec![("notes.txt".to_string(), FileChange::Removed)], diff.files);
+        assert_eq!(1, diff.changed.len());
+        assert_eq!("Position X", diff.changed[0].properties[0].key);
+        assert_eq!(Some("2.0"), diff.changed[0].properties[0].before.as_deref());
+        assert!(diff.to_string().contains("+ PROP 'Wheeled Suitcase'"));
+    }
+}
@@ -0,0 +1,189 @@
+// every edit made to a mission, recorded so it can be exported and applied
+// again to a fresh load of the same mission, or to others like it
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::playmission::Object;
+use crate::tea::{Edit, TeaError, TeaHandler};
+
+// uuids change every time a mission is loaded, so edits are matched
+// to objects by the name they had before they were first edited.
+// objects inserted by the journal itself keep their uuids
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct Journal {
+    objects: BTreeMap<Uuid, String>,
+    #[serde(default)]
+    inserted: BTreeSet<Uuid>,
+    edits: Vec<Edit>,
+}
+
+impl Journal {
+
+    // creates empty journal
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // parse journal from exported json
+    pub fn from_json(buf: &[u8]) -> Result<Self> {
+        Ok(serde_json::from_slice(buf)?)
+    }
+
+    // export journal as json
+    pub fn to_json(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec_pretty(self)?)
+    }
+
+    // get names of objects an edit touches that have not been seen before,
+    // to be recorded alongside it once it has been applied
+    pub fn names(&self, edit: &Edit, objects: &HashMap<Uuid, Object>) -> Vec<(Uuid, String)> {
+        edit.uuids()
+            .into_iter()
+            .filter(|uuid| !self.objects.contains_key(uuid) && !self.inserted.contains(uuid))
+            .filter_map(|uuid| Some((uuid, objects.get(&uuid)?.name()?)))
+            .collect()
+    }
+
+    // records an applied edit
+    pub fn record(&mut self, names: Vec<(Uuid, String)>, edit: Edit) {
+        self.objects.extend(names);
+        let inserted = edit.inserted().into_iter().filter(|uuid| !self.objects.contains_key(uuid));
+        self.inserted.extend(inserted);
+        self.edits.push(edit);
+    }
+
+    // get recorded edits, oldest first
+    pub fn edits(&self) -> &[Edit] {
+        &self.edits
+    }
+
+    // get recorded edits, with uuids replaced by those of the objects
+    // with the same names in another load
+    pub fn resolve(&self, objects: &HashMap<Uuid, Object>) -> Result<Vec<Edit>> {
+
+        let mut uuids = HashMap::new();
+        for (recorded, name) in &self.objects {
+            let mut matching = objects.values().filter(|o| o.name().as_ref() == Some(name));
+            let found = matching.next().ok_or_else(|| JournalError::NoObject(name.clone()))?;
+            if matching.next().is_some() {
+                return Err(JournalError::AmbiguousObject(name.clone()))
+            }
+            uuids.insert(*recorded, *found.uuid());
+        }
+        uuids.extend(self.inserted.iter().map(|uuid| (*uuid, *uuid)));
+
+        self.edits.iter()
+            .cloned()
+            .map(|edit| edit.map_uuids(&|uuid| uuids.get(&uuid).copied()).ok_or(JournalError::UnnamedObject))
+            .collect()
+
+    }
+
+}
+
+// applies a journal as an edit script to each of many missions, returning
+// each edited mission or why it could not be edited
+pub fn apply_to_all(journal: &Journal, missions: impl IntoIterator<Item = (String, Vec<u8>)>) -> Vec<(String, std::result::Result<Vec<u8>, TeaError>)> {
+    missions.into_iter()
+        .map(|(name, buf)| {
+            let edited = TeaHandler::from_buffer_lenient(buf).and_then(|mut th| {
+                th.replay(journal)?;
+                th.serialize()
+            });
+            (name, edited)
+        })
+        .collect()
+}
+
+pub type Result<T> = std::result::Result<T, JournalError>;
+
+#[derive(Debug, Error)]
+pub enum JournalError {
+    #[error("no object named {0} to replay edits on")]
+    NoObject(String),
+    #[error("more than one object named {0} to replay edits on")]
+    AmbiguousObject(String),
+    #[error("journal edits an object with no name, which can't be replayed")]
+    UnnamedObject,
+    #[error("failed reading or writing journal")]
+    Json {
+        #[from]
+        source: serde_json::Error,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tea::Event;
+    use crate::utils::{get_test_dir, testcase, uuid_of, zip_files};
+
+    fn value(th: &TeaHandler, name: &str, k: &str) -> String {
+        th.display_properties(uuid_of(th, name)).unwrap().into_iter().find(|(n, _)| n == k).unwrap().1.to_string()
+    }
+
+    fn set(uuid: Uuid, k: &str, v: &str) -> Edit {
+        Edit::UpdateProperty { uuid, key: k.into(), value: v.into() }
+    }
+
+    #[test]
+    fn replay_reproduces_edits() {
+        let mut th = testcase();
+        let (suitcase, television) = (uuid_of(&th, "Wheeled Suitcase"), uuid_of(&th, "Television"));
+        th.event(Event::Edit(set(suitcase, "Position X", "1.0")));
+        th.event(Event::Edit(Edit::Batch { edits: vec![set(suitcase, "Name", "Suitcase"), set(television, "Position Y", "2.0")] }));
+        th.event(Event::Undo);
+        th.event(Event::Edit(set(suitcase, "Position Z", "3.0")));
+        assert_eq!(4, th.journal().edits().len());
+
+        let mut fresh = testcase();
+        let before = value(&fresh, "Television", "Position Y");
+        fresh.event(Event::ImportJournal { buffer: th.journal().to_json().unwrap() });
+        assert_eq!(None, fresh.display_status());
+        for k in ["Position X", "Position Y", "Position Z"] {
+            assert_eq!(value(&th, "Wheeled Suitcase", k), value(&fresh, "Wheeled Suitcase", k));
+        }
+        assert_eq!(before, value(&fresh, "Television", "Position Y"));
+
+        // and is undone as one step
+        fresh.event(Event::Undo);
+        assert_eq!(value(&testcase(), "Wheeled Suitcase", "Position Z"), value(&fresh, "Wheeled Suitcase", "Position Z"));
+    }
+
+    #[test]
+    fn apply_to_all_reports_each_mission() {
+        let mut th = testcase();
+        th.event(Event::Edit(set(uuid_of(&th, "Wheeled Suitcase"), "Position X", "1.0")));
+
+        let renamed = get_test_dir("testcase").into_iter()
+            .map(|(name, buf)| match name.as_str() {
+                "Test.mission" => (name, String::from_utf8(buf).unwrap().replace("Wheeled Suitcase", "Suitcase").into_bytes()),
+                _ => (name, buf),
+            })
+            .collect();
+        let missions = vec![
+            ("testcase".to_string(), zip_files(get_test_dir("testcase"))),
+            ("renamed".to_string(), zip_files(renamed)),
+        ];
+
+        let results = apply_to_all(th.journal(), missions);
+        let edited = TeaHandler::from_buffer_lenient(results[0].1.as_ref().unwrap().clone()).unwrap();
+        assert_eq!("1.0", value(&edited, "Wheeled Suitcase", "Position X"));
+        assert!(matches!(
+            results[1].1,
+            Err(TeaError::Journal { source: JournalError::NoObject(ref name) }) if name == "Wheeled Suitcase"
+        ));
+    }
+
+    #[test]
+    fn file_buffers_are_base64() {
+        let edit = Edit::UpdateFile { uuid: Uuid::nil(), key: "a.jpg".into(), buffer: b"abc".to_vec() };
+        let json = serde_json::to_string(&edit).unwrap();
+        assert!(json.contains("\"YWJj\""));
+        assert_eq!(edit, serde_json::from_str(&json).unwrap());
+    }
+}
@@ -0,0 +1,85 @@
+// which objects are shown and which can be picked in the viewport. these
+// belong to the editor rather than the mission, so are kept with autosaves
+// but never saved into the mission itself
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::playmission::Object;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct Flags {
+    pub hidden: bool,
+    pub locked: bool,
+}
+
+// flags can be set for every object of a type, or for single objects
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum Layer {
+    Type(String),
+    Object(Uuid),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Layers {
+    types: BTreeMap<String, Flags>,
+    objects: HashMap<Uuid, Flags>,
+}
+
+impl Layers {
+
+    // get the flags set for a layer, which are all off unless set
+    pub fn get(&self, layer: &Layer) -> Flags {
+        match layer {
+            Layer::Type(r#type) => self.types.get(r#type),
+            Layer::Object(uuid) => self.objects.get(uuid),
+        }.copied().unwrap_or_default()
+    }
+
+    // sets the flags for a layer, forgetting them once all are off again
+    pub fn set(&mut self, layer: Layer, flags: Flags) {
+        let unset = flags == Flags::default();
+        match layer {
+            Layer::Type(r#type) if unset => { self.types.remove(&r#type); },
+            Layer::Type(r#type) => { self.types.insert(r#type, flags); },
+            Layer::Object(uuid) if unset => { self.objects.remove(&uuid); },
+            Layer::Object(uuid) => { self.objects.insert(uuid, flags); },
+        }
+    }
+
+    // get the flags an object ends up with, from its own and its type's
+    pub fn of(&self, uuid: Uuid, object: &Object) -> Flags {
+        let (own, of_type) = (self.get(&Layer::Object(uuid)), self.get(&Layer::Type(object.r#type().into())));
+        Flags { hidden: own.hidden || of_type.hidden, locked: own.locked || of_type.locked }
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tea::{Event, TeaHandler};
+    use crate::utils::{testcase, uuid_of};
+
+    #[test]
+    fn types_and_objects_combine() {
+        let mut th = testcase();
+        let (suitcase, player) = (uuid_of(&th, "Wheeled Suitcase"), uuid_of(&th, "Player"));
+
+        th.event(Event::SetLayer { layer: Layer::Type("PROP".into()), flags: Flags { hidden: true, locked: false } });
+        th.event(Event::SetLayer { layer: Layer::Object(player), flags: Flags { hidden: false, locked: true } });
+        assert!(th.display_hidden().contains(&suitcase));
+        assert!(!th.display_hidden().contains(&player));
+        assert_eq!(vec![player], th.display_locked());
+
+        // kept in autosaves, but not in the mission
+        assert_eq!(2, th.display_revision());
+        let restored = TeaHandler::from_snapshot(th.snapshot().unwrap()).unwrap();
+        assert_eq!(th.display_layers(), restored.display_layers());
+        th.event(Event::SetLayer { layer: Layer::Object(player), flags: Flags::default() });
+        assert!(th.display_locked().is_empty());
+        assert_eq!(Flags::default(), th.display_layers().get(&Layer::Object(player)));
+    }
+}
@@ -0,0 +1,22 @@
+#![allow(non_snake_case)]
+
+pub mod playmission;
+pub mod components;
+pub mod three;
+pub mod utils;
+pub mod autosave;
+pub mod selection;
+pub mod journal;
+pub mod query;
+pub mod replace;
+pub mod script;
+pub mod clipboard;
+pub mod merge;
+pub mod diff;
+pub mod placement;
+pub mod map;
+pub mod layers;
+pub mod lighting;
+pub mod links;
+pub mod volumes;
+pub mod tea;
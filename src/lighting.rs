@@ -0,0 +1,84 @@
+// previews how tiles are lit. tiles ship a set of lightmaps for each of their
+// lighting states, named after the tile's model and the state's label, i.e.
+// "BARONIAL_3DOOR_Lights On!_lmap0.tga". each LOCATION picks one by its
+// Lighting Set, which is one of its tile's Light Label or Dark Label
+
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use crate::playmission::Object;
+
+// which lightmaps tiles are previewed with, if any
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Preview {
+    #[default]
+    Off,
+    // whichever each tile's Lighting Set picks
+    AsSet,
+    // every tile with its lights on, or off
+    Light,
+    Dark,
+}
+
+// get the label of the lighting state a tile is previewed in
+pub fn label(object: &Object, preview: Preview) -> Option<String> {
+    let (properties, k) = match preview {
+        Preview::Off => return None,
+        Preview::AsSet => (object.properties(), "Lighting Set"),
+        Preview::Light => (object.datafile(), "Light Label"),
+        Preview::Dark => (object.datafile(), "Dark Label"),
+    };
+    properties.get_value(k).ok().map(ToString::to_string).filter(|label| !label.is_empty())
+}
+
+// get the names of a tile's lightmaps for how it is previewed, in order,
+// as they are found among the mission's files
+pub fn lightmaps(object: &Object, preview: Preview, files: &HashMap<String, Vec<u8>>) -> Vec<String> {
+
+    if object.r#type() != "LOCATION" { return vec![] }
+    let Some(label) = label(object, preview) else { return vec![] };
+    let Ok(model) = object.datafile().get_value("Filename").map(ToString::to_string) else { return vec![] };
+    let model = model.rsplit_once('.').map_or(model.as_str(), |(stem, _)| stem).to_uppercase();
+
+    // files may have been saved in a different case to the model's name
+    let find = |name: String| files.keys().find(|k| k.eq_ignore_ascii_case(&name)).cloned();
+    (0..)
+        .map_while(|n| find(format!("{model}_{label}_lmap{n}.tga")))
+        .collect()
+
+}
+
+// get the lightmaps of every tile that has them, sorted by uuid
+pub fn tiles(objects: &HashMap<Uuid, Object>, preview: Preview, files: &HashMap<String, Vec<u8>>) -> Vec<(Uuid, Vec<String>)> {
+    let mut tiles: Vec<(Uuid, Vec<String>)> = objects.iter()
+        .map(|(uuid, o)| (*uuid, lightmaps(o, preview, files)))
+        .filter(|(_, lightmaps)| !lightmaps.is_empty())
+        .collect();
+    tiles.sort_by_key(|(uuid, _)| *uuid);
+    tiles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tea::{Edit, Event, TeaHandler};
+    use crate::utils::{testcase, uuid_of};
+
+    #[test]
+    fn lighting_set_picks_lightmaps() {
+        let mut th = testcase();
+        let tile = uuid_of(&th, "Baronial_3Door");
+        let of = |th: &TeaHandler, preview| th.display_lightmaps(preview).into_iter().find(|(uuid, _)| *uuid == tile).map(|(_, l)| l).unwrap_or_default();
+
+        let lit = of(&th, Preview::AsSet);
+        assert_eq!(4, lit.len());
+        assert_eq!("BARONIAL_3DOOR_Lights On!_lmap0.tga", lit[0]);
+        assert!(th.display_mission_file(&lit[3]).is_some());
+        assert_eq!("BARONIAL_3DOOR_Lights Off!_lmap2.tga", of(&th, Preview::Dark)[2]);
+        assert!(th.display_lightmaps(Preview::Off).is_empty());
+
+        th.event(Event::Edit(Edit::UpdateProperty { uuid: tile, key: "Lighting Set".into(), value: "Lights Off!".into() }));
+        assert_eq!(of(&th, Preview::Dark), of(&th, Preview::AsSet));
+    }
+}
@@ -0,0 +1,89 @@
+// rules as links from the entity that causes them to the one they affect.
+// entities with no place in the level, like the game itself or media, are
+// left to be pinned somewhere out of the way by whatever draws the links
+
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use crate::placement::position;
+use crate::playmission::Object;
+
+// one end of a link
+#[derive(Debug, Clone, PartialEq)]
+pub enum End {
+    // an object in the level, to be drawn wherever it is
+    Object(Uuid),
+    // anything else, by name
+    Pinned(String),
+    // more than one object in the level with the name, so which is meant
+    // can't be told
+    Ambiguous(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Link {
+    pub rule: Uuid,
+    pub description: String,
+    pub cause: End,
+    pub effect: End,
+}
+
+// get a link for every rule naming both its entities, sorted by rule name
+pub fn links(objects: &HashMap<Uuid, Object>) -> Vec<Link> {
+
+    let end = |name: String| {
+        let mut matching = objects.iter()
+            .filter(|(_, o)| o.r#type() != "RULE" && o.name().as_ref() == Some(&name) && position(o).is_some());
+        match (matching.next(), matching.next()) {
+            (Some((uuid, _)), None) => End::Object(*uuid),
+            (Some(_), Some(_)) => End::Ambiguous(name),
+            (None, _) => End::Pinned(name),
+        }
+    };
+    let entity = |rule: &Object, k: &str| {
+        rule.properties().get_value(k).ok().map(ToString::to_string).filter(|name| !name.is_empty())
+    };
+
+    let mut rules: Vec<(&Uuid, &Object)> = objects.iter().filter(|(_, o)| o.r#type() == "RULE").collect();
+    rules.sort_by_key(|(_, o)| o.name());
+    rules.into_iter()
+        .filter_map(|(uuid, rule)| Some(Link {
+            rule: *uuid,
+            description: entity(rule, "Description").unwrap_or_else(|| rule.name().unwrap_or_default()),
+            cause: end(entity(rule, "Cause Entity")?),
+            effect: end(entity(rule, "Effect Entity")?),
+        }))
+        .collect()
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tea::{Edit, Event};
+    use crate::utils::{testcase, uuid_of};
+
+    #[test]
+    fn entities_without_positions_are_pinned() {
+        let th = testcase();
+
+        let links = th.display_links();
+        let rule = links.iter().find(|link| link.rule == uuid_of(&th, "Rule 1")).unwrap();
+        assert_eq!("At the start of the game , Television starts displaying Video 1", rule.description);
+        assert_eq!(End::Pinned("My Game".into()), rule.cause);
+        assert_eq!(End::Object(uuid_of(&th, "Television")), rule.effect);
+        assert!(links.iter().any(|link| link.cause == End::Object(uuid_of(&th, "Player"))));
+    }
+
+    #[test]
+    fn shared_names_are_ambiguous() {
+        let mut th = testcase();
+        let uuid = uuid_of(&th, "Wheeled Suitcase");
+        th.event(Event::Edit(Edit::UpdateProperty { uuid, key: "Name".into(), value: "Television".into() }));
+
+        let links = th.display_links();
+        let rule = links.iter().find(|link| link.rule == uuid_of(&th, "Rule 1")).unwrap();
+        assert_eq!(End::Ambiguous("Television".into()), rule.effect);
+    }
+}
@@ -1,16 +1,11 @@
 #![allow(non_snake_case)]
 
-mod playmission;
-mod components;
-mod three;
-mod utils;
-mod tea;
-
 use std::io::Cursor;
 
 // import the prelude to get access to the `rsx!` macro and the `Element` type
 use dioxus::prelude::*;
 use gloo_console::log;
+use gloo_timers::callback::Interval;
 use image::ImageFormat;
 use uuid::Uuid;
 use image::io::Reader as ImageReader;
@@ -18,13 +13,22 @@ use base64::prelude::*;
 use wasm_bindgen::closure::Closure;
 use wasm_bindgen::JsCast;
 
-use crate::components::{ File, FilePicker, Viewport };
-use crate::playmission::Value;
-use crate::tea::TeaHandler;
-use crate::three::Scene;
+use missioneditor2::{autosave, clipboard, tea};
+use missioneditor2::autosave::{ IndexedDb, Session, Storage };
+use missioneditor2::components::{ DiffPanel, File, FilePicker, FindReplace, LayersPanel, SnapSettings, View, Viewport, ViewportSignals };
+use missioneditor2::lighting::Preview;
+use missioneditor2::playmission::{error::chain, Value};
+use missioneditor2::placement::Snap;
+use missioneditor2::query::Query;
+use missioneditor2::selection::Selection;
+use missioneditor2::tea::TeaHandler;
+use missioneditor2::three::Scene;
 
 const _TAILWIND_URL: &str = manganis::mg!(file("input.css"));
 
+// milliseconds between autosaves, if anything has changed
+const AUTOSAVE_INTERVAL: u32 = 30_000;
+
 fn main() {
     launch(App);
 }
@@ -32,58 +36,174 @@ fn main() {
 fn App() -> Element {
 
     // storing three.js canvas for rendering
-    let mut scene: Signal<Option<Scene>> = use_signal(|| None);
+    let scene: Signal<Option<Scene>> = use_signal(|| None);
 
     // loading file
     let mut import = use_signal(|| File::None);
+    let mut load_error: Signal<Option<String>> = use_signal(|| None);
+    let mut recovery: Signal<Option<(String, Vec<u8>)>> = use_signal(|| None);
     let tea_signal = use_signal(|| None);
+    // bumped each time a mission is opened
+    let loaded = use_signal(|| 0);
+    // autosaves the open mission, replaced when another is opened
+    let autosave = use_signal(|| None);
     let mut tea = use_context_provider(|| tea_signal);
-    let selected_signal = use_signal(|| Uuid::nil());
+    let selected_signal = use_signal(Selection::new);
     let selected = use_context_provider(|| selected_signal);
+    let snap_signal = use_signal(Snap::default);
+    use_context_provider(|| snap_signal);
     
     // seems to be the best way to do this... For Real ??...
     if matches!(*import.read(), File::Loaded{..}) {
 
-        let File::Loaded { data, .. } = import.replace(File::None) else { unreachable!() };
-
-        if let Ok(mut th) = TeaHandler::from_buffer(data) {
+        let File::Loaded { name, data } = import.replace(File::None) else { unreachable!() };
 
-            th.render((*scene.write()).iter_mut().next().unwrap());
+        match TeaHandler::from_buffer(data.clone()) {
+            Ok(th) => open_mission(th, Session::new(name, js_sys::Date::now()), scene, tea, loaded, autosave),
+            Err(e) => {
+                *load_error.write() = Some(chain(&e));
+                *recovery.write() = Some((name, data));
+            },
+        }
 
-            *tea.write() = Some(th);
+    }
 
-            // setup key listening
-            let on_keypress = Closure::<dyn FnMut(_)>::new(
-                move |e: web_sys::KeyboardEvent| {
-                    (*tea.write()).iter_mut().next().unwrap().event(tea::Event::Keypress{e});
-                }
-            );
-            web_sys::window()
-                .unwrap()
-                .add_event_listener_with_callback("keypress", on_keypress.as_ref().unchecked_ref())
-                .unwrap();
-            on_keypress.forget();
+    // loads the last failed import again, keeping whatever can't be loaded as-is
+    let recover_closure = move |_| {
+        let Some((name, data)) = recovery.write().take() else { return };
+        match TeaHandler::from_buffer_lenient(data) {
+            Ok(th) => open_mission(th, Session::new(name, js_sys::Date::now()), scene, tea, loaded, autosave),
+            Err(e) => *load_error.write() = Some(chain(&e)),
         }
+    };
 
-    }
+    // sessions autosaved before the page was last closed
+    let autosaves = use_resource(|| async { autosave::recent(&IndexedDb::open().await?).await });
+
+    // reopens an autosaved session where it was left off
+    let restore_closure = move |session: Session| {
+        spawn(async move {
+            let snapshot = async { IndexedDb::open().await?.read(session.id).await }.await;
+            let restored = match snapshot {
+                Ok(snapshot) => TeaHandler::from_snapshot(snapshot).map_err(|e| chain(&e)),
+                Err(e) => Err(chain(&e)),
+            };
+            match restored {
+                Ok(th) => open_mission(th, session, scene, tea, loaded, autosave),
+                Err(e) => *load_error.write() = Some(e),
+            }
+        });
+    };
 
     // various signals and setup for main page rendering
     let selected_file_key = use_signal(|| None);
     let save_closure = move |_| tea.write().iter_mut().next().unwrap().event(tea::Event::Save);
     let undo_closure = move |_| tea.write().iter_mut().next().unwrap().event(tea::Event::Undo);
     let redo_closure = move |_| tea.write().iter_mut().next().unwrap().event(tea::Event::Redo);
+    let export_journal_closure = move |_| tea.write().iter_mut().next().unwrap().event(tea::Event::ExportJournal);
+
+    // copies the selection to the system clipboard, to paste here or in another mission
+    let copy_closure = move |_| {
+        let copied = tea.read().as_ref().map(|tea| tea.copy(selected.read().uuids()));
+        match copied {
+            Some(Ok(text)) => { spawn(async move {
+                if let Err(e) = clipboard::write(&text).await {
+                    tea.write().iter_mut().next().unwrap().report(&e);
+                }
+            }); },
+            Some(Err(e)) => tea.write().iter_mut().next().unwrap().report(&e),
+            None => {},
+        }
+    };
+    let paste_closure = move |_| {
+        spawn(async move {
+            match clipboard::read().await {
+                Ok(text) => tea.write().iter_mut().next().unwrap().event(tea::Event::Paste { text }),
+                Err(e) => tea.write().iter_mut().next().unwrap().report(&e),
+            }
+        });
+    };
+
+    // object search, with matches highlighted in the viewport. it's parsed
+    // once per change, and while half typed the last query that parsed is
+    // kept, so its results stay up alongside why the new one doesn't parse
+    let mut search = use_signal(String::new);
+    let mut query = use_signal(Query::default);
+    let mut query_error = use_signal(|| None::<String>);
+    let mut matches = use_signal(Vec::new);
+    use_effect(move || {
+        let text = search.read();
+        let tea = tea.read();
+        let Some(tea) = tea.as_ref() else { return };
+        match tea.query(&text) {
+            Ok(parsed) => {
+                if *query.peek() != parsed { query.set(parsed) }
+                if query_error.peek().is_some() { query_error.set(None) }
+            },
+            Err(e) => query_error.set(Some(chain(&e))),
+        }
+    });
+    use_effect(move || {
+        let query = query.read();
+        let tea = tea.read();
+        let found = match tea.as_ref() {
+            Some(tea) if !search.peek().trim().is_empty() => {
+                let mut found = tea.search(&query);
+                found.sort();
+                found
+            },
+            _ => vec![],
+        };
+        if *matches.peek() != found {
+            matches.set(found);
+        }
+    });
+
+    // journal importer, to replay edits recorded elsewhere
+    let mut journal_import = use_signal(|| File::None);
+    if matches!(*journal_import.read(), File::Loaded{..}) {
+
+        let File::Loaded { data, .. } = journal_import.replace(File::None) else { unreachable!() };
+        tea.write().iter_mut().next().unwrap().event(tea::Event::ImportJournal { buffer: data })
+
+    }
+
+    // mission importer, merging every object of another mission into this one
+    let mut merge_import = use_signal(|| File::None);
+    if matches!(*merge_import.read(), File::Loaded{..}) {
+
+        let File::Loaded { data, .. } = merge_import.replace(File::None) else { unreachable!() };
+        tea.write().iter_mut().next().unwrap().event(tea::Event::Merge { buffer: data })
+
+    }
+
+    // script editor, run over the whole mission as one step
+    let mut script_source = use_signal(String::new);
+    let run_script_closure = move |_| {
+        let source = script_source.read().clone();
+        tea.write().iter_mut().next().unwrap().event(tea::Event::RunScript { source })
+    };
+
+    // whether the viewport shows 3d, the top-down map, or what the player sees
+    let mut view = use_signal(|| View::Perspective);
+    // which lightmaps tiles are lit with, if any
+    let mut lighting = use_signal(|| Preview::Off);
+    // whether rules are drawn as arrows between their entities
+    let mut links_shown = use_signal(|| true);
 
     // resource file importer
     let mut file_import = use_signal(|| File::None);
     if matches!(*file_import.read(), File::Loaded{..}) {
 
         let File::Loaded { data, .. } = file_import.replace(File::None) else { unreachable!() };
-        tea.write().iter_mut().next().unwrap().event(tea::Event::UpdateFile { uuid: *selected.read(), key: (*selected_file_key.read()).clone().unwrap(), buffer: data })
+        if let Some(uuid) = selected.read().single() {
+            tea.write().iter_mut().next().unwrap().event(tea::Event::Edit(tea::Edit::UpdateFile { uuid, key: (*selected_file_key.read()).clone().unwrap(), buffer: data }))
+        }
 
     }
 
     rsx! {
-        Viewport{ scene_signal: scene, selected_signal },
+        Viewport{ signals: ViewportSignals { scene, selected: selected_signal, highlighted: matches, view, links: links_shown, lighting, loaded } },
         {tea.with_mut(|tea| {
             if let Some(tea) = tea {
                 rsx! {
@@ -91,10 +211,27 @@ fn App() -> Element {
                     // left sidebar and buttons
                     div {
                         class: "panel-container left-0 top-0",
+                        input {
+                            class: "text-field w-full",
+                            placeholder: "search, i.e. type:PROP Position Y>1",
+                            value: "{search}",
+                            oninput: move |e| search.set(e.value()),
+                        }
                         div {
                             class: "panel",
-                            for (uuid, name) in tea.display_objects() {
-                                ObjectListing {uuid: uuid, name, selected_signal: selected}
+                            if let Some(e) = &*query_error.read() {
+                                p {
+                                    "{e}"
+                                }
+                            }
+                            for (r#type, objects) in tea.display_object_groups(&query.read()) {
+                                p {
+                                    class: "group-heading",
+                                    "{r#type}"
+                                }
+                                for (uuid, name) in objects {
+                                    ObjectListing {uuid: uuid, name, selected_signal: selected}
+                                }
                             }
                         }
                         div {
@@ -113,13 +250,97 @@ fn App() -> Element {
                                 onclick: redo_closure,
                                 "redo"
                             }
+                            a {
+                                class: "link",
+                                onclick: copy_closure,
+                                "copy"
+                            }
+                            a {
+                                class: "link",
+                                onclick: paste_closure,
+                                "paste"
+                            }
+                            a {
+                                class: "link",
+                                onclick: export_journal_closure,
+                                "export journal"
+                            }
+                            select {
+                                class: "text-field",
+                                onchange: move |e| view.set(match e.value().as_str() {
+                                    "map" => View::Map,
+                                    "player" => View::Player,
+                                    _ => View::Perspective,
+                                }),
+                                option { value: "3d", selected: *view.read() == View::Perspective, "3d view" }
+                                option { value: "map", selected: *view.read() == View::Map, "map view" }
+                                option { value: "player", selected: *view.read() == View::Player, "player view" }
+                            }
+                            select {
+                                class: "text-field",
+                                title: "rough preview: tile models aren't loaded, so each tile's first lightmap is stretched over its floor plan",
+                                onchange: move |e| lighting.set(match e.value().as_str() {
+                                    "set" => Preview::AsSet,
+                                    "light" => Preview::Light,
+                                    "dark" => Preview::Dark,
+                                    _ => Preview::Off,
+                                }),
+                                option { value: "off", selected: *lighting.read() == Preview::Off, "no lighting" }
+                                option { value: "set", selected: *lighting.read() == Preview::AsSet, "floors: lighting sets" }
+                                option { value: "light", selected: *lighting.read() == Preview::Light, "floors: all lights on" }
+                                option { value: "dark", selected: *lighting.read() == Preview::Dark, "floors: all lights off" }
+                            }
+                            a {
+                                class: "link",
+                                onclick: move |_| links_shown.toggle(),
+                                if *links_shown.read() { "hide rule links" } else { "show rule links" }
+                            }
+                        }
+                        div {
+                            p {
+                                class: "link",
+                                "replay journal"
+                            }
+                            FilePicker { signal: journal_import }
+                        }
+                        div {
+                            p {
+                                class: "link",
+                                "merge mission"
+                            }
+                            FilePicker { signal: merge_import }
                         }
+                        div {
+                            textarea {
+                                class: "text-field w-full",
+                                rows: 6,
+                                placeholder: "script",
+                                value: "{script_source}",
+                                oninput: move |e| script_source.set(e.value()),
+                            }
+                            a {
+                                class: "link",
+                                onclick: run_script_closure,
+                                "run script"
+                            }
+                        }
+                        SnapSettings {}
+                        LayersPanel {}
+                        FindReplace {}
+                        DiffPanel {}
                     }
 
-                    // status text
-                    p {
+                    // status text, under any warnings from loading
+                    div {
                         class: "font-mono text-xs link fixed left-0 bottom-0",
-                        "{tea.display_status():?}"
+                        for warning in tea.display_warnings() {
+                            p {
+                                "warning: {warning}"
+                            }
+                        }
+                        p {
+                            "{tea.display_status().unwrap_or_default()}"
+                        }
                     },
                     
                     // right sidebar
@@ -127,12 +348,12 @@ fn App() -> Element {
                         class: "panel-container right-0 top-0",
                         div {
                             class: "panel",
-                            if let Ok(properties) = tea.display_properties(*selected.read()) {
+                            if let Ok(properties) = tea.display_shared_properties(selected.read().uuids()) {
                                 for (name, value) in properties {
-                                    if let Value::Bool(b) = value {
+                                    if let Some(Value::Bool(b)) = value {
                                         PropertyListingBool {name, value: *b, datafile: false}
                                     } else {
-                                        PropertyListingString {name, value: value.to_string(), datafile: false}
+                                        PropertyListingString {name, value: value.map(|v| v.to_string()), datafile: false}
                                     }
                                 }
                             } else {
@@ -140,12 +361,12 @@ fn App() -> Element {
                                     "no properties"
                                 }
                             }
-                            if let Ok(properties) = tea.display_datafile(*selected.read()) {
+                            if let Ok(properties) = tea.display_shared_datafile(selected.read().uuids()) {
                                 for (name, value) in properties {
-                                    if let Value::Bool(b) = value {
+                                    if let Some(Value::Bool(b)) = value {
                                         PropertyListingBool {name, value: *b, datafile: true}
                                     } else {
-                                        PropertyListingString {name, value: value.to_string(), datafile: true}
+                                        PropertyListingString {name, value: value.map(|v| v.to_string()), datafile: true}
                                     }
                                 }
                             } else {
@@ -165,12 +386,12 @@ fn App() -> Element {
                                 rsx! {
                                     if let Some(key) = key_option {
                                         FileBack { file_signal: selected_file_key }
-                                        if let Ok(buf) = tea.display_file(*selected.read(), key) {
+                                        if let Some(Ok(buf)) = selected.read().single().map(|uuid| tea.display_file(uuid, key)) {
                                             FileViewer{ buf: buf.to_owned() }
                                             FilePicker{ signal: file_import }
                                         }
                                     } else {
-                                        if let Ok(files) = tea.display_files(*selected.read()) {
+                                        if let Some(Ok(files)) = selected.read().single().map(|uuid| tea.display_files(uuid)) {
                                             for file_key in files {
                                                 FileListing {file_key, file_signal: selected_file_key}
                                             }
@@ -194,6 +415,35 @@ fn App() -> Element {
                             "import reformatted playmission file [7z x -ooutput ./cluck.playmission && cd output && 7z a cluck.zip ./]"
                         }
                         FilePicker { signal: import }
+                        if let Some(e) = &*load_error.read() {
+                            p {
+                                class: "font-mono text-xs link",
+                                "{e}"
+                            }
+                        }
+                        if recovery.read().is_some() {
+                            a {
+                                class: "link",
+                                onclick: recover_closure,
+                                "load anyway, keeping broken objects as-is"
+                            }
+                        }
+                        if let Some(Ok(sessions)) = &*autosaves.value().read() {
+                            if !sessions.is_empty() {
+                                p {
+                                    class: "link",
+                                    "or recover an autosaved session"
+                                }
+                            }
+                            for session in sessions.clone() {
+                                a {
+                                    class: "link",
+                                    onclick: move |_| restore_closure(session.clone()),
+                                    "{session.name} [{display_time(session.timestamp)}]"
+                                }
+                                br {}
+                            }
+                        }
                     }
                 }
             }
@@ -202,11 +452,69 @@ fn App() -> Element {
 
 }
 
+// renders and starts handling events for a freshly loaded mission
+fn open_mission(mut th: TeaHandler, mut session: Session, mut scene: Signal<Option<Scene>>, mut tea: Signal<Option<TeaHandler>>, mut loaded: Signal<u32>, mut autosave: Signal<Option<Interval>>) {
+
+    th.render((*scene.write()).iter_mut().next().unwrap());
+
+    *tea.write() = Some(th);
+    *loaded.write() += 1;
+
+    // setup key listening
+    let on_keypress = Closure::<dyn FnMut(_)>::new(
+        move |e: web_sys::KeyboardEvent| {
+            (*tea.write()).iter_mut().next().unwrap().event(tea::Event::Keypress{e});
+        }
+    );
+    web_sys::window()
+        .unwrap()
+        .add_event_listener_with_callback("keypress", on_keypress.as_ref().unchecked_ref())
+        .unwrap();
+    on_keypress.forget();
+
+    // keep a copy in browser storage in case the page is closed. the mission
+    // is only peeked at, so that nothing redraws when there is nothing to save.
+    // the last mission's autosave stops as it is replaced, so it can't save
+    // this one under its session
+    let mut saved = 0;
+    let interval = Interval::new(AUTOSAVE_INTERVAL, move || {
+        let Some(th) = &*tea.peek() else { return };
+        if th.display_revision() == saved { return }
+        saved = th.display_revision();
+        let snapshot = match th.snapshot() {
+            Ok(snapshot) => snapshot,
+            Err(e) => return log!(format!("autosave failed: {}", chain(&e))),
+        };
+        session.timestamp = js_sys::Date::now();
+        let session = session.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let stored = async { autosave::store(&IndexedDb::open().await?, &session, &snapshot).await }.await;
+            if let Err(e) = stored {
+                log!(format!("autosave failed: {}", chain(&e)));
+            }
+        });
+    });
+    autosave.set(Some(interval));
+
+}
+
+// formats milliseconds since the unix epoch as local time
+fn display_time(timestamp: f64) -> String {
+    js_sys::Date::new(&timestamp.into())
+        .to_locale_string("default", &wasm_bindgen::JsValue::UNDEFINED)
+        .into()
+}
+
 #[component]
-fn ObjectListing(uuid: Uuid, name: String, selected_signal: Signal<Uuid>) -> Element {
+fn ObjectListing(uuid: Uuid, name: String, selected_signal: Signal<Selection>) -> Element {
+    let class = if selected_signal.read().contains(&uuid) { "selected" } else { "" };
     rsx! {
         a {
-            onclick: move |_| *selected_signal.write() = uuid,
+            class,
+            onclick: move |e: MouseEvent| {
+                let additive = e.modifiers().ctrl() || e.modifiers().meta();
+                selected_signal.write().click(uuid, additive);
+            },
             "{name}"
         }
         br {}
@@ -217,7 +525,7 @@ fn ObjectListing(uuid: Uuid, name: String, selected_signal: Signal<Uuid>) -> Ele
 fn PropertyListingBool(name: String, value: bool, datafile: bool) -> Element {
     log!(name.clone());
     let mut tea = use_context::<Signal<Option<TeaHandler>>>();
-    let selected = use_context::<Signal<Uuid>>();
+    let selected = use_context::<Signal<Selection>>();
     let on_change = if datafile {
         datafile_update_closure(name.clone(), tea, selected)
     } else {
@@ -241,10 +549,10 @@ fn PropertyListingBool(name: String, value: bool, datafile: bool) -> Element {
 }
 
 #[component]
-fn PropertyListingString(name: String, value: String, datafile: bool) -> Element {
+fn PropertyListingString(name: String, value: Option<String>, datafile: bool) -> Element {
     log!(name.clone());
     let mut tea = use_context::<Signal<Option<TeaHandler>>>();
-    let selected = use_context::<Signal<Uuid>>();
+    let selected = use_context::<Signal<Selection>>();
     let on_change = if datafile {
         datafile_update_closure(name.clone(), tea, selected)
     } else {
@@ -255,7 +563,9 @@ fn PropertyListingString(name: String, value: String, datafile: bool) -> Element
             class: "text-field",
             r#type: "text",
             name: name.clone(),
-            value: value,
+            // none when the selected objects disagree on the value
+            placeholder: if value.is_none() { "mixed" } else { "" },
+            value: value.unwrap_or_default(),
             onchange: on_change,
         }
         label {
@@ -267,31 +577,35 @@ fn PropertyListingString(name: String, value: String, datafile: bool) -> Element
     }
 }
 
-fn property_update_closure(name: String, mut tea: Signal<Option<TeaHandler>>, selected: Signal<Uuid>) -> Box<dyn FnMut(Event<FormData>)> {
+fn property_update_closure(name: String, mut tea: Signal<Option<TeaHandler>>, selected: Signal<Selection>) -> Box<dyn FnMut(Event<FormData>)> {
 
     let cls = move |js_event: Event<FormData>| {
-        tea.write().iter_mut().next().unwrap().event(
-            tea::Event::UpdateProperty{
-                uuid: *selected.read(),
+        // edits to many objects undo together
+        let edits = selected.read().uuids().iter()
+            .map(|uuid| tea::Edit::UpdateProperty{
+                uuid: *uuid,
                 key: name.clone(),
                 value: js_event.value()
-            }
-        )
+            })
+            .collect();
+        tea.write().iter_mut().next().unwrap().event(tea::Event::Edit(tea::Edit::Batch{edits}))
     };
     Box::new(cls)
 
 }
 
-fn datafile_update_closure(name: String, mut tea: Signal<Option<TeaHandler>>, selected: Signal<Uuid>) -> Box<dyn FnMut(Event<FormData>)> {
+fn datafile_update_closure(name: String, mut tea: Signal<Option<TeaHandler>>, selected: Signal<Selection>) -> Box<dyn FnMut(Event<FormData>)> {
 
     let cls = move |js_event: Event<FormData>| {
-        tea.write().iter_mut().next().unwrap().event(
-            tea::Event::UpdateDatafile{
-                uuid: *selected.read(),
+        // edits to many objects undo together
+        let edits = selected.read().uuids().iter()
+            .map(|uuid| tea::Edit::UpdateDatafile{
+                uuid: *uuid,
                 key: name.clone(),
                 value: js_event.value()
-            }
-        )
+            })
+            .collect();
+        tea.write().iter_mut().next().unwrap().event(tea::Event::Edit(tea::Edit::Batch{edits}))
     };
     Box::new(cls)
 
@@ -0,0 +1,124 @@
+// the top-down map of a mission. everything with a place on the floor gets
+// an icon coloured by its type, along with the blanking plates the mission
+// closes off doors that lead to no other tile with
+
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use crate::placement::position;
+use crate::playmission::{BlankingPlate, BoundingBox, Location, Object, Side};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IconKind {
+    Tile,
+    BlankingPlate,
+    Prop,
+    Character,
+    Pickup,
+    Trigger,
+    PlayerStart,
+    Other,
+}
+
+// an icon, drawn flat on the floor over an object. blanking plates belong
+// to their tile, so move along with it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Icon {
+    pub uuid: Uuid,
+    pub kind: IconKind,
+    // from the object's origin, across x and z
+    pub offset: (f32, f32),
+    // across x and z
+    pub size: (f32, f32),
+}
+
+impl IconKind {
+
+    // get by the type named in the mission file
+    fn of(r#type: &str) -> Self {
+        match r#type {
+            "LOCATION" => IconKind::Tile,
+            "PROP" | "ACTIVE_PROP" => IconKind::Prop,
+            "CHARACTER" => IconKind::Character,
+            "PICKUP" => IconKind::Pickup,
+            "TRIGGER" => IconKind::Trigger,
+            "PLAYER" => IconKind::PlayerStart,
+            _ => IconKind::Other,
+        }
+    }
+
+    // get as r, g, b
+    pub fn color(&self) -> (f32, f32, f32) {
+        match self {
+            IconKind::Tile => (0.6, 0.6, 0.6),
+            IconKind::BlankingPlate => (0.4, 0.2, 0.1),
+            IconKind::Prop => (0.2, 0.5, 1.0),
+            IconKind::Character => (1.0, 0.5, 0.0),
+            IconKind::Pickup => (1.0, 0.9, 0.0),
+            IconKind::Trigger => (0.8, 0.2, 0.8),
+            IconKind::PlayerStart => (0.0, 1.0, 0.3),
+            IconKind::Other => (1.0, 1.0, 1.0),
+        }
+    }
+
+}
+
+// get icons for every object with a position, and for every blanking plate
+pub fn icons(objects: &HashMap<Uuid, Object>, plates: &[BlankingPlate]) -> Vec<Icon> {
+
+    let tiles: Vec<(Uuid, BoundingBox)> = objects.iter()
+        .filter(|(_, o)| o.r#type() == "LOCATION")
+        .filter_map(|(uuid, o)| Some((*uuid, BoundingBox::of(o.properties())?)))
+        .collect();
+
+    let mut icons: Vec<Icon> = objects.iter()
+        .filter(|(_, o)| position(o).is_some())
+        .map(|(uuid, o)| {
+            let kind = IconKind::of(o.r#type());
+            let size = match kind {
+                IconKind::Tile => BoundingBox::of(o.properties()).map_or((1.0, 1.0), |bbox| bbox.size()),
+                _ => (0.6, 0.6),
+            };
+            Icon { uuid: *uuid, kind, offset: (0.0, 0.0), size }
+        })
+        .collect();
+
+    // plates are kept for the whole mission, so belong to whichever tile they stand on the edge of
+    for plate in plates {
+        let on_edge = |(_, bbox): &&(Uuid, BoundingBox)| Side::ALL.iter().any(|side| side.holds(bbox, plate.position));
+        let Some((uuid, bbox)) = tiles.iter().find(on_edge) else { continue };
+        let centre = ((bbox.min[0] + bbox.max[0]) / 2.0, (bbox.min[2] + bbox.max[2]) / 2.0);
+        let offset = (plate.position[0] - centre.0, plate.position[2] - centre.1);
+        // plates lie across the way they face
+        let size = if plate.facing.0.abs() > plate.facing.1.abs() { (0.2, Location::DOOR_WIDTH) } else { (Location::DOOR_WIDTH, 0.2) };
+        icons.push(Icon { uuid: *uuid, kind: IconKind::BlankingPlate, offset, size });
+    }
+
+    icons
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{testcase, uuid_of};
+
+    #[test]
+    fn icons_show_kept_plates_and_the_player() {
+        let th = testcase();
+        let icons = th.display_map();
+
+        // the tiles meet where the three door tile's north side is the two door tile's south side,
+        // so only the plates the mission keeps are drawn
+        let plates = |uuid| icons.iter()
+            .filter(|icon| icon.uuid == uuid && icon.kind == IconKind::BlankingPlate)
+            .map(|icon| icon.offset)
+            .collect::<Vec<_>>();
+        assert_eq!(vec![(3.0, 0.0), (-3.0, 0.0)], plates(uuid_of(&th, "Baronial_3Door")));
+        assert_eq!(vec![(0.0, 3.0)], plates(uuid_of(&th, "Baronial_2Door_Out")));
+
+        let player = icons.iter().find(|icon| icon.uuid == uuid_of(&th, "Player")).unwrap();
+        assert_eq!(IconKind::PlayerStart, player.kind);
+    }
+}
@@ -0,0 +1,327 @@
+// merges the objects of another mission into this one. names and files
+// that would clash are numbered, identical shared files are kept once,
+// and rules are pointed at the renamed objects they connected
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::Cursor;
+
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::playmission::{error::{chain, PlaymissionError}, unique_file_name, unique_name, MissionObject, Object, Properties, Value};
+use crate::tea::Edit;
+
+// rule properties that may name objects, to follow them when renamed
+const REFERENCES: [&str; 4] = ["Cause Entity", "Effect Entity", "Effect Method Parameter", "Effect Method Parameter 2"];
+
+// objects from elsewhere, as fragments with the files they own,
+// along with the shared files they need
+pub(crate) struct Incoming {
+    pub mission: Option<String>,
+    pub objects: Vec<(String, BTreeMap<String, Vec<u8>>)>,
+    pub files: HashMap<String, Vec<u8>>,
+}
+
+// what to do with an incoming shared file where this mission
+// already has a different file under the same name
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Clash {
+    Keep,
+    Rename,
+}
+
+// what importing would do, along with anything renamed or left out on the way
+pub struct Imported {
+    pub edits: Vec<Edit>,
+    pub uuids: Vec<Uuid>,
+    pub notes: Vec<String>,
+}
+
+// gets the edits that merge every object of another mission into this one
+pub fn merge(buf: &[u8], mission: &MissionObject, objects: &HashMap<Uuid, Object>) -> Result<Imported> {
+
+    let (other, other_objects, warnings) = MissionObject::deserialize_lenient(Cursor::new(buf))
+        .map_err(|source| MergeError::Read { source })?;
+    let incoming = Incoming {
+        mission: name_in(other.properties(), "Name"),
+        objects: other.order(&other_objects).iter()
+            .map(|uuid| {
+                let object = &other_objects[uuid];
+                object.to_fragment().map_err(|source| MergeError::Pack { name: label(object), source })
+            })
+            .collect::<Result<_>>()?,
+        files: other.files().clone(),
+    };
+
+    let mut imported = import(incoming, mission, objects, Clash::Rename)?;
+    let warnings = warnings.iter().map(|e| format!("warning: {}", chain(e)));
+    imported.notes.splice(0..0, warnings);
+    Ok(imported)
+
+}
+
+// gets the edits that add objects from elsewhere. objects get new uuids,
+// and are loaded apart from this mission so that their files can't clash
+// with its files until they have been renamed
+pub(crate) fn import(mut incoming: Incoming, mission: &MissionObject, objects: &HashMap<Uuid, Object>, clash: Clash) -> Result<Imported> {
+
+    let mut edits = vec![];
+    let mut notes = vec![];
+
+    let mut file_names: HashSet<String> = mission.files().keys().cloned()
+        .chain(objects.values().flat_map(|o| o.datafile_name().map(String::from).into_iter().chain(o.files().keys().cloned())))
+        .chain(incoming.files.keys().cloned())
+        .collect();
+
+    // different shared files under names this mission already has are renamed,
+    // along with everything naming them. renaming inside other files can make
+    // those differ in turn, so this goes on until nothing clashes
+    let differs = |incoming: &Incoming, key: &str| mission.files().get(key).is_some_and(|existing| *existing != incoming.files[key]);
+    if clash == Clash::Rename {
+        loop {
+            let mut clashing: Vec<&String> = incoming.files.keys().filter(|k| differs(&incoming, k)).collect();
+            clashing.sort();
+            let Some(key) = clashing.first().map(|k| k.to_string()) else { break };
+            let unique = unique_file_name(&key, |n| file_names.contains(n));
+            file_names.insert(unique.clone());
+            notes.push(format!("renamed file {key} to {unique}"));
+            let buffer = incoming.files.remove(&key).unwrap();
+            incoming.files.insert(unique.clone(), buffer);
+            rename_everywhere(&mut incoming, &key, &unique);
+        }
+    }
+
+    // identical shared files are kept once, and otherwise this mission's own are kept
+    let mut shared: Vec<String> = incoming.files.keys().cloned().collect();
+    shared.sort();
+    let mut identical = 0;
+    let mut scratch_files = vec![];
+    for key in shared {
+        let buffer = incoming.files.remove(&key).unwrap();
+        match mission.files().get(&key) {
+            None => {
+                edits.push(Edit::UpdateMissionFile { key: key.clone(), buffer: Some(buffer.clone()) });
+                scratch_files.push((key, buffer));
+            },
+            Some(existing) if *existing == buffer => {
+                identical += 1;
+                scratch_files.push((key, buffer));
+            },
+            Some(existing) => {
+                notes.push(format!("kept this mission's own {key}"));
+                scratch_files.push((key, existing.clone()));
+            },
+        }
+    }
+    if identical > 0 {
+        notes.push(format!("kept one copy of {identical} identical files"));
+    }
+
+    let mut scratch = MissionObject::new(Properties::new(), scratch_files.into_iter().collect());
+    let mut names: HashSet<String> = objects.values().filter_map(Object::name).collect();
+
+    let mut renamed = HashMap::new();
+    if let (Some(from), Some(to)) = (incoming.mission, name_in(mission.properties(), "Name")) {
+        renamed.insert(from, to);
+    }
+
+    let mut added = vec![];
+    for (markup, files) in incoming.objects {
+
+        let mut object = scratch.load_fragment(&markup, files, true)
+            .map_err(|source| MergeError::Load { source })?
+            .with_uuid(Uuid::new_v4());
+
+        if let Some(name) = object.name() {
+            let unique = unique_name(&name, |n| names.contains(n));
+            if unique != name {
+                object.set_property("Name", unique.clone()).map_err(|source| MergeError::Rename { name: name.clone(), source })?;
+                notes.push(format!("renamed {name} to {unique}"));
+            }
+            names.insert(unique.clone());
+            renamed.insert(name, unique);
+        }
+        if let Some(datafile_name) = object.datafile_name().map(String::from) {
+            let unique = unique_file_name(&datafile_name, |n| file_names.contains(n));
+            file_names.insert(unique.clone());
+            object = object.with_datafile_name(unique);
+        }
+        let owned: Vec<String> = object.files().keys().cloned().collect();
+        for file_name in owned {
+            let unique = unique_file_name(&file_name, |n| file_names.contains(n));
+            if unique != file_name {
+                object.rename_file(&file_name, unique.clone()).map_err(|source| MergeError::Rename { name: file_name.clone(), source })?;
+            }
+            file_names.insert(unique);
+        }
+        added.push(object);
+
+    }
+
+    for object in added.iter_mut().filter(|o| o.r#type() == "RULE") {
+        for key in REFERENCES {
+            let Some(new) = name_in(object.properties(), key).and_then(|old| renamed.get(&old)) else { continue };
+            object.set_property(key, new.clone()).map_err(|source| MergeError::Relink { name: label(object), source })?;
+        }
+    }
+
+    let mut uuids = vec![];
+    for object in added {
+        let (markup, files) = object.to_fragment().map_err(|source| MergeError::Pack { name: label(&object), source })?;
+        uuids.push(*object.uuid());
+        edits.push(Edit::InsertObject { uuid: *object.uuid(), markup, files, lenient: true });
+    }
+    Ok(Imported { edits, uuids, notes })
+
+}
+
+// renames a shared file wherever incoming objects or files name it, i.e.
+// the mesh in a datafile, or a texture in a mesh
+fn rename_everywhere(incoming: &mut Incoming, old: &str, new: &str) {
+
+    for (markup, files) in incoming.objects.iter_mut() {
+        *markup = rename_in(markup, old, new);
+        for buffer in files.values_mut() {
+            rename_in_buffer(buffer, old, new);
+        }
+    }
+    for buffer in incoming.files.values_mut() {
+        rename_in_buffer(buffer, old, new);
+    }
+
+}
+
+// as rename_in, for files that are text
+fn rename_in_buffer(buffer: &mut Vec<u8>, old: &str, new: &str) {
+    let Ok(text) = std::str::from_utf8(buffer) else { return };
+    let renamed = rename_in(text, old, new);
+    if renamed != text {
+        *buffer = renamed.into_bytes();
+    }
+}
+
+// replaces a file name in text where it stands alone, so "base.tga"
+// isn't found inside "blank_base.tga"
+fn rename_in(text: &str, old: &str, new: &str) -> String {
+
+    let before = |c: char| !(c.is_alphanumeric() || matches!(c, '_' | '-' | '.'));
+    let after = |c: char| c.is_whitespace() || matches!(c, '<' | '"' | '\'' | ',' | ';');
+
+    let mut renamed = String::with_capacity(text.len());
+    let mut last = 0;
+    for (at, _) in text.match_indices(old) {
+        let alone = text[..at].chars().next_back().is_none_or(before)
+            && text[at + old.len()..].chars().next().is_none_or(after);
+        if alone {
+            renamed.push_str(&text[last..at]);
+            renamed.push_str(new);
+            last = at + old.len();
+        }
+    }
+    renamed.push_str(&text[last..]);
+    renamed
+
+}
+
+// get an object's name for errors, or its type if it has none
+fn label(object: &Object) -> String {
+    object.name().unwrap_or_else(|| format!("unnamed {}", object.r#type()))
+}
+
+// get a text value that may be a name
+pub(crate) fn name_in(properties: &Properties, k: &str) -> Option<String> {
+    match properties.get_value(k) {
+        Ok(Value::String(s)) => Some(s.clone()),
+        _ => None,
+    }
+}
+
+pub type Result<T> = std::result::Result<T, MergeError>;
+
+#[derive(Debug, Error)]
+pub enum MergeError {
+    #[error("failed reading the mission to merge")]
+    Read {
+        source: PlaymissionError,
+    },
+    #[error("failed packing up {name} to merge")]
+    Pack {
+        name: String,
+        source: PlaymissionError,
+    },
+    #[error("failed loading an object to merge")]
+    Load {
+        source: PlaymissionError,
+    },
+    #[error("failed renaming {name} to merge it")]
+    Rename {
+        name: String,
+        source: PlaymissionError,
+    },
+    #[error("failed pointing rule {name} at renamed objects")]
+    Relink {
+        name: String,
+        source: PlaymissionError,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tea::{Event, TeaHandler};
+    use crate::utils::{get_test_dir, uuid_of, zip_files};
+
+    #[test]
+    fn file_names_stand_alone() {
+        assert_eq!("map_Kd base 2.tga\n", rename_in("map_Kd base.tga\n", "base.tga", "base 2.tga"));
+        assert_eq!("<A>blank_base.tga</A>", rename_in("<A>blank_base.tga</A>", "base.tga", "base 2.tga"));
+        assert_eq!("base 2.tga", rename_in("base.tga", "base.tga", "base 2.tga"));
+    }
+
+    #[test]
+    fn merge_renames_clashes() {
+        let files = get_test_dir("testcase");
+        let mut th = TeaHandler::from_buffer_lenient(zip_files(files.clone())).unwrap();
+        let before = th.display_objects().len();
+
+        // the other mission has its own mesh under the same name
+        let other: Vec<(String, Vec<u8>)> = files.into_iter()
+            .map(|(k, v)| if k == "ETelevision.obj" { (k, b"# another television\n".to_vec()) } else { (k, v) })
+            .collect();
+        th.event(Event::Merge { buffer: zip_files(other) });
+
+        assert_eq!(before * 2, th.display_objects().len());
+        let status = th.display_status().unwrap();
+        assert!(status.contains("renamed file ETelevision.obj to ETelevision 2.obj"), "{status}");
+        assert!(status.contains("renamed Rule 1 to Rule 2"), "{status}");
+
+        let datafile = th.display_datafile(uuid_of(&th, "Television 2")).unwrap();
+        assert!(datafile.iter().any(|(k, v)| *k == "Object" && v.to_string() == "ETelevision 2.obj"));
+        let rule = th.display_properties(uuid_of(&th, "Rule 2")).unwrap();
+        assert!(rule.iter().any(|(k, v)| k == "Effect Entity" && v.to_string() == "Television 2"));
+
+        th.event(Event::Undo);
+        assert_eq!(before, th.display_objects().len());
+    }
+
+    #[test]
+    fn merge_renames_objects_that_failed_to_load() {
+        let files = get_test_dir("testcase");
+        let mut th = TeaHandler::from_buffer_lenient(zip_files(files.clone())).unwrap();
+
+        // without its datafile, the other television can only be kept as markup
+        let other: Vec<(String, Vec<u8>)> = files.into_iter().filter(|(k, _)| k != "etelevision.aprop").collect();
+        th.event(Event::Merge { buffer: zip_files(other) });
+        let status = th.display_status().unwrap();
+        assert!(status.contains("renamed Television to Television 2"), "{status}");
+        let groups = th.display_object_groups(&crate::query::Query::default());
+        let fragments = groups.iter().find(|(r#type, _)| *r#type == "FRAGMENT").unwrap();
+        assert!(fragments.1.iter().any(|(_, name)| name == "Television 2"));
+
+        // and keeps its new name once saved
+        let saved = TeaHandler::from_buffer_lenient(th.serialize().unwrap()).unwrap();
+        let names: Vec<String> = saved.display_objects().into_iter().map(|(_, name)| name).collect();
+        assert_eq!(1, names.iter().filter(|name| *name == "Television").count());
+        assert!(names.contains(&"Television 2".to_string()));
+    }
+}
@@ -0,0 +1,247 @@
+// snaps and clamps objects as they are placed. props can snap to a grid and
+// to rotation steps, and be kept inside the tile they sit in, while tiles
+// snap to the tile grid so that their doors line up with their neighbours,
+// taking the blanking plates on their sides along with them
+
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use crate::playmission::{format_blanking_plates, format_coordinates, format_orientation, format_position, parse_coordinates, BlankingPlate, BoundingBox, Object, Properties, Side, Value};
+
+// the size of the smallest tiles, which every tile is a multiple of
+const TILE_GRID: f32 = 6.0;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Snap {
+    // spacing of the grid props snap to across the floor, if at all
+    pub grid: Option<f32>,
+    // degrees turned by each rotation step, i.e. 90 or 45, if at all
+    pub rotation: Option<f32>,
+    // keep props inside the tile they are in
+    pub clamp_to_tiles: bool,
+    // keep tiles on the tile grid
+    pub tiles: bool,
+}
+
+// where an object is being put, as x, y, z and w, x, y, z
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Placement {
+    pub position: [f32; 3],
+    pub orientation: Option<[f32; 4]>,
+}
+
+// where an object ends up, with the property values that put it there
+// and any values of the mission itself that go along with it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Placed {
+    pub placement: Placement,
+    pub values: Vec<(&'static str, String)>,
+    pub mission: Vec<(&'static str, String)>,
+}
+
+// get the position of an object. tiles have no height, so are on the floor,
+// and the player is wherever they start
+pub fn position(object: &Object) -> Option<[f32; 3]> {
+    let properties = object.properties();
+    if object.r#type() == "PLAYER" {
+        let Ok(Value::String(start)) = properties.get_value("Start Position") else { return None };
+        return parse_coordinates(start)
+    }
+    let x = properties.get_float("Position X").ok()?;
+    let z = properties.get_float("Position Z").ok()?;
+    Some([x, properties.get_float("Position Y").unwrap_or(0.0), z])
+}
+
+// snaps and clamps a placement of an object, which tiles are snapped to
+// the tile grid, and everything else by the snap settings
+pub fn place(uuid: Uuid, objects: &HashMap<Uuid, Object>, mission: &Properties, placement: Placement, snap: &Snap) -> Option<Placed> {
+
+    let object = objects.get(&uuid)?;
+    let current = position(object)?;
+    let has = |k: &str| object.properties().contains_key(k);
+
+    if object.r#type() == "LOCATION" {
+        let bbox = BoundingBox::of(object.properties())?;
+        let [mut x, _, mut z] = placement.position;
+        if snap.tiles {
+            let (width, depth) = bbox.size();
+            (x, z) = (snap_tile(x, width), snap_tile(z, depth));
+        }
+        let (dx, dz) = (x - current[0], z - current[2]);
+        let moved = bbox.translated(dx, dz);
+        let values = vec![
+            ("Position X", x.to_string()),
+            ("Position Z", z.to_string()),
+            ("Bounding Box Min", format_coordinates(moved.min)),
+            ("Bounding Box Max", format_coordinates(moved.max)),
+        ];
+        // tiles can't be turned, so are kept square
+        let placement = Placement { position: [x, current[1], z], orientation: Some([1.0, 0.0, 0.0, 0.0]) };
+        return Some(Placed { placement, values, mission: move_plates(mission, &bbox, dx, dz) })
+    }
+
+    let [mut x, y, mut z] = placement.position;
+    if let Some(grid) = snap.grid.filter(|g| *g > 0.0) {
+        (x, z) = ((x / grid).round() * grid, (z / grid).round() * grid);
+    }
+    let mut position = [x, y, z];
+    if snap.clamp_to_tiles {
+        let tile = objects.values()
+            .filter(|o| o.r#type() == "LOCATION")
+            .filter_map(|o| BoundingBox::of(o.properties()))
+            .find(|bbox| bbox.contains(current));
+        if let Some(tile) = tile {
+            position = tile.clamp(position);
+        }
+    }
+    let orientation = placement.orientation
+        .map(|o| snap.rotation.filter(|r| *r > 0.0).map_or(o, |step| snap_rotation(o, step.to_radians())));
+
+    // the player is placed by where they start, not where they were saved
+    let player = object.r#type() == "PLAYER";
+    let mut values = if player {
+        vec![("Start Position", format_position(position))]
+    } else {
+        vec![
+            ("Position X", position[0].to_string()),
+            ("Position Y", position[1].to_string()),
+            ("Position Z", position[2].to_string()),
+        ]
+    };
+    if let Some(orientation) = orientation {
+        values.push((if player { "Start Orientation" } else { "Orientation" }, format_orientation(orientation)));
+    }
+    values.retain(|(k, _)| has(k));
+    Some(Placed { placement: Placement { position, orientation }, values, mission: vec![] })
+
+}
+
+// get the mission's blanking plates with those on the sides of a tile moved
+// along with it, if any are
+fn move_plates(mission: &Properties, bbox: &BoundingBox, dx: f32, dz: f32) -> Vec<(&'static str, String)> {
+
+    let Some(mut plates) = BlankingPlate::of(mission) else { return vec![] };
+    let mut moved = false;
+    for plate in plates.iter_mut().filter(|plate| Side::ALL.iter().any(|side| side.holds(bbox, plate.position))) {
+        plate.position[0] += dx;
+        plate.position[2] += dz;
+        moved = true;
+    }
+    if !moved || (dx == 0.0 && dz == 0.0) {
+        return vec![]
+    }
+    vec![(BlankingPlate::KEY, format_blanking_plates(&plates))]
+
+}
+
+// get the nearest centre of a tile this wide whose edges are on the tile grid
+fn snap_tile(centre: f32, width: f32) -> f32 {
+    let edge = centre - width / 2.0;
+    (edge / TILE_GRID).round() * TILE_GRID + width / 2.0
+}
+
+// turns about each axis to the nearest step, working in three.js's XYZ euler angles
+fn snap_rotation([w, x, y, z]: [f32; 4], step: f32) -> [f32; 4] {
+
+    let (m11, m12, m13) = (1.0 - 2.0 * (y * y + z * z), 2.0 * (x * y - z * w), 2.0 * (x * z + y * w));
+    let (m22, m23) = (1.0 - 2.0 * (x * x + z * z), 2.0 * (y * z - x * w));
+    let (m32, m33) = (2.0 * (y * z + x * w), 1.0 - 2.0 * (x * x + y * y));
+
+    let ey = m13.clamp(-1.0, 1.0).asin();
+    let (ex, ez) = if m13.abs() < 0.9999999 {
+        ((-m23).atan2(m33), (-m12).atan2(m11))
+    } else {
+        (m32.atan2(m22), 0.0)
+    };
+    let [ex, ey, ez] = [ex, ey, ez].map(|angle| (angle / step).round() * step);
+
+    let (c1, c2, c3) = ((ex / 2.0).cos(), (ey / 2.0).cos(), (ez / 2.0).cos());
+    let (s1, s2, s3) = ((ex / 2.0).sin(), (ey / 2.0).sin(), (ez / 2.0).sin());
+    [
+        c1 * c2 * c3 - s1 * s2 * s3,
+        s1 * c2 * c3 + c1 * s2 * s3,
+        c1 * s2 * c3 - s1 * c2 * s3,
+        c1 * c2 * s3 + s1 * s2 * c3,
+    ]
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::IconKind;
+    use crate::tea::{Edit, Event};
+    use crate::utils::{testcase, uuid_of};
+
+    fn value<'a>(placed: &'a Placed, k: &str) -> &'a str {
+        &placed.values.iter().find(|(key, _)| *key == k).unwrap().1
+    }
+
+    #[test]
+    fn props_snap_and_stay_in_their_tile() {
+        let th = testcase();
+        let suitcase = uuid_of(&th, "Wheeled Suitcase");
+        let snap = Snap { grid: Some(0.5), rotation: Some(90.0), clamp_to_tiles: true, tiles: true };
+
+        // turned a little past a quarter turn about y, as w, x, y, z
+        let turned = [(0.55f32).cos(), 0.0, (0.55f32).sin(), 0.0];
+        let placed = th.display_placement(suitcase, Placement { position: [-31.2, 0.7, 27.3], orientation: Some(turned) }, &snap).unwrap();
+        assert_eq!([-31.0, 0.7, 27.5], placed.placement.position);
+        let [w, x, y, z] = placed.placement.orientation.unwrap();
+        let half = std::f32::consts::FRAC_1_SQRT_2;
+        assert!((w - half).abs() < 1e-5 && x.abs() < 1e-5 && (y - half).abs() < 1e-5 && z.abs() < 1e-5);
+
+        // the suitcase is in Baronial_3Door, which runs from -36 to -30 across x
+        let placed = th.display_placement(suitcase, Placement { position: [-20.0, 0.5, 27.0], orientation: None }, &snap).unwrap();
+        assert_eq!("-30", value(&placed, "Position X"));
+    }
+
+    #[test]
+    fn player_moves_by_start_position() {
+        let th = testcase();
+        let player = uuid_of(&th, "Player");
+        let quarter = [std::f32::consts::FRAC_1_SQRT_2, 0.0, std::f32::consts::FRAC_1_SQRT_2, 0.0];
+        let placed = th.display_placement(player, Placement { position: [-31.5, 0.8, 30.25], orientation: Some(quarter) }, &Snap::default()).unwrap();
+        assert_eq!(vec!["Start Position", "Start Orientation"], placed.values.iter().map(|(k, _)| *k).collect::<Vec<_>>());
+        assert_eq!("-31.5, 0.8, 30.25", value(&placed, "Start Position"));
+        assert_eq!("0.70710677, 0.0, 0.70710677, 0.0", value(&placed, "Start Orientation"));
+    }
+
+    #[test]
+    fn tiles_snap_to_tile_grid() {
+        let th = testcase();
+        let tile = uuid_of(&th, "Baronial_3Door");
+        let placed = th.display_placement(tile, Placement { position: [-25.0, 0.0, 26.0], orientation: None }, &Snap { tiles: true, ..Default::default() }).unwrap();
+        assert_eq!("-27", value(&placed, "Position X"));
+        assert_eq!("27", value(&placed, "Position Z"));
+        assert_eq!("-30.0, 340282346638528860000000000000000000000.0, 24.0", value(&placed, "Bounding Box Min"));
+        assert_eq!("-24.0, -340282346638528860000000000000000000000.0, 30.0", value(&placed, "Bounding Box Max"));
+    }
+    #[test]
+    fn plates_move_with_their_tile() {
+        let mut th = testcase();
+        let tile = uuid_of(&th, "Baronial_3Door");
+        let properties = th.display_properties(tile).unwrap();
+        let at = |k: &str| properties.iter().find(|(n, _)| n == k).unwrap().1.number().unwrap();
+        let position = [at("Position X") + 12.0, 0.0, at("Position Z") - 12.0];
+        let placed = th.display_placement(tile, Placement { position, orientation: None }, &Snap { tiles: true, ..Default::default() }).unwrap();
+        assert_eq!(vec![BlankingPlate::KEY], placed.mission.iter().map(|(k, _)| *k).collect::<Vec<_>>());
+
+        for (key, value) in placed.values {
+            th.event(Event::Edit(Edit::UpdateProperty { uuid: tile, key: key.into(), value }));
+        }
+        for (key, value) in placed.mission {
+            th.event(Event::Edit(Edit::UpdateMission { key: key.into(), value }));
+        }
+
+        // away from the other tile, only the plated sides are left with doors
+        let plates: Vec<(f32, f32)> = th.display_map().into_iter()
+            .filter(|icon| icon.uuid == tile && icon.kind == IconKind::BlankingPlate)
+            .map(|icon| icon.offset)
+            .collect();
+        assert_eq!(vec![(3.0, 0.0), (-3.0, 0.0)], plates);
+        let doors = th.display_doors();
+        assert_eq!(vec![Side::East, Side::West], doors.iter().find(|(uuid, _)| *uuid == tile).unwrap().1);
+    }
+}
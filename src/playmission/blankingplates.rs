@@ -0,0 +1,111 @@
+// missions close off doors that lead to no other tile with blanking plates.
+// they are kept together in BLANKINGPLATES as zlib compressed base64: a count,
+// then for each plate its model, where it stands and which way it faces into
+// its tile, followed by two bytes that aren't understood yet
+
+use std::io::{Read, Write};
+
+use base64::prelude::*;
+use flate2::{read::ZlibDecoder, write::ZlibEncoder, Compression};
+
+use super::{Properties, Value};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlankingPlate {
+    pub model: String,
+    pub position: [f32; 3],
+    // into the tile, across x and z
+    pub facing: (f32, f32),
+    // not understood yet, so written back as they were read
+    pub unknown: [u8; 2],
+}
+
+impl BlankingPlate {
+
+    // the mission key they are kept under
+    pub const KEY: &'static str = "Blanking Plates";
+
+    // get every plate from the properties of the mission itself
+    pub fn of(properties: &Properties) -> Option<Vec<Self>> {
+        match properties.get_value(Self::KEY) {
+            Ok(Value::String(s)) => parse_blanking_plates(s),
+            _ => None,
+        }
+    }
+
+}
+
+// get plates from their encoded form, with none if it can't be read
+pub fn parse_blanking_plates(s: &str) -> Option<Vec<BlankingPlate>> {
+
+    let compressed = BASE64_STANDARD_NO_PAD.decode(s.trim().trim_end_matches('=')).ok()?;
+    let mut buf = vec![];
+    ZlibDecoder::new(compressed.as_slice()).read_to_end(&mut buf).ok()?;
+    let bytes = &mut buf.as_slice();
+
+    let count = u32::from_le_bytes(take(bytes)?);
+    (0..count)
+        .map(|_| {
+            let [length] = take(bytes)?;
+            let (model, rest) = bytes.split_at_checked(length.into())?;
+            let model = String::from_utf8_lossy(model).into_owned();
+            *bytes = rest;
+            let [x, y, z, facing_x, facing_z] = [(); 5].map(|_| take(bytes).map(f32::from_le_bytes));
+            let unknown = take(bytes)?;
+            Some(BlankingPlate { model, position: [x?, y?, z?], facing: (facing_x?, facing_z?), unknown })
+        })
+        .collect()
+
+}
+
+// write plates in their encoded form, as missions keep them
+pub fn format_blanking_plates(plates: &[BlankingPlate]) -> String {
+
+    let mut buf = (plates.len() as u32).to_le_bytes().to_vec();
+    for plate in plates {
+        // names are only ever short, but longer ones can't be written
+        let model = &plate.model.as_bytes()[..plate.model.len().min(u8::MAX.into())];
+        buf.push(model.len() as u8);
+        buf.extend_from_slice(model);
+        let [x, y, z] = plate.position;
+        for f in [x, y, z, plate.facing.0, plate.facing.1] {
+            buf.extend_from_slice(&f.to_le_bytes());
+        }
+        buf.extend_from_slice(&plate.unknown);
+    }
+
+    // as compressed by missionmaker, which never fails writing to memory
+    let mut encoder = ZlibEncoder::new(vec![], Compression::best());
+    encoder.write_all(&buf).unwrap();
+    BASE64_STANDARD_NO_PAD.encode(encoder.finish().unwrap())
+
+}
+
+// takes bytes off the front of a buffer
+fn take<const N: usize>(bytes: &mut &[u8]) -> Option<[u8; N]> {
+    let (taken, rest) = bytes.split_first_chunk::<N>()?;
+    *bytes = rest;
+    Some(*taken)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plates_face_into_their_tile() {
+        let plates = parse_blanking_plates("eNpjZmBgEC7LTC7JL8pMzItPyknMy9bLT8piYGA5xMDQYMLAIOCkt3e3MZC9H5fKDwcZwOCGI0gVUPVmRhwqBQ4hqbQHmcvIAAD7gSbl").unwrap();
+        assert_eq!(3, plates.len());
+        assert_eq!("victorian_blank.obj", plates[1].model);
+        assert_eq!([-30.0, 0.0, 27.0], plates[1].position);
+        assert_eq!(-1.0, plates[1].facing.0);
+        assert_eq!(1.0, plates[2].facing.0);
+        assert!(parse_blanking_plates("not a plate").is_none());
+    }
+
+    #[test]
+    fn plates_round_trip() {
+        let plates = parse_blanking_plates("eNpjZmBgEC7LTC7JL8pMzItPyknMy9bLT8piYGA5xMDQYMLAIOCkt3e3MZC9H5fKDwcZwOCGI0gVUPVmRhwqBQ4hqbQHmcvIAAD7gSbl").unwrap();
+        assert_eq!(Some(plates.clone()), parse_blanking_plates(&format_blanking_plates(&plates)));
+    }
+}
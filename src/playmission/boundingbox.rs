@@ -0,0 +1,82 @@
+// tiles store their extent as two corners in text, i.e. "-36.0, 340282346638528860000000000000000000000.0, 30.0".
+// missions leave heights unbounded, which are written out in full
+
+use super::{orientation::{format_float, parse_floats}, Properties, Value};
+
+// how an unbounded height is written
+const UNBOUNDED: &str = "340282346638528860000000000000000000000.0";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub min: [f32; 3],
+    pub max: [f32; 3],
+}
+
+impl BoundingBox {
+
+    // get from the bounding box properties of a LOCATION
+    pub fn of(properties: &Properties) -> Option<Self> {
+        let corner = |k| match properties.get_value(k) {
+            Ok(Value::String(s)) => parse_coordinates(s),
+            _ => None,
+        };
+        Some(Self { min: corner("Bounding Box Min")?, max: corner("Bounding Box Max")? })
+    }
+
+    // get width across x and depth across z
+    pub fn size(&self) -> (f32, f32) {
+        (self.max[0] - self.min[0], self.max[2] - self.min[2])
+    }
+
+    // whether a point is over the box, ignoring height
+    pub fn contains(&self, [x, _, z]: [f32; 3]) -> bool {
+        (self.min[0]..=self.max[0]).contains(&x) && (self.min[2]..=self.max[2]).contains(&z)
+    }
+
+    // get the nearest point over the box, ignoring height
+    pub fn clamp(&self, [x, y, z]: [f32; 3]) -> [f32; 3] {
+        [x.clamp(self.min[0], self.max[0]), y, z.clamp(self.min[2], self.max[2])]
+    }
+
+    // get the box moved across the floor
+    pub fn translated(&self, dx: f32, dz: f32) -> Self {
+        let mut moved = *self;
+        for corner in [&mut moved.min, &mut moved.max] {
+            corner[0] += dx;
+            corner[2] += dz;
+        }
+        moved
+    }
+
+}
+
+// get [x, y, z] from a corner
+pub fn parse_coordinates(s: &str) -> Option<[f32; 3]> {
+    parse_floats(s)
+}
+
+// write [x, y, z] as a corner, keeping unbounded heights as they were written
+pub fn format_coordinates(coordinates: [f32; 3]) -> String {
+    coordinates
+        .map(|c| match c {
+            f32::MAX => UNBOUNDED.to_string(),
+            c if c == -f32::MAX => format!("-{UNBOUNDED}"),
+            c => format_float(c),
+        })
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn corners_round_trip() {
+        let corner = format!("-36.0, {UNBOUNDED}, 30.0");
+        let parsed = parse_coordinates(&corner).unwrap();
+        assert_eq!([-36.0, f32::MAX, 30.0], parsed);
+        assert_eq!(corner, format_coordinates(parsed));
+        assert_eq!(format!("-30.0, -{UNBOUNDED}, 36.5"), format_coordinates([-30.0, -f32::MAX, 36.5]));
+        assert_eq!(None, parse_coordinates("1.0, 2.0"));
+    }
+}
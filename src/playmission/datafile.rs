@@ -4,7 +4,7 @@ use std::str;
 use gloo_console::log;
 
 use crate::playmission::structs::{ Properties, Property, Value };
-use crate::playmission::error::{Result, PlaymissionError as Error};
+use crate::playmission::error::{Context, Result, PlaymissionError as Error};
 
 // parse datafile to properties
 pub fn deserialize(datafile: &[u8]) -> Result<Properties> {
@@ -21,16 +21,29 @@ pub fn deserialize(datafile: &[u8]) -> Result<Properties> {
 	Ok(new)
 }
 
+// write properties back out as a datafile, one line per property
+pub fn serialize(properties: &Properties) -> Vec<u8> {
+
+	let mut lines: Vec<(&String, &Property)> = properties.iter().collect();
+	lines.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+	lines.into_iter()
+		.map(|(k, v)| format!("{} = {}\r\n", k, v.text()))
+		.collect::<String>()
+		.into_bytes()
+}
+
 // splits datafile into string-typed properties
 fn split(datafile: &str) -> Result<Vec<(String, String)>> {
 
 	let mut parsed = vec![];
 
-	for line in datafile.lines().filter(|l| *l != "") {
+	for (i, line) in datafile.lines().enumerate().filter(|(_, l)| *l != "") {
 
 		let split: Vec<&str> = line.splitn(2, " = ").collect();
 		if split.len() != 2 {
-			return Err(Error::MalformedDatafileLine(line.into()))
+			let position = Context::Position { line: i + 1, column: 1 };
+			return Err(Error::MalformedDatafileLine(line.into()).context(position))
 		} else {
 			parsed.push((split[0].into(), split[1].into()));
 		}
@@ -74,9 +87,34 @@ mod tests {
 		let datafile = get_test("datafile_datafile.txt");
 		let default = get_test("datafile_default.txt");
 
-		let found = Properties::from_datafile_default(datafile, default).unwrap();
+		let found = Properties::from_datafile_default("datafile.tile", datafile, "Default.tile", default).unwrap();
 		
 		pretty_assert_eq!(expected, found);
 	}
 
+	#[test]
+	fn ser_round_trips() {
+		let datafile = get_test("datafile_datafile.txt");
+		let default = get_test("datafile_default.txt");
+		let expected = Properties::from_datafile_default("datafile.tile", datafile, "Default.tile", default.clone()).unwrap();
+
+		let found = Properties::from_datafile_default("datafile.tile", serialize(&expected), "Default.tile", default).unwrap();
+
+		pretty_assert_eq!(expected, found);
+	}
+
+	#[test]
+	fn malformed_line_has_position() {
+		let datafile = "Name = Baronial_2Door\n\nSize X 6".as_bytes().to_vec();
+		let default = get_test("datafile_default.txt");
+
+		let found = Properties::from_datafile_default("datafile.tile", datafile, "Default.tile", default).err().unwrap();
+
+		let expected = vec![
+			Context::File("datafile.tile".into()),
+			Context::Position { line: 3, column: 1 },
+		];
+		pretty_assert_eq!(expected, found.contexts());
+	}
+
 }
\ No newline at end of file
@@ -1,19 +1,34 @@
+use std::fmt;
+
 use thiserror::Error;
 
 pub type Result<T> = std::result::Result<T, PlaymissionError>;
 
 #[derive(Debug, Error)]
 pub enum PlaymissionError {
+    #[error("{context}")]
+    Context {
+        context: Context,
+        #[source]
+        source: Box<PlaymissionError>,
+    },
+
     #[error("datafile name was not found when serializing datafile")]
     NoDatafileName,
-    #[error("could not find matching end tag for {0}")]
-    NoMatchingTag(String),
+    #[error("found malformed tag at byte {0}")]
+    MalformedTag(usize),
+    #[error("could not find matching end tag for {0} opened at byte {1}")]
+    NoMatchingTag(String, usize),
     #[error("could not find missionobject in object map")]
     NoMissionObject,
     #[error("found malformed line ('{0}' not in form 'foo = bar') when parsing datafile")]
     MalformedDatafileLine(String),
     #[error("attempted to write value {0} of type {1} into properties when {0} was already specified as {2}")]
     MergedWrongType(String, String, String),
+    #[error("no object type {0} to load")]
+    UnknownObjectType(String),
+    #[error("saved object order does not match the objects in the mission")]
+    MismatchedOrder,
     #[error("missing required file {0} in filemap")]
     MissingFile(String),
     #[error("missing required property {0} in properties")]
@@ -26,11 +41,13 @@ pub enum PlaymissionError {
     WrongTypeCast(String, String),
     #[error("found wrong type for value {0} when {1} was expected")]
     WrongTypeFound(String, String),
+    #[error("found end tag at byte {0} with no matching start tag")]
+    UnexpectedEndTag(usize),
 
     #[error("failed to read loaded buffer to string")]
     Utf8 {
         #[from]
-        source: std::str::Utf8Error, 
+        source: std::str::Utf8Error,
     },
     #[error("failed handling playmission as zip")]
     Zip {
@@ -47,9 +64,137 @@ pub enum PlaymissionError {
         #[from]
         source: quick_xml::DeError,
     },
-    #[error("failed regex replacement")]
-    Regex {
+    #[error("failed reading or writing xml events")]
+    Xml {
         #[from]
-        source: fancy_regex::Error,
+        source: quick_xml::Error,
+    }
+}
+
+impl PlaymissionError {
+
+    // wraps self in a layer of context
+    pub fn context(self, context: Context) -> Self {
+        Self::Context { context, source: Box::new(self) }
+    }
+
+    // returns contexts outermost first
+    pub fn contexts(&self) -> Vec<Context> {
+        let mut contexts = vec![];
+        let mut e = self;
+        while let Self::Context { context, source } = e {
+            contexts.push(context.clone());
+            e = source;
+        }
+        contexts
+    }
+
+    // returns the error underneath all contexts
+    pub fn root(&self) -> &Self {
+        match self {
+            Self::Context { source, .. } => source.root(),
+            e => e,
+        }
+    }
+
+}
+
+// where in a playmission an error happened
+#[derive(Debug, Clone, PartialEq)]
+pub enum Context {
+    File(String),
+    Position { line: usize, column: usize },
+    Object { r#type: String, name: Option<String> },
+    Property(String),
+}
+
+impl Context {
+
+    // creates position context from a byte offset into a source string
+    pub fn position(s: &str, offset: usize) -> Self {
+        let before = &s[..offset.min(s.len())];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let column = before[line_start..].chars().count() + 1;
+        Self::Position { line, column }
+    }
+
+    // creates object context from a type and optional name
+    pub fn object(r#type: impl Into<String>, name: Option<String>) -> Self {
+        Self::Object { r#type: r#type.into(), name }
+    }
+
+}
+
+impl fmt::Display for Context {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File(name) => write!(f, "in file {name}"),
+            Self::Position { line, column } => write!(f, "at line {line}, column {column}"),
+            Self::Object { r#type, name: Some(name) } => write!(f, "in {type} '{name}'"),
+            Self::Object { r#type, name: None } => write!(f, "in unnamed {type}"),
+            Self::Property(key) => write!(f, "on property '{key}'"),
+        }
+    }
+}
+
+// adds context to any result that can become a playmission error
+pub trait ResultExt<T> {
+    fn context(self, context: Context) -> Result<T>;
+    fn with_context(self, f: impl FnOnce() -> Context) -> Result<T>;
+}
+
+impl<T, E: Into<PlaymissionError>> ResultExt<T> for std::result::Result<T, E> {
+
+    fn context(self, context: Context) -> Result<T> {
+        self.map_err(|e| e.into().context(context))
+    }
+
+    fn with_context(self, f: impl FnOnce() -> Context) -> Result<T> {
+        self.map_err(|e| e.into().context(f()))
+    }
+
+}
+
+// renders an error and all of its sources, outermost first
+pub fn chain(e: &dyn std::error::Error) -> String {
+    let mut s = e.to_string();
+    let mut source = e.source();
+    while let Some(e) = source {
+        s.push_str(": ");
+        s.push_str(&e.to_string());
+        source = e.source();
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn position_from_offset() {
+        let s = "<A>\n<B>\n  <C>";
+        assert_eq!(Context::Position { line: 1, column: 1 }, Context::position(s, 0));
+        assert_eq!(Context::Position { line: 2, column: 1 }, Context::position(s, 4));
+        assert_eq!(Context::Position { line: 3, column: 3 }, Context::position(s, 10));
+    }
+
+    #[test]
+    fn chain_outermost_first() {
+        let e: Result<()> = Err(PlaymissionError::WrongTypeCast("abc".into(), "VTYPE_FLOAT".into()));
+        let e = e
+            .context(Context::Property("Position X".into()))
+            .context(Context::object("PROP", Some("Bookcase".into())))
+            .context(Context::Position { line: 40, column: 1 })
+            .context(Context::File("Test.mission".into()))
+            .unwrap_err();
+
+        assert_eq!(
+            "in file Test.mission: at line 40, column 1: in PROP 'Bookcase': on property 'Position X': failed to cast value abc to type VTYPE_FLOAT",
+            chain(&e)
+        );
+        assert_eq!(4, e.contexts().len());
+        assert!(matches!(e.root(), PlaymissionError::WrongTypeCast(..)));
     }
-}
\ No newline at end of file
+}
@@ -89,6 +89,12 @@ impl IntoIterator for Filemap {
     }
 }
 
+impl FromIterator<(String, Vec<u8>)> for Filemap {
+    fn from_iter<T: IntoIterator<Item = (String, Vec<u8>)>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
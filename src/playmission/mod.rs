@@ -2,9 +2,22 @@ mod filemap;
 mod xmlcleaner;
 mod datafile;
 mod structs;
+mod naming;
+mod orientation;
+mod boundingbox;
+mod blankingplates;
 pub mod error;
 
 pub use structs::mission::MissionObject;
-pub use structs::traits::Object;
-pub use structs::properties::Value;
-pub use error::Result;
\ No newline at end of file
+pub use structs::traits::{ Face, Object };
+pub use structs::location::{ door_sides, render_doors, Location, Side };
+pub use structs::player::Player;
+pub use structs::special_effect::SpecialEffect;
+pub use structs::trigger::Trigger;
+pub use structs::properties::{ Properties, Value };
+pub use naming::{ unique_name, unique_file_name };
+pub use orientation::{ parse_orientation, format_orientation, format_position };
+pub use boundingbox::{ BoundingBox, parse_coordinates, format_coordinates };
+pub use blankingplates::{ BlankingPlate, parse_blanking_plates, format_blanking_plates };
+pub use error::Result;
+pub use xmlcleaner::{ clean, dirty };
\ No newline at end of file
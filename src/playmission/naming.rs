@@ -0,0 +1,51 @@
+// keeps names of objects and files unique when adding to a mission
+
+// get the first of name, "name 2", "name 3" ... that isn't taken.
+// a number already on the end of name is counted up from
+pub fn unique_name(name: &str, taken: impl Fn(&str) -> bool) -> String {
+    if !taken(name) {
+        return name.into()
+    }
+    let (base, start) = split_number(name);
+    (start..)
+        .map(|n| format!("{base} {n}"))
+        .find(|candidate| !taken(candidate))
+        .unwrap()
+}
+
+// as unique_name, but numbering before the extension, i.e. "name 2.prop"
+pub fn unique_file_name(file_name: &str, taken: impl Fn(&str) -> bool) -> String {
+    let (stem, extension) = match file_name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem, format!(".{extension}")),
+        _ => (file_name, String::new()),
+    };
+    let stem = unique_name(stem, |candidate| taken(&format!("{candidate}{extension}")));
+    format!("{stem}{extension}")
+}
+
+// split "name 3" into ("name", 4), or "name" into ("name", 2)
+fn split_number(name: &str) -> (&str, u32) {
+    name.rsplit_once(' ')
+        .and_then(|(base, n)| Some((base, n.parse::<u32>().ok()?.checked_add(1)?)))
+        .unwrap_or((name, 2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_count_up() {
+        let taken = ["Bookcase", "Bookcase 2", "Lamp 3"];
+        assert_eq!("Chair", unique_name("Chair", |n| taken.contains(&n)));
+        assert_eq!("Bookcase 3", unique_name("Bookcase", |n| taken.contains(&n)));
+        assert_eq!("Lamp 4", unique_name("Lamp 3", |n| taken.contains(&n)));
+    }
+
+    #[test]
+    fn file_names_keep_extension() {
+        let taken = ["suitcase.prop", "suitcase 2.prop"];
+        assert_eq!("suitcase 3.prop", unique_file_name("suitcase.prop", |n| taken.contains(&n)));
+        assert_eq!("suitcase.aprop", unique_file_name("suitcase.aprop", |n| taken.contains(&n)));
+    }
+}
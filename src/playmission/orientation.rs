@@ -0,0 +1,47 @@
+// orientations are stored as quaternions in text, w first, i.e. "1.0, 0.0, 0.0, 0.0".
+// the player's start position is written out the same way, as x, y, z
+
+// get [w, x, y, z] from an orientation
+pub fn parse_orientation(orientation: &str) -> Option<[f32; 4]> {
+    parse_floats(orientation)
+}
+
+// write [w, x, y, z] as an orientation
+pub fn format_orientation(orientation: [f32; 4]) -> String {
+    orientation.map(format_float).join(", ")
+}
+
+// write [x, y, z] as a start position
+pub fn format_position(position: [f32; 3]) -> String {
+    position.map(format_float).join(", ")
+}
+
+// get exactly N comma separated floats, as orientations, positions and corners are written
+pub(super) fn parse_floats<const N: usize>(s: &str) -> Option<[f32; N]> {
+    let parts: Vec<f32> = s.split(',')
+        .map(|part| part.trim().parse::<f32>())
+        .collect::<Result<_, _>>()
+        .ok()?;
+    parts.try_into().ok()
+}
+
+// write a float with only as many digits as it holds, so nothing past its
+// precision is made up, but always with a decimal point as missions have
+pub(super) fn format_float(f: f32) -> String {
+    let s = f.to_string();
+    if s.contains('.') { s } else { s + ".0" }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orientations_round_trip() {
+        let parsed = parse_orientation("0.9763972163, -0.1743841618, -0.1255526990, 0.0217995960").unwrap();
+        assert_eq!(parsed, parse_orientation(&format_orientation(parsed)).unwrap());
+        assert_eq!(Some([1.0, 0.0, 0.0, 0.0]), parse_orientation("1.0, 0.0, 0.0, 0.0"));
+        assert_eq!(None, parse_orientation("1.0, 0.0, 0.0"));
+        assert_eq!("1.0, 0.0, -0.5, 0.0", format_orientation([1.0, 0.0, -0.5, 0.0]));
+    }
+}
@@ -0,0 +1,128 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::{ traits::ObjectHandler, CollapsedObject, ConstructedObject, Object, Properties, Raw, Value };
+use crate::{playmission::{
+    error::{PlaymissionError as Error, Result},
+    filemap::Filemap
+}, three::Scene};
+
+// an element of the mission file that could not be loaded, kept as-is
+// so it can be written back unchanged
+#[derive(Serialize, Debug, PartialEq, Clone)]
+#[serde(rename = "FRAGMENT")]
+pub struct FragmentRaw {
+    #[serde(skip)]
+    fragment: String,
+    properties: Properties,
+}
+
+impl FragmentRaw {
+
+    // create new from markup, and the name of the object if it is known
+    pub fn new(fragment: impl Into<String>, name: Option<String>) -> Result<Self> {
+        let mut properties = Properties::new();
+        if let Some(name) = name {
+            properties.insert_new("Name", name, "VTYPE_STRING", Some("READONLY"))?;
+        }
+        Ok(Self { fragment: fragment.into(), properties })
+    }
+
+}
+
+impl Raw for FragmentRaw {
+
+    // based on if any loading needs to happen at all,
+	// returns self as either intermediary or object
+    fn begin(mut self: Box<Self>) -> Result<ConstructedObject> {
+
+        self.properties.insert_new("Fragment", self.fragment, "VTYPE_STRING", None)?;
+
+        let handler = Box::new(Fragment);
+
+        let new = Object::new(handler, self.properties, None, None, None);
+
+        Ok(ConstructedObject::done(new))
+    }
+
+	// cast self to serialize
+	fn as_serialize(self: Box<Self>) -> Box<dyn erased_serde::Serialize> {
+        Box::new(self)
+    }
+
+	// get ref to properties
+	fn properties(&self) -> &Properties {
+        &self.properties
+    }
+
+    // fragments are already markup, so are written back verbatim
+    fn serialize_xml(self: Box<Self>) -> Result<Vec<u8>> {
+        Ok(self.fragment.into_bytes())
+    }
+
+}
+
+pub struct Fragment;
+
+impl ObjectHandler for Fragment {
+
+    // renders object to canvas
+	fn render(&mut self, uuid: &Uuid, properties: &Properties, datafile: &Properties, files: &Filemap, scene: &mut Scene) -> Result<()> {
+
+        // nothing to render for this object ...
+        Ok(())
+
+	}
+
+	// handles internal state for property updates
+	fn view_property_update(&mut self, k: &str, v: &Value) -> Result<()> {
+        Ok(())
+    }
+
+	// sama datafile
+	fn view_datafile_update(&self, k: &str, v: &Value) -> Result<()> {
+        Ok(())
+    }
+
+	// sama file
+	fn view_file_update(&self, k: &str, v: &[u8]) -> Result<()> {
+        Ok(())
+    }
+
+	// iteratively collapses to raw stage and emits files to place in filemap
+	fn collapse(&self, mut properties: Properties, datafile: Properties, datafile_name: Option<String>, files: Filemap) -> Result<CollapsedObject> {
+
+        let Value::String(fragment) = properties.take_value("Fragment")? else {
+            return Err(Error::WrongTypeFound("Fragment".into(), "VTYPE_STRING".into()))
+        };
+        let name = properties.take_value("Name").ok().map(|n| n.to_string());
+        let fragment = match &name {
+            Some(name) => with_name(&fragment, name),
+            None => fragment,
+        };
+
+        let raw = Box::new(FragmentRaw::new(fragment, name)?) as Box<dyn Raw>;
+
+        Ok(CollapsedObject::new(raw, files))
+
+    }
+
+    fn r#type(&self) -> &'static str {
+        "FRAGMENT"
+    }
+
+}
+
+// writes a name into the object's own Name property in its markup, so that
+// a renamed fragment keeps its new name when written back
+fn with_name(fragment: &str, name: &str) -> String {
+    const KEY: &str = "<ATTR: NAME >Name</ATTR>";
+    const VALUE: &str = "<ATTR: VALUE >";
+    let value = fragment.find(KEY)
+        .and_then(|key| fragment[key..].find(VALUE).map(|i| key + i + VALUE.len()))
+        .and_then(|start| fragment[start..].find("</ATTR>").map(|i| (start, start + i)));
+    match value {
+        Some((start, end)) => format!("{}{}{}", &fragment[..start], name, &fragment[end..]),
+        None => fragment.into(),
+    }
+}
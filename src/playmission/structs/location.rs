@@ -1,11 +1,15 @@
 use serde::{ Serialize, Deserialize };
 use uuid::Uuid;
 
-use super::{ traits::{render_default_orb, ObjectHandler, Prerequisite}, CollapsedObject, ConstructedObject, Intermediary, Object, Properties, Property, Raw, Value };
+use wasm_bindgen::JsCast;
+
+use super::{ traits::{ObjectHandler, Prerequisite}, CollapsedObject, ConstructedObject, Intermediary, Object, Properties, Property, Raw, Value };
 use crate::{playmission::{
+    blankingplates::BlankingPlate,
+    boundingbox::BoundingBox,
     error::{PlaymissionError as Error, Result},
-    filemap::Filemap, xmlcleaner
-}, three::{Mesh, Scene}};
+    datafile, filemap::Filemap
+}, three::{BoxGeometry, CanvasTexture, Mesh, MeshBasicMaterial, Object3D, Scene}};
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename = "LOCATION", rename_all = "SCREAMING_SNAKE_CASE")]
@@ -34,6 +38,11 @@ impl Raw for LocationRaw {
         Box::new(self)
     }
 
+	// get ref to properties
+	fn properties(&self) -> &Properties {
+        &self.properties
+    }
+
 }
 
 impl Intermediary for LocationRaw {
@@ -57,7 +66,7 @@ impl Intermediary for LocationRaw {
         let bbox_max = Property::new(Value::String(self.bbox_max), None);
         self.properties.add("Bounding Box Max", bbox_max)?;
 
-        let datafile = Properties::from_datafile_default(datafile, default)?;
+        let datafile = Properties::from_datafile_default(&self.datafile_name, datafile, Self::DEFAULT, default)?;
         let handler = Box::new(Location::new());
 
         let new = Object::new(handler, self.properties, Some(datafile), Some(self.datafile_name), None);
@@ -68,14 +77,122 @@ impl Intermediary for LocationRaw {
 
 }
 
+// tiles are drawn as floor plans: a floor labelled with the tile name, walls
+// outlining the bounding box, and an opening for each door
+#[derive(Default)]
 pub struct Location {
-    mesh: Option<Mesh>
+    mesh: Option<Mesh>,
+    label: Option<(web_sys::HtmlCanvasElement, CanvasTexture)>,
+}
+
+// an edge of a tile, facing +z, -z, +x or -x
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Side {
+    North,
+    South,
+    East,
+    West,
+}
+
+impl Side {
+
+    pub const ALL: [Side; 4] = [Side::North, Side::South, Side::East, Side::West];
+
+    // get the middle of this side of a tile, from the middle of the tile
+    pub fn offset(&self, width: f32, depth: f32) -> (f32, f32) {
+        match self {
+            Side::North => (0.0, depth / 2.0),
+            Side::South => (0.0, -depth / 2.0),
+            Side::East => (width / 2.0, 0.0),
+            Side::West => (-width / 2.0, 0.0),
+        }
+    }
+
+    // whether a point is on this side of a tile, ignoring height
+    pub fn holds(&self, bbox: &BoundingBox, [x, _, z]: [f32; 3]) -> bool {
+        const EPSILON: f32 = 0.01;
+        let between = |v: f32, min: f32, max: f32| (min - EPSILON..=max + EPSILON).contains(&v);
+        match self {
+            Side::North => (z - bbox.max[2]).abs() < EPSILON && between(x, bbox.min[0], bbox.max[0]),
+            Side::South => (z - bbox.min[2]).abs() < EPSILON && between(x, bbox.min[0], bbox.max[0]),
+            Side::East => (x - bbox.max[0]).abs() < EPSILON && between(z, bbox.min[2], bbox.max[2]),
+            Side::West => (x - bbox.min[0]).abs() < EPSILON && between(z, bbox.min[2], bbox.max[2]),
+        }
+    }
+
 }
 
 impl Location {
 
+    // bounding boxes leave height unbounded, so walls are drawn this high
+    const WALL_HEIGHT: f32 = 3.0;
+    pub const DOOR_WIDTH: f32 = 1.2;
+    const DOOR_HEIGHT: f32 = 2.2;
+    // doors are drawn once every tile is known, see door_sides
+    const DOOR: &'static str = "door";
+    // keys that move a tile's sides, and with them its doors and its neighbours'
+    pub const LAYOUT_KEYS: [&'static str; 4] = ["Position X", "Position Z", "Bounding Box Min", "Bounding Box Max"];
+
     pub fn new() -> Location {
-        Location { mesh: None }
+        Location::default()
+    }
+
+    // draws the name onto the floor
+    fn draw_label(canvas: &web_sys::HtmlCanvasElement, name: &str) {
+        let Some(context) = canvas.get_context("2d").ok().flatten()
+            .and_then(|c| c.dyn_into::<web_sys::CanvasRenderingContext2d>().ok()) else { return };
+        let (width, height) = (f64::from(canvas.width()), f64::from(canvas.height()));
+        context.set_fill_style(&"#303030".into());
+        context.fill_rect(0.0, 0.0, width, height);
+        context.set_fill_style(&"#ffffff".into());
+        context.set_font("24px monospace");
+        context.set_text_align("center");
+        context.set_text_baseline("middle");
+        let _ = context.fill_text_with_max_width(name, width / 2.0, height / 2.0, width);
+    }
+
+}
+
+// get the sides of a tile that have doors. missions don't say where doors
+// are, but each one either leads into another tile or is closed off with a
+// blanking plate, so a side has a door if either is found there
+pub fn door_sides(bbox: &BoundingBox, others: &[BoundingBox], plates: &[BlankingPlate]) -> Vec<Side> {
+    let (width, depth) = bbox.size();
+    let centre = ((bbox.min[0] + bbox.max[0]) / 2.0, (bbox.min[2] + bbox.max[2]) / 2.0);
+    Side::ALL.into_iter()
+        .filter(|side| {
+            // a step through the middle of the side, which is in the next tile if there is one
+            let (x, z) = side.offset(width, depth);
+            let (step_x, step_z) = side.offset(1.0, 1.0);
+            let beyond = [centre.0 + x + step_x, 0.0, centre.1 + z + step_z];
+            others.iter().any(|other| other.contains(beyond))
+                || plates.iter().any(|plate| side.holds(bbox, plate.position))
+        })
+        .collect()
+}
+
+// draws a tile's doors onto its floor, replacing any drawn before
+pub fn render_doors(floor: &Object3D, bbox: &BoundingBox, sides: &[Side]) {
+
+    for child in floor.children() {
+        let child = child.unchecked_into::<Object3D>();
+        if child.name() == Location::DOOR {
+            floor.remove(&child);
+        }
+    }
+
+    let (width, depth) = bbox.size();
+    for side in sides {
+        let mat = MeshBasicMaterial::new();
+        mat.color().set_rgb(0.0, 1.0, 0.0);
+        let door = Mesh::new(&BoxGeometry::new(Location::DOOR_WIDTH, Location::DOOR_HEIGHT, 0.1), &mat);
+        let (x, z) = side.offset(width, depth);
+        door.position().set(x, Location::DOOR_HEIGHT / 2.0, z);
+        if matches!(side, Side::East | Side::West) {
+            door.rotation().set_y(std::f32::consts::FRAC_PI_2);
+        }
+        door.dyn_ref::<Object3D>().unwrap().set_name(Location::DOOR.into());
+        floor.add(door.unchecked_ref());
     }
 
 }
@@ -83,9 +200,38 @@ impl Location {
 impl ObjectHandler for Location {
 
     // renders object to canvas
-	fn render(&mut self, uuid: &Uuid, properties: &Properties, datafile: &Properties, files: &Filemap, scene: &mut Scene) -> Result<()> {
-
-        self.mesh = Some(render_default_orb(uuid, properties, scene)?);
+	fn render(&mut self, uuid: &Uuid, properties: &Properties, _datafile: &Properties, files: &Filemap, scene: &mut Scene) -> Result<()> {
+
+        let bbox = BoundingBox::of(properties)
+            .ok_or_else(|| Error::WrongTypeCast("Bounding Box Min/Max".into(), "bounding box".into()))?;
+        let (width, depth) = bbox.size();
+
+        // the floor is what gets picked, with everything else on it
+        let canvas = web_sys::window().unwrap()
+            .document().unwrap()
+            .create_element("canvas").unwrap()
+            .dyn_into::<web_sys::HtmlCanvasElement>().unwrap();
+        canvas.set_width(256);
+        canvas.set_height((256.0 * depth / width).round().max(1.0) as u32);
+        Self::draw_label(&canvas, &properties.get_value("Name")?.to_string());
+        let texture = CanvasTexture::new(&canvas);
+        let mat = MeshBasicMaterial::new();
+        mat.color().set_rgb(1.0, 1.0, 1.0);
+        mat.set_map(&texture);
+        let floor = Mesh::new(&BoxGeometry::new(width, 0.02, depth), &mat);
+        floor.position().set(properties.get_float("Position X")?, 0.0, properties.get_float("Position Z")?);
+        floor.dyn_ref::<Object3D>().unwrap().set_name(uuid.to_string());
+
+        let mat = MeshBasicMaterial::new();
+        mat.color().set_rgb(0.6, 0.6, 0.6);
+        mat.set_wireframe(true);
+        let walls = Mesh::new(&BoxGeometry::new(width, Self::WALL_HEIGHT, depth), &mat);
+        walls.position().set(0.0, Self::WALL_HEIGHT / 2.0, 0.0);
+        floor.dyn_ref::<Object3D>().unwrap().add(walls.unchecked_ref());
+
+        scene.add(&floor);
+        self.mesh = Some(floor);
+        self.label = Some((canvas, texture));
         Ok(())
 
 	}
@@ -93,12 +239,17 @@ impl ObjectHandler for Location {
 	// handles internal state for property updates
 	fn view_property_update(&mut self, k: &str, v: &Value) -> Result<()> {
 
+        if let (Some((canvas, texture)), "Name") = (&self.label, k) {
+            Self::draw_label(canvas, &v.to_string());
+            texture.set_needs_update(true);
+        }
+
         let Some(ref mut mesh) = self.mesh else { return Ok(()) };
         let Value::Float(f) = v else { return Ok(()) };
 
+        // tiles sit on the floor, so have no height to move
         match k {
             "Position X" => { mesh.position().set_x(*f); }
-            "Position Y" => { mesh.position().set_y(*f); }
             "Position Z" => { mesh.position().set_z(*f); }
             _ => {},
         };
@@ -120,7 +271,7 @@ impl ObjectHandler for Location {
 	fn collapse(&self, mut properties: Properties, datafile: Properties, datafile_name: Option<String>, mut files: Filemap) -> Result<CollapsedObject> {
 
         let datafile_name = datafile_name.ok_or(Error::NoDatafileName)?;
-        files.add(datafile_name.clone(), xmlcleaner::serialize(&datafile)?)?;
+        files.add(datafile_name.clone(), datafile::serialize(&datafile))?;
 
         let Value::String(bbox_min) = properties.take_value("Bounding Box Min")? else {
             return Err(Error::WrongTypeFound("Bounding Box Min".into(), "VTYPE_STRING".into()))
@@ -144,4 +295,21 @@ impl ObjectHandler for Location {
         "LOCATION"
     }
 
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{testcase, uuid_of};
+
+    #[test]
+    fn doors_lead_to_tiles_or_plates() {
+        let th = testcase();
+        let doors = th.display_doors();
+        let sides = |name| doors.iter().find(|(uuid, _)| *uuid == uuid_of(&th, name)).unwrap().1.clone();
+
+        // the two door tile joins the three door tile on its north side
+        assert_eq!(vec![Side::North, Side::East, Side::West], sides("Baronial_3Door"));
+        assert_eq!(vec![Side::North, Side::South], sides("Baronial_2Door_Out"));
+    }
+}
@@ -27,6 +27,11 @@ impl Raw for MediaRaw {
         Box::new(self)
     }
 
+	// get ref to properties
+	fn properties(&self) -> &Properties {
+        &self.properties
+    }
+
 }
 
 impl Intermediary for MediaRaw {
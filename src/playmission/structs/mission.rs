@@ -1,107 +1,24 @@
-use std::{collections::HashMap, io::{ Cursor, Read, Seek, Write }};
-use gloo_console::log;
-use serde::{ Deserialize, Serialize, Deserializer };
+use std::{collections::{BTreeMap, HashMap}, io::{ Cursor, Read, Seek, Write }, str};
 use uuid::Uuid;
 use zip::{write::FileOptions, ZipWriter};
 
-use super::{ active_prop::ActivePropRaw, character::CharacterRaw, door::DoorRaw, location::LocationRaw, media::MediaRaw, pickup::PickupRaw, player::PlayerRaw, prop::PropRaw, rule::RuleRaw, special_effect::SpecialEffectRaw, traits::ObjectHandler, trigger::TriggerRaw, user_data::UserDataRaw, CollapsedObject, ConstructedObject, Object, Properties, Raw, Value };
+use super::{ active_prop::ActivePropRaw, character::CharacterRaw, door::DoorRaw, fragment::FragmentRaw, location::LocationRaw, media::MediaRaw, pickup::PickupRaw, player::PlayerRaw, prop::PropRaw, rule::RuleRaw, special_effect::SpecialEffectRaw, trigger::TriggerRaw, user_data::UserDataRaw, CollapsedObject, ConstructedObject, Object, Properties, Raw, Value };
 use crate::playmission::{
-    error::{PlaymissionError as Error, Result},
+    error::{Context, PlaymissionError as Error, Result, ResultExt},
     filemap::Filemap,
     xmlcleaner,
 };
 
-#[derive(Deserialize, Debug, PartialEq, Clone)]
-#[serde(rename = "GAME", rename_all = "SCREAMING_SNAKE_CASE")]
-pub struct IntermediaryMissionRaw {
-    #[serde(rename = "ExpandedSize")]
-    pub expanded_size: i32,
-    #[serde(rename = "BLANKINGPLATES")]
-    pub blanking_plates: String,
-    #[serde(rename = "Meta")]
-    pub meta: String,
-    pub properties: Properties,
-
-    #[serde(default, rename = "ACTIVE_PROP")]
-    pub active_props: Vec<ActivePropRaw>,
-    #[serde(default, rename = "CHARACTER")]
-    pub characters: Vec<CharacterRaw>,
-    #[serde(default, rename = "DOOR")]
-    pub doors: Vec<DoorRaw>,
-    #[serde(default, rename = "LOCATION")]
-    pub locations: Vec<LocationRaw>,
-    #[serde(default, rename = "MEDIA")]
-    pub medias: Vec<MediaRaw>,
-    #[serde(default, rename = "PICKUP")]
-    pub pickups: Vec<PickupRaw>,
-    #[serde(default, rename = "PROP")]
-    pub props: Vec<PropRaw>,
-    #[serde(default, rename = "PLAYER")]
-    pub players: Vec<PlayerRaw>,
-    #[serde(default, rename = "RULE")]
-    pub rules: Vec<RuleRaw>,
-    #[serde(default, rename = "SPECIAL_EFFECT")]
-    pub special_effects: Vec<SpecialEffectRaw>,
-    #[serde(default, rename = "TRIGGER")]
-    pub triggers: Vec<TriggerRaw>,
-    #[serde(default, rename = "USER_DATA")]
-    pub user_datas: Vec<UserDataRaw>,
-}
-
-#[derive(Serialize)]
-#[serde(rename = "GAME", rename_all = "SCREAMING_SNAKE_CASE")]
-pub struct IntermediaryMission {
-    #[serde(rename = "ExpandedSize")]
-    pub expanded_size: i32,
-    #[serde(rename = "BLANKINGPLATES")]
-    pub blanking_plates: String,
-    #[serde(rename = "Meta")]
-    pub meta: String,
-    pub properties: Properties,
-
-    pub raws: Vec<Box<dyn Raw>>,
-}
-
-impl<'de> Deserialize<'de> for IntermediaryMission {
-    fn deserialize<D>(deserializer: D) -> std::result::Result<IntermediaryMission, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        let raw = IntermediaryMissionRaw::deserialize(deserializer)?;
-        Ok(Self::from_raw(raw))
-    }
-}
-
-impl IntermediaryMission {
-
-    // create new from existing structures
-    fn new(expanded_size: i32, blanking_plates: String, meta: String, properties: Properties, raws: Vec<Box<dyn Raw>>) -> Self {
-        Self { expanded_size, blanking_plates, meta, properties, raws }
-    }
-
-    // join raw datastructures into trait object vector
-    fn from_raw(raw: IntermediaryMissionRaw) -> Self {
-        
-        macro_rules! chain_as_raw {
-            ($i:expr,$($j:expr),+) => {
-                $i.into_iter().map(|o| Box::new(o) as Box<dyn Raw>)
-                $(.chain($j.into_iter().map(|o| Box::new(o) as Box<dyn Raw>)))+
-            };
-        }
-        
-        let raws = chain_as_raw!(raw.active_props, raw.characters,
-            raw.doors, raw.locations, raw.medias, raw.pickups, raw.props, raw.players,
-            raw.rules, raw.special_effects, raw.triggers, raw.user_datas).collect();
-
-        Self {
-            expanded_size: raw.expanded_size,
-            blanking_plates: raw.blanking_plates,
-            meta: raw.meta,
-            properties: raw.properties,
-            raws,
-        }
-    }
+// attributes of the mission itself, in the order missionmaker writes them
+const HEADER: [&str; 4] = ["PROPERTIES", "Meta", "ExpandedSize", "BLANKINGPLATES"];
 
+// child of the mission root, in the order they were loaded,
+// so they can be written back in the same place
+#[derive(Debug, PartialEq, Clone)]
+enum Slot {
+    Header(String),
+    Object(Uuid),
+    Unknown(String),
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -109,38 +26,210 @@ pub struct MissionObject {
     uuid: Uuid,
 	properties: Properties,
 	files: Filemap,
+    layout: Vec<Slot>,
 }
 
 impl MissionObject {
 
     // creates new self
     pub fn new(properties: Properties, files: Filemap) -> Self {
-        Self { uuid: Uuid::new_v4(), properties, files }
+        let layout = HEADER.iter().map(|h| Slot::Header(h.to_string())).collect();
+        Self { uuid: Uuid::new_v4(), properties, files, layout }
+    }
+
+    // get ref to properties of the mission itself
+    pub fn properties(&self) -> &Properties {
+        &self.properties
+    }
+
+    // sets a property of the mission itself, returning the old value
+    pub fn set_property(&mut self, k: impl AsRef<str>, v: impl Into<String>) -> Result<Option<Value>> {
+        let name = self.properties.get_value("Name").ok().map(|n| n.to_string());
+        self.properties.replace_or_add_property_value(k, v).context(Context::object("GAME", name))
+    }
+
+    // removes a property of the mission itself, returning its value
+    pub fn remove_property(&mut self, k: impl AsRef<str>) -> Result<Value> {
+        let name = self.properties.get_value("Name").ok().map(|n| n.to_string());
+        self.properties.take_value(k).context(Context::object("GAME", name))
+    }
+
+    // get ref to files not owned by any object, i.e. shared defaults and textures
+    pub fn files(&self) -> &HashMap<String, Vec<u8>> {
+        &self.files
+    }
+
+    // sets or removes a file not owned by any object, returning the old one
+    pub fn set_file(&mut self, k: impl Into<String> + AsRef<str>, v: Option<Vec<u8>>) -> Option<Vec<u8>> {
+        match v {
+            Some(v) => self.files.insert(k.into(), v),
+            None => self.files.remove(k.as_ref()),
+        }
     }
 
     // creates self from reader over zip file
     pub fn deserialize(r: impl Read + Seek) -> Result<(Self, HashMap<Uuid, Object>)> {
+        let (mission, objects, _) = Self::load(r, false)?;
+        Ok((mission, objects))
+    }
+
+    // same, but objects that fail to load are kept as fragments of the
+    // mission file instead, and their errors returned as warnings
+    pub fn deserialize_lenient(r: impl Read + Seek) -> Result<(Self, HashMap<Uuid, Object>, Vec<Error>)> {
+        Self::load(r, true)
+    }
+
+    fn load(r: impl Read + Seek, lenient: bool) -> Result<(Self, HashMap<Uuid, Object>, Vec<Error>)> {
 
         // load all files in zip to map
         let mut filemap = Filemap::from_reader(r)?;
-    
-        // parse intermediary objects from base mission file
-        let mission_file = filemap.take_closure(|s| s.ends_with(".mission")).ok_or(Error::MissingFile("{.mission file}".into()))?;
-        let mut mission: IntermediaryMission = xmlcleaner::deserialize(&mission_file)?;
-    
-        // construct full objects from intermediaries
+
+        // take base mission file
+        let mission_name = filemap.keys()
+            .find(|k| k.ends_with(".mission"))
+            .cloned()
+            .ok_or(Error::MissingFile("{.mission file}".into()))?;
+        let mission_file = filemap.remove(&mission_name).unwrap();
+        let in_mission = || Context::File(mission_name.clone());
+        let source = str::from_utf8(&mission_file).with_context(in_mission)?;
+
+        // parse each element of the mission on its own, so that failures
+        // can be traced back to where they happened
+        let mut expanded_size: Option<i32> = None;
+        let mut blanking_plates: Option<String> = None;
+        let mut meta: Option<String> = None;
+        let mut properties: Option<Properties> = None;
         let mut objects: HashMap<Uuid, Object> = HashMap::new();
-        for object in mission.raws.into_iter() {
-            let object = load_intermediary(object, &mut filemap)?;
-            objects.insert(object.uuid().clone(), object);
+        let mut layout = vec![];
+        let mut warnings = vec![];
+
+        for element in xmlcleaner::children(source).with_context(in_mission)? {
+
+            let fragment = &source[element.start..element.end];
+            let at_element = || Context::position(source, element.start);
+            let object = match element.name {
+                "ExpandedSize" => xmlcleaner::deserialize(fragment.as_bytes()).map(|v| { expanded_size = Some(v); None }),
+                "BLANKINGPLATES" => xmlcleaner::deserialize(fragment.as_bytes()).map(|v| { blanking_plates = Some(v); None }),
+                "Meta" => xmlcleaner::deserialize(fragment.as_bytes()).map(|v| { meta = Some(v); None }),
+                "PROPERTIES" => xmlcleaner::deserialize(fragment.as_bytes()).map(|v| { properties = Some(v); None }),
+                r#type => match load_object(r#type, fragment.as_bytes(), &mut filemap) {
+
+                    // the mission header can't be recovered, but objects can
+                    Err(e) if lenient => {
+                        let e = e.context(at_element()).context(in_mission());
+                        let object = keep_as_fragment(fragment, &e, &mut filemap);
+                        warnings.push(e);
+                        object.map(Some)
+                    },
+                    result => result,
+
+                },
+            };
+
+            let object = object
+                .with_context(at_element)
+                .with_context(in_mission)?;
+
+            // anything we don't know how to read is kept as it was
+            let slot = match object {
+                Some(object) => {
+                    let slot = Slot::Object(object.uuid().clone());
+                    objects.insert(object.uuid().clone(), object);
+                    slot
+                },
+                None if HEADER.contains(&element.name) => Slot::Header(element.name.into()),
+                None => Slot::Unknown(fragment.into()),
+            };
+            layout.push(slot);
+
         }
-    
+
+        let missing = |k: &str| Error::MissingProperty(k.into()).context(in_mission());
+        let expanded_size = expanded_size.ok_or_else(|| missing("ExpandedSize"))?;
+        let blanking_plates = blanking_plates.ok_or_else(|| missing("BLANKINGPLATES"))?;
+        let meta = meta.ok_or_else(|| missing("Meta"))?;
+        let mut properties = properties.ok_or_else(|| missing("PROPERTIES"))?;
+
         // move mission attributes to properties
-		mission.properties.insert_new("Expanded Size", mission.expanded_size.to_string(), "VTYPE_INT", None)?;
-        mission.properties.insert_new("Blanking Plates", mission.blanking_plates, "VTYPE_STRING", None)?;
-        mission.properties.insert_new("Meta", mission.meta, "VTYPE_STRING", None)?;
-        let mission = Self::new(mission.properties, filemap);
-		Ok((mission, objects))
+        properties.insert_new("Expanded Size", expanded_size.to_string(), "VTYPE_INT", None)?;
+        properties.insert_new("Blanking Plates", blanking_plates, "VTYPE_STRING", None)?;
+        properties.insert_new("Meta", meta, "VTYPE_STRING", None)?;
+        let mission = Self { layout, ..Self::new(properties, filemap) };
+        Ok((mission, objects, warnings))
+
+    }
+
+    // loads a single object from its fragment of a mission file, along with the
+    // files that belong only to it, i.e. from Object::to_fragment. if lenient,
+    // an object that fails to load is kept as the fragment instead
+    pub fn load_fragment(&mut self, fragment: &str, files: BTreeMap<String, Vec<u8>>, lenient: bool) -> Result<Object> {
+
+        let r#type = xmlcleaner::root(fragment)?.to_owned();
+
+        // files go in with the shared ones, and whatever the object doesn't take is removed again
+        let added: Vec<String> = files.keys().cloned().collect();
+        self.files.merge(files.into_iter().collect())?;
+        let object = match load_object(&r#type, fragment.as_bytes(), &mut self.files) {
+            Ok(None) => Err(Error::UnknownObjectType(r#type)),
+            Err(e) if lenient => keep_as_fragment(fragment, &e, &mut self.files),
+            result => result.map(Option::unwrap),
+        };
+        for name in added {
+            self.files.remove(&name);
+        }
+        object
+
+    }
+
+    // get the uuids of objects in the order they are written on save
+    pub fn order(&self, objects: &HashMap<Uuid, Object>) -> Vec<Uuid> {
+        self.arrange(objects)
+            .into_iter()
+            .filter_map(|slot| match slot {
+                Slot::Object(uuid) if objects.contains_key(&uuid) => Some(uuid),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // gives objects loaded from a save the uuids they had before it,
+    // from the order given when saving
+    pub fn restore_uuids(&mut self, mut objects: HashMap<Uuid, Object>, order: &[Uuid]) -> Result<HashMap<Uuid, Object>> {
+
+        let mut order = order.iter();
+        let mut restored = HashMap::new();
+        for slot in self.layout.iter_mut() {
+            let Slot::Object(uuid) = slot else { continue };
+            let Some(old) = order.next() else { return Err(Error::MismatchedOrder) };
+            let object = objects.remove(uuid).ok_or(Error::MismatchedOrder)?;
+            restored.insert(*old, object.with_uuid(*old));
+            *uuid = *old;
+        }
+
+        if order.next().is_some() || !objects.is_empty() {
+            return Err(Error::MismatchedOrder)
+        }
+        Ok(restored)
+
+    }
+
+    // lays out the mission for saving. objects added since loading go after
+    // the last loaded object, or before the trailing attributes if there are none,
+    // sorted by name so they are saved in the same order every time
+    fn arrange(&self, objects: &HashMap<Uuid, Object>) -> Vec<Slot> {
+
+        let mut layout = self.layout.clone();
+        let insert_at = match layout.iter().rposition(|s| matches!(s, Slot::Object(_))) {
+            Some(i) => i + 1,
+            None => layout.iter().position(|s| *s == Slot::Header("ExpandedSize".into())).unwrap_or(layout.len()),
+        };
+        let mut added: Vec<Uuid> = objects.keys()
+            .filter(|uuid| !layout.contains(&Slot::Object(**uuid)))
+            .cloned()
+            .collect();
+        added.sort_by_key(|uuid| (objects[uuid].name(), *uuid));
+        layout.splice(insert_at..insert_at, added.into_iter().map(Slot::Object));
+        layout
 
     }
 
@@ -157,19 +246,42 @@ impl MissionObject {
             return Err(Error::WrongTypeFound("meta".into(), "VTYPE_STRING".into()))
         };
     
+        let layout = self.arrange(&objects);
+
         // collapse objects
-        let collapsed = objects.into_values().map(|o| o.collapse()).collect::<Result<Vec<CollapsedObject>>>()?;
-    
-        // collect results
-        let mut raws = vec![];
-        for co in collapsed {
-            self.files.merge(co.files)?;
-            raws.push(co.raw);
+        let mut collapsed = objects.into_iter()
+            .map(|(uuid, o)| Ok((uuid, o.collapse()?)))
+            .collect::<Result<HashMap<Uuid, CollapsedObject>>>()?;
+
+        // serialize .mission one element at a time in the order it was loaded,
+        // so that fragments and unknown elements can be written back as they were
+        let mut elements = vec![];
+        for slot in layout {
+            let element = match slot {
+                Slot::Header(name) => match name.as_str() {
+                    "PROPERTIES" => xmlcleaner::serialize(&self.properties)?,
+                    "Meta" => xmlcleaner::serialize_with_root("Meta", &meta)?,
+                    "ExpandedSize" => xmlcleaner::serialize_with_root("ExpandedSize", &expanded_size)?,
+                    "BLANKINGPLATES" => xmlcleaner::serialize_with_root("BLANKINGPLATES", &blanking_plates)?,
+                    _ => continue,
+                },
+                Slot::Object(uuid) => {
+                    // objects deleted since loading have no slot to fill
+                    let Some(mut co) = collapsed.remove(&uuid) else { continue };
+                    self.files.merge(std::mem::take(&mut co.files))?;
+                    co.serialize_xml()?
+                },
+                Slot::Unknown(markup) => markup.into_bytes(),
+            };
+            elements.push(element);
         }
 
-        // serialize .mission
-        let intermediary_mission = IntermediaryMission::new(expanded_size, blanking_plates, meta, self.properties, raws);
-        let intermediary_mission_ser = xmlcleaner::serialize(&intermediary_mission)?;
+        let mut intermediary_mission_ser = b"<OBJECT: GAME >\n".to_vec();
+        for element in elements {
+            intermediary_mission_ser.extend(element);
+            intermediary_mission_ser.push(b'\n');
+        }
+        intermediary_mission_ser.extend(b"</OBJECT>\n");
     
         // TODO: we should probably make sure that hits is aactually alwyas Test.mission,
         // or otherwise save it earlier in execution
@@ -192,13 +304,82 @@ impl MissionObject {
 
 }
 
+// deserializes a raw object from its own fragment of the mission file,
+// or nothing if the element is not an object type we know about
+fn raw_from_fragment(r#type: &str, fragment: &[u8]) -> Option<Result<Box<dyn Raw>>> {
+
+    macro_rules! raw {
+        ($t:ty) => {
+            Some(xmlcleaner::deserialize::<$t>(fragment).map(|raw| Box::new(raw) as Box<dyn Raw>))
+        };
+    }
+
+    match r#type {
+        "ACTIVE_PROP" => raw!(ActivePropRaw),
+        "CHARACTER" => raw!(CharacterRaw),
+        "DOOR" => raw!(DoorRaw),
+        "LOCATION" => raw!(LocationRaw),
+        "MEDIA" => raw!(MediaRaw),
+        "PICKUP" => raw!(PickupRaw),
+        "PLAYER" => raw!(PlayerRaw),
+        "PROP" => raw!(PropRaw),
+        "RULE" => raw!(RuleRaw),
+        "SPECIAL_EFFECT" => raw!(SpecialEffectRaw),
+        "TRIGGER" => raw!(TriggerRaw),
+        "USER_DATA" => raw!(UserDataRaw),
+        _ => None,
+    }
+
+}
+
+// loads a single object from its fragment of the mission file
+fn load_object(r#type: &str, fragment: &[u8], filemap: &mut Filemap) -> Result<Option<Object>> {
+
+    let Some(raw) = raw_from_fragment(r#type, fragment) else { return Ok(None) };
+    let raw = raw.with_context(|| Context::object(r#type, None))?;
+    let name = raw.name();
+    let in_object = move || Context::object(r#type, name);
+
+    // find what serde dropped by writing the raw straight back out
+    let original = str::from_utf8(fragment)?;
+    let known = xmlcleaner::serialize(&*raw).with_context(in_object.clone())?;
+    let unknown = xmlcleaner::unknown_children(original, str::from_utf8(&known)?).with_context(in_object.clone())?;
+
+    let object = load_intermediary(raw, filemap).with_context(in_object)?;
+    Ok(Some(object.with_unknown(unknown)))
+
+}
+
+// keeps an object that failed to load as its markup, named if the error knows the name
+fn keep_as_fragment(fragment: &str, e: &Error, filemap: &mut Filemap) -> Result<Object> {
+    let name = e.contexts().into_iter().find_map(|c| match c {
+        Context::Object { name, .. } => name,
+        _ => None,
+    });
+    let raw = Box::new(FragmentRaw::new(fragment, name)?);
+    load_intermediary(raw, filemap)
+}
+
 // loads single object based on files in filemap
 fn load_intermediary(raw: Box<dyn Raw>, filemap: &mut Filemap) -> Result<Object> {
 
+    // files are only taken from the filemap once the object is done,
+    // so an object that fails to load leaves them all behind
+    let mut taken = vec![];
+
     macro_rules! intermediary_or_return {
         ($i:expr) => {
             match $i {
-                ConstructedObject::Done(object) => return Ok(object),
+                ConstructedObject::Done(object) => {
+                    let source = object.datafile_name().and_then(|name| filemap.get(name)).cloned();
+                    for file_name in taken {
+                        filemap.remove(&file_name);
+                    }
+                    return Ok(match source {
+                        Some(source) => object.with_datafile_source(source),
+                        None => object,
+                    })
+                },
                 ConstructedObject::More(intermediary) => intermediary,
             }
         };
@@ -211,15 +392,11 @@ fn load_intermediary(raw: Box<dyn Raw>, filemap: &mut Filemap) -> Result<Object>
         let mut files = Filemap::new();
         for prequisite in intermediary.files()? {
 
-            log!(prequisite.file_name);
-
-            let file = if prequisite.shared {
-                filemap.get(prequisite.file_name).cloned()
-            } else {
-                filemap.remove(prequisite.file_name)
-            };
-            
-            let file = file.ok_or(Error::MissingFile(prequisite.file_name.into()))?;
+            let file = filemap.get(prequisite.file_name).cloned()
+                .ok_or(Error::MissingFile(prequisite.file_name.into()))?;
+            if !prequisite.shared {
+                taken.push(prequisite.file_name.to_owned());
+            }
 
             files.add(prequisite.file_name, file)?;
             
@@ -311,4 +488,147 @@ fn load_intermediary(raw: Box<dyn Raw>, filemap: &mut Filemap) -> Result<Object>
 //         // let mut f = std::fs::File::create("mission.zip").unwrap();
 //         // f.write_all(&zip).unwrap();
 //     }
-// }
\ No newline at end of file
+// }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{get_test_dir, zip_files};
+
+    // zips the testcase mission after replacing part of one of its files
+    fn edited(file: &str, from: &str, to: &str) -> Vec<u8> {
+        let files = get_test_dir("testcase").into_iter()
+            .map(|(name, buf)| {
+                if name == file {
+                    let edited = str::from_utf8(&buf).unwrap().replacen(from, to, 1);
+                    (name, edited.into_bytes())
+                } else {
+                    (name, buf)
+                }
+            })
+            .collect();
+        zip_files(files)
+    }
+
+    // loads the testcase mission after replacing part of one of its files
+    fn load_edited(file: &str, from: &str, to: &str) -> Result<(MissionObject, HashMap<Uuid, Object>)> {
+        MissionObject::deserialize(Cursor::new(edited(file, from, to)))
+    }
+
+    // finds the markup kept for an object that failed to load
+    fn fragment_of<'a>(objects: &'a HashMap<Uuid, Object>, name: &str) -> Option<&'a Value> {
+        objects.values()
+            .find(|o| o.name().as_deref() == Some(name))
+            .and_then(|o| o.properties().get_value("Fragment").ok())
+    }
+
+    #[test]
+    fn missing_file_has_object() {
+        let e = load_edited("Test.mission", "", "").err().unwrap();
+        let expected = vec![
+            Context::File("Test.mission".into()),
+            Context::Position { line: 791, column: 1 },
+            Context::object("MEDIA", Some("Video 1".into())),
+        ];
+        assert_eq!(expected, e.contexts());
+        assert!(matches!(e.root(), Error::MissingFile(f) if f == "Sky Sunset.avi"));
+    }
+
+    #[test]
+    fn error_in_mission_has_position() {
+        let e = load_edited("Test.mission", "<ATTR: VALUE >-31.69334</ATTR>", "<ATTR: VALUE >abc</ATTR>").err().unwrap();
+        let expected = vec![
+            Context::File("Test.mission".into()),
+            Context::Position { line: 260, column: 1 },
+            Context::object("PROP", None),
+        ];
+        assert_eq!(expected, e.contexts());
+    }
+
+    #[test]
+    fn error_in_datafile_has_chain() {
+        let e = load_edited("suitcase_wheeled.prop", "Description = ", "Description: ").err().unwrap();
+        let expected = vec![
+            Context::File("Test.mission".into()),
+            Context::Position { line: 260, column: 1 },
+            Context::object("PROP", Some("Wheeled Suitcase".into())),
+            Context::File("suitcase_wheeled.prop".into()),
+            Context::Position { line: 2, column: 1 },
+        ];
+        assert_eq!(expected, e.contexts());
+        assert!(matches!(e.root(), Error::MalformedDatafileLine(_)));
+    }
+
+    #[test]
+    fn lenient_keeps_broken_objects() {
+        let (_, objects, warnings) = MissionObject::deserialize_lenient(Cursor::new(edited("Test.mission", "", ""))).unwrap();
+
+        assert_eq!(1, warnings.len());
+        assert!(matches!(warnings[0].root(), Error::MissingFile(f) if f == "Sky Sunset.avi"));
+        assert_eq!(13, objects.len());
+
+        let source = String::from_utf8(get_test_dir("testcase").into_iter().find(|(n, _)| n == "Test.mission").unwrap().1).unwrap();
+        let Some(Value::String(fragment)) = fragment_of(&objects, "Video 1") else { panic!("no fragment for Video 1") };
+        assert!(fragment.starts_with("<OBJECT: MEDIA >") && fragment.ends_with("</OBJECT>"));
+        assert!(source.contains(fragment.as_str()));
+    }
+
+    #[test]
+    fn lenient_saves_fragments_unchanged() {
+        let (mission, objects, _) = MissionObject::deserialize_lenient(Cursor::new(edited("Test.mission", "", ""))).unwrap();
+        let Some(Value::String(fragment)) = fragment_of(&objects, "Video 1").cloned() else { panic!("no fragment for Video 1") };
+
+        let saved = mission.serialize(objects).unwrap();
+        let mut files = Filemap::from_reader(Cursor::new(&saved)).unwrap();
+        let saved_mission = String::from_utf8(files.remove("Test.mission").unwrap()).unwrap();
+        assert!(saved_mission.contains(&fragment));
+
+        // and is kept again when the saved mission is loaded
+        let (_, objects, _) = MissionObject::deserialize_lenient(Cursor::new(saved)).unwrap();
+        assert_eq!(13, objects.len());
+        assert_eq!(Some(&Value::String(fragment)), fragment_of(&objects, "Video 1"));
+    }
+
+    #[test]
+    fn lenient_still_fails_on_header() {
+        let e = MissionObject::deserialize_lenient(Cursor::new(edited("Test.mission", "<ATTR: ExpandedSize >130", "<ATTR: ExpandedSize >abc"))).err().unwrap();
+        assert_eq!(Context::File("Test.mission".into()), e.contexts()[0]);
+    }
+
+    #[test]
+    fn unknown_elements_saved_in_place() {
+        let files = get_test_dir("testcase").into_iter()
+            .map(|(name, buf)| {
+                if name == "Test.mission" {
+                    let edited = str::from_utf8(&buf).unwrap()
+                        .replacen("<ATTR: Meta >bb68tcb0fu097d1v</ATTR>\n", "<ATTR: Meta >bb68tcb0fu097d1v</ATTR>\n<OBJECT: VEHICLE ><ATTR: Wheels >4</ATTR></OBJECT>\n", 1)
+                        .replacen("<OBJECT: PROP >\n", "<OBJECT: PROP >\n<ATTR: Colour >Red</ATTR>\n", 1);
+                    (name, edited.into_bytes())
+                } else {
+                    (name, buf)
+                }
+            })
+            .collect();
+        let (mission, objects, _) = MissionObject::deserialize_lenient(Cursor::new(zip_files(files))).unwrap();
+
+        let saved = mission.serialize(objects).unwrap();
+        let mut files = Filemap::from_reader(Cursor::new(&saved)).unwrap();
+        let saved_mission = String::from_utf8(files.remove("Test.mission").unwrap()).unwrap();
+        assert!(saved_mission.contains("<ATTR: Meta >bb68tcb0fu097d1v</ATTR>\n<OBJECT: VEHICLE ><ATTR: Wheels >4</ATTR></OBJECT>\n"));
+        assert!(saved_mission.contains("<OBJECT: PROP >\n<ATTR: Colour >Red</ATTR>\n"));
+    }
+    #[test]
+    fn added_objects_saved_by_name() {
+        let (mission, mut objects, _) = MissionObject::deserialize_lenient(Cursor::new(zip_files(get_test_dir("testcase")))).unwrap();
+        let television = objects.values().find(|o| o.name().as_deref() == Some("Television")).unwrap().clone();
+        for name in ["Zebra", "Aardvark", "Mole"] {
+            let mut added = television.clone().with_uuid(Uuid::new_v4());
+            added.set_property("Name", name).unwrap();
+            objects.insert(*added.uuid(), added);
+        }
+
+        let order = mission.order(&objects);
+        let names: Vec<Option<String>> = order[order.len() - 3..].iter().map(|uuid| objects[uuid].name()).collect();
+        assert_eq!(vec![Some("Aardvark".into()), Some("Mole".into()), Some("Zebra".into())], names);
+    }
+}
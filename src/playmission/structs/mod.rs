@@ -1,6 +1,7 @@
 pub mod active_prop;
 pub mod character;
 pub mod door;
+pub mod fragment;
 pub mod location;
 pub mod media;
 pub mod mission;
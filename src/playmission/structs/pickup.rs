@@ -4,7 +4,7 @@ use uuid::Uuid;
 use super::{ traits::{render_default_orb, ObjectHandler, Prerequisite}, CollapsedObject, ConstructedObject, Intermediary, Object, Properties, Property, Raw, Value };
 use crate::{playmission::{
     error::{PlaymissionError as Error, Result},
-    filemap::Filemap, xmlcleaner
+    datafile, filemap::Filemap
 }, three::{Mesh, Scene}};
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
@@ -33,6 +33,11 @@ impl Raw for PickupRaw {
         Box::new(self)
     }
 
+	// get ref to properties
+	fn properties(&self) -> &Properties {
+        &self.properties
+    }
+
 }
 
 impl Intermediary for PickupRaw {
@@ -54,7 +59,7 @@ impl Intermediary for PickupRaw {
         let orientation_property = Property::new(Value::String(self.orientation), None);
         self.properties.add("Orientation", orientation_property)?;
 
-        let datafile = Properties::from_datafile_default(datafile, default)?;
+        let datafile = Properties::from_datafile_default(&self.datafile_name, datafile, Self::DEFAULT, default)?;
         let handler = Box::new(Pickup::new());
 
         let new = Object::new(handler, self.properties, Some(datafile), Some(self.datafile_name), None);
@@ -117,7 +122,7 @@ impl ObjectHandler for Pickup {
 	fn collapse(&self, mut properties: Properties, datafile: Properties, datafile_name: Option<String>, mut files: Filemap) -> Result<CollapsedObject> {
 
         let datafile_name = datafile_name.ok_or(Error::NoDatafileName)?;
-        files.add(datafile_name.clone(), xmlcleaner::serialize(&datafile)?)?;
+        files.add(datafile_name.clone(), datafile::serialize(&datafile))?;
 
         let Value::String(orientation) = properties.take_value("Orientation")? else {
             return Err(Error::WrongTypeFound("Orientation".into(), "VTYPE_STRING".into()))
@@ -137,4 +142,8 @@ impl ObjectHandler for Pickup {
         "PICKUP"
     }
 
+    fn mesh(&self) -> Option<&Mesh> {
+        self.mesh.as_ref()
+    }
+
 }
\ No newline at end of file
@@ -1,11 +1,14 @@
 use serde::{ Serialize, Deserialize };
 use uuid::Uuid;
 
-use super::{ traits::{render_default_orb, ObjectHandler}, CollapsedObject, ConstructedObject, Object, Properties, Raw, Value };
+use wasm_bindgen::JsCast;
+
+use super::{ traits::{set_orientation, ObjectHandler}, CollapsedObject, ConstructedObject, Object, Properties, Raw, Value };
 use crate::{playmission::{
+    boundingbox::parse_coordinates,
     error::{PlaymissionError as Error, Result},
     filemap::Filemap
-}, three::{Mesh, Scene}};
+}, three::{ArrowHelper, CapsuleGeometry, ConeGeometry, Mesh, MeshBasicMaterial, Object3D, PerspectiveCamera, Scene, Vector3}};
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename = "PLAYER", rename_all = "SCREAMING_SNAKE_CASE")]
@@ -38,16 +41,35 @@ impl Raw for PlayerRaw {
         Box::new(self)
     }
 
+	// get ref to properties
+	fn properties(&self) -> &Properties {
+        &self.properties
+    }
+
 }
 
+// the player is drawn where they start, as a capsule with an arrow and the
+// outline of their view showing which way they face. their eye is a camera
+// the viewport can look through
+#[derive(Default)]
 pub struct Player {
     mesh: Option<Mesh>
 }
 
 impl Player {
 
+    // the name of the camera at the player's eye
+    pub const VIEW: &'static str = "player view";
+    pub const FOV: f32 = 75.0;
+    const RADIUS: f32 = 0.3;
+    const HEIGHT: f32 = 1.7;
+    // how far above the middle of the capsule the eye is
+    const EYE: f32 = 0.6;
+    // how far out the outline of the view is drawn
+    const REACH: f32 = 2.0;
+
     pub fn new() -> Player {
-        Player { mesh: None }
+        Player::default()
     }
 
 }
@@ -57,7 +79,43 @@ impl ObjectHandler for Player {
     // renders object to canvas
 	fn render(&mut self, uuid: &Uuid, properties: &Properties, datafile: &Properties, files: &Filemap, scene: &mut Scene) -> Result<()> {
 
-        self.mesh = Some(render_default_orb(uuid, properties, scene)?);
+        let mat = MeshBasicMaterial::new();
+        mat.color().set_rgb(0.0, 1.0, 0.3);
+        let capsule = Mesh::new(&CapsuleGeometry::new(Self::RADIUS, Self::HEIGHT - 2.0 * Self::RADIUS, 4, 8), &mat);
+        let Value::String(start) = properties.get_value("Start Position")? else {
+            return Err(Error::WrongTypeFound("Start Position".into(), "VTYPE_STRING".into()))
+        };
+        let [x, y, z] = parse_coordinates(start)
+            .ok_or_else(|| Error::WrongTypeCast("Start Position".into(), "coordinates".into()))?;
+        capsule.position().set(x, y, z);
+        if let Ok(Value::String(orientation)) = properties.get_value("Start Orientation") {
+            set_orientation(&capsule, orientation);
+        }
+        let capsule_object = capsule.dyn_ref::<Object3D>().unwrap();
+        capsule_object.set_name(uuid.to_string());
+
+        // everything else looks out from the eye, down -z as cameras do
+        let arrow = ArrowHelper::new(&Vector3::new(0.0, 0.0, -1.0), &Vector3::new(0.0, Self::EYE, 0.0), 1.0, 0x00ff4c);
+        capsule_object.add(arrow.unchecked_ref());
+
+        // a four sided cone reaching out from the eye, squared up with the view
+        let half_height = Self::REACH * (Self::FOV / 2.0).to_radians().tan();
+        let mat = MeshBasicMaterial::new();
+        mat.color().set_rgb(0.0, 1.0, 0.3);
+        mat.set_wireframe(true);
+        let frustum = Mesh::new(&ConeGeometry::new(half_height * std::f32::consts::SQRT_2, Self::REACH, 4), &mat);
+        frustum.rotation().set_x(std::f32::consts::FRAC_PI_2);
+        frustum.rotation().set_y(std::f32::consts::FRAC_PI_4);
+        frustum.position().set(0.0, Self::EYE, -Self::REACH / 2.0);
+        capsule_object.add(frustum.unchecked_ref());
+
+        let eye = PerspectiveCamera::new(Self::FOV.into(), 1.0, 0.1, 1000.0);
+        eye.position().set(0.0, Self::EYE, 0.0);
+        eye.set_name(Self::VIEW.into());
+        capsule_object.add(&eye);
+
+        scene.add(&capsule);
+        self.mesh = Some(capsule);
         Ok(())
 
 	}
@@ -66,12 +124,12 @@ impl ObjectHandler for Player {
 	fn view_property_update(&mut self, k: &str, v: &Value) -> Result<()> {
 
         let Some(ref mut mesh) = self.mesh else { return Ok(()) };
-        let Value::Float(f) = v else { return Ok(()) };
+        let Value::String(s) = v else { return Ok(()) };
 
+        // the player is drawn where they start, so their saved position doesn't move them
         match k {
-            "Position X" => { mesh.position().set_x(*f); }
-            "Position Y" => { mesh.position().set_y(*f); }
-            "Position Z" => { mesh.position().set_z(*f); }
+            "Start Position" => if let Some([x, y, z]) = parse_coordinates(s) { mesh.position().set(x, y, z); },
+            "Start Orientation" => set_orientation(mesh, s),
             _ => {},
         };
 
@@ -5,7 +5,7 @@ use serde::{ Deserialize, Serialize, Deserializer, Serializer };
 
 use crate::playmission::{
     datafile,
-    error::{PlaymissionError as Error, Result},
+    error::{chain, Context, PlaymissionError as Error, Result, ResultExt},
     xmlcleaner,
 };
 
@@ -30,6 +30,8 @@ impl Value {
                     .map_err(|_| e("VTYPE_BOOL"))?)
             ),
             "VTYPE_FLOAT" => {
+                // missionmaker sometimes writes floats with a trailing 'f'
+                let vr = vr.strip_suffix('f').unwrap_or(vr);
                 Ok(Self::Float(vr.parse().map_err(|_| e("VTYPE_FLOAT"))?))
             }
             "VTYPE_INT" => {
@@ -48,20 +50,40 @@ impl Value {
             Self::String(_) => "VTYPE_STRING",
         }
     }
+
+    // get a float or int as a float, i.e. for sizes written either way
+    pub fn number(&self) -> Option<f32> {
+        match self {
+            Self::Float(f) => Some(*f),
+            Self::Int(i) => Some(*i as f32),
+            _ => None,
+        }
+    }
 }
 
 impl ToString for Value {
-    // returns parsed string from value type
+    // returns string from value type, formatted the way missionmaker writes it
     fn to_string(&self) -> String {
         match self {
-            Self::Bool(v) => v.to_string(),
-            Self::Float(v) => v.to_string(),
+            Self::Bool(true) => "True".into(),
+            Self::Bool(false) => "False".into(),
+            Self::Float(v) => {
+                let s = v.to_string();
+                if s.contains(|c: char| !(c.is_ascii_digit() || c == '-')) { s } else { s + ".0" }
+            },
             Self::Int(v) => v.to_string(),
             Self::String(v) => v.clone(),
         }
     }
 }
 
+// value as it was written in the file it was loaded from
+#[derive(Debug, Clone)]
+struct Lexical {
+    value: Value,
+    text: String,
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 struct PropertyRaw {
@@ -77,34 +99,38 @@ impl PropertyRaw {
         Self {
             name: name.into(),
             vtype: property.value().vtype().into(),
-            value: property.value().to_string(),
+            value: property.text(),
             flags: property.flags.clone(),
         }
     }
 }
 
-// intermediary for a property
-#[derive(Debug, PartialEq, Clone)]
+// intermediary for a property. remembers how its value was written when
+// loaded, so that it can be written back the same way unless changed
+#[derive(Debug, Clone)]
 pub struct Property {
     value: Value,
     flags: Option<String>,
+    lexical: Option<Lexical>,
 }
 
 impl Property {
     // creates new intermediary property
     pub fn new(value: Value, flags: Option<String>) -> Self {
-        Self { value, flags: flags }
+        Self { value, flags: flags, lexical: None }
+    }
+
+    // creates new intermediary property from its text in a file
+    fn parse(text: String, vtype: &str, flags: Option<String>) -> Result<Self> {
+        let value = Value::new(&text, vtype)?;
+        let lexical = Some(Lexical { value: value.clone(), text });
+        Ok(Self { value, flags, lexical })
     }
 
     // parses new property with typed enum from raw serde output
-    fn from_raw(mut raw: PropertyRaw) -> Result<(String, Self)> {
+    fn from_raw(raw: PropertyRaw) -> Result<(String, Self)> {
         let name = raw.name;
-        // remove trailing 'f' from floats
-        if raw.vtype == "VTYPE_FLOAT" && raw.value.ends_with("f") {
-            raw.value.truncate(raw.value.len() - 1)
-        }
-        let value = Value::new(raw.value, &raw.vtype)?;
-        let new = Self { value, flags: raw.flags };
+        let new = Self::parse(raw.value, &raw.vtype, raw.flags).with_context(|| Context::Property(name.clone()))?;
         Ok((name, new))
     }
 
@@ -113,6 +139,14 @@ impl Property {
         &self.value
     }
 
+    // get value as text, as it was loaded if it still has the same value
+    pub fn text(&self) -> String {
+        match &self.lexical {
+            Some(lexical) if lexical.value == self.value => lexical.text.clone(),
+            _ => self.value.to_string(),
+        }
+    }
+
     // get ref to flags
     fn flags(&self) -> Option<&str> {
         self.flags.as_deref()
@@ -124,6 +158,13 @@ impl Property {
     }
 }
 
+// properties are the same if their values are, however they were written
+impl PartialEq for Property {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value && self.flags == other.flags
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
 #[serde(rename = "PROPERTIES", rename_all = "SCREAMING_SNAKE_CASE")]
 struct PropertiesRaw {
@@ -168,11 +209,14 @@ impl Properties {
         Ok(new)
     }
 
-    // parses new mapping from datafile and default buffers
-    pub fn from_datafile_default(datafile: Vec<u8>, default: Vec<u8>) -> Result<Self> {
-        let parsed_datafile: Properties = datafile::deserialize(&datafile)?;
-        let parsed_default: Properties = xmlcleaner::deserialize(&default)?;
+    // parses new mapping from named datafile and default buffers
+    pub fn from_datafile_default(datafile_name: &str, datafile: Vec<u8>, default_name: &str, default: Vec<u8>) -> Result<Self> {
+        let parsed_datafile: Properties = datafile::deserialize(&datafile)
+            .with_context(|| Context::File(datafile_name.into()))?;
+        let parsed_default: Properties = xmlcleaner::deserialize(&default)
+            .with_context(|| Context::File(default_name.into()))?;
         parsed_default.default_for(parsed_datafile)
+            .with_context(|| Context::File(datafile_name.into()))
     }
 
     // add property to map, returning error if name already taken
@@ -195,7 +239,8 @@ impl Properties {
     {
         let new = Property {
             value: Value::new(v, vtype)?,
-            flags: flags.map(|s| s.into())
+            flags: flags.map(|s| s.into()),
+            lexical: None,
         };
 
         self.insert(k.into(), new);
@@ -236,23 +281,23 @@ impl Properties {
                 continue
             };
 
-            let v_vtype = v.value().vtype().into();
-            let default_vtype = default.value().vtype().into();
-
-            let new_value = if let Value::String(s) = v.value {
-                Value::new(s, default_vtype)?
-            } else if v_vtype == default_vtype {
-                v.value
-            } else {
-                return Err(Error::MergedWrongType(k.into(), v_vtype, default_vtype))
-            };
+            let v_vtype: String = v.value().vtype().into();
+            let default_vtype: String = default.value().vtype().into();
 
             let new_flags = match v.flags {
                 Some(flags) => Some(flags),
                 None => default.flags
             };
 
-            self.insert(k, Property::new(new_value, new_flags));
+            let new = if let Value::String(s) = v.value {
+                Property::parse(s, &default_vtype, new_flags).with_context(|| Context::Property(k.clone()))?
+            } else if v_vtype == default_vtype {
+                Property { flags: new_flags, ..v }
+            } else {
+                return Err(Error::MergedWrongType(k.into(), v_vtype, default_vtype))
+            };
+
+            self.insert(k, new);
 
         }
 
@@ -275,8 +320,10 @@ impl Properties {
     
             if let Err(e) = self.insert_new(k, v.into(), vtype, flags.as_deref()) {
                 self.insert(k.into(), existing);
-                Err(e)
+                Err(e.context(Context::Property(k.into())))
             } else {
+                // keep how it was loaded, in case it is changed back
+                self.get_mut(k).unwrap().lexical = existing.lexical.clone();
                 Ok(Some(existing.take_value()))
             }
 
@@ -321,7 +368,7 @@ impl<'de> Deserialize<'de> for Properties {
     {
         use serde::de::Error;
         let raw = PropertiesRaw::deserialize(deserializer)?;
-        Properties::from_raw(raw).map_err(Error::custom)
+        Properties::from_raw(raw).map_err(|e| Error::custom(chain(&e)))
     }
 }
 
@@ -333,4 +380,59 @@ impl Serialize for Properties {
         let raw = PropertiesRaw::from_properties(self);
         raw.serialize(serializer)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn property(name: &str, vtype: &str, value: &str) -> String {
+        format!("<OBJECT: PROPERTY ><ATTR: NAME >{name}</ATTR><ATTR: VTYPE >{vtype}</ATTR><ATTR: VALUE >{value}</ATTR><ATTR: FLAGS ></ATTR></OBJECT>")
+    }
+
+    fn load(properties: &[(&str, &str, &str)]) -> Properties {
+        let inner: String = properties.iter().map(|(n, t, v)| property(n, t, v)).collect();
+        xmlcleaner::deserialize(format!("<OBJECT: PROPERTIES >{inner}</OBJECT>").as_bytes()).unwrap()
+    }
+
+    fn text(properties: &Properties, k: &str) -> String {
+        properties.get(k).unwrap().text()
+    }
+
+    #[test]
+    fn unedited_values_keep_their_text() {
+        let properties = load(&[
+            ("A", "VTYPE_FLOAT", "1.5f"),
+            ("B", "VTYPE_FLOAT", "-31.974569"),
+            ("C", "VTYPE_BOOL", "True"),
+            ("D", "VTYPE_INT", "007"),
+        ]);
+        assert_eq!(Value::Float(1.5), *properties.get_value("A").unwrap());
+        assert_eq!("1.5f", text(&properties, "A"));
+        assert_eq!("-31.974569", text(&properties, "B"));
+        assert_eq!("True", text(&properties, "C"));
+        assert_eq!("007", text(&properties, "D"));
+
+        let saved = String::from_utf8(xmlcleaner::serialize(&properties).unwrap()).unwrap();
+        for value in ["1.5f", "-31.974569", "True", "007"] {
+            assert!(saved.contains(&format!("<ATTR: VALUE >{value}</ATTR>")));
+        }
+    }
+
+    #[test]
+    fn edited_values_are_canonical() {
+        let mut properties = load(&[
+            ("A", "VTYPE_FLOAT", "1.5f"),
+            ("B", "VTYPE_BOOL", "True"),
+        ]);
+
+        properties.replace_or_add_property_value("A", "2").unwrap();
+        properties.replace_or_add_property_value("B", "false").unwrap();
+        assert_eq!("2.0", text(&properties, "A"));
+        assert_eq!("False", text(&properties, "B"));
+
+        // changing back to the loaded value gives back the loaded text
+        properties.replace_or_add_property_value("A", "1.5").unwrap();
+        assert_eq!("1.5f", text(&properties, "A"));
+    }
+}
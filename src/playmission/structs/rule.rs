@@ -27,6 +27,11 @@ impl Raw for RuleRaw {
         Box::new(self)
     }
 
+	// get ref to properties
+	fn properties(&self) -> &Properties {
+        &self.properties
+    }
+
 }
 
 pub struct Rule;
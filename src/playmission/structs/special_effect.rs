@@ -1,11 +1,13 @@
 use serde::{ Serialize, Deserialize };
 use uuid::Uuid;
 
-use super::{ traits::{render_default_orb, ObjectHandler, Prerequisite}, CollapsedObject, ConstructedObject, Intermediary, Object, Properties, Property, Raw, Value };
+use wasm_bindgen::JsCast;
+
+use super::{ traits::{render_handle, Face, ObjectHandler, Prerequisite}, CollapsedObject, ConstructedObject, Intermediary, Object, Properties, Property, Raw, Value };
 use crate::{playmission::{
     error::{PlaymissionError as Error, Result},
-    filemap::Filemap, xmlcleaner
-}, three::{Mesh, Scene}};
+    datafile, filemap::Filemap
+}, three::{CanvasTexture, Mesh, MeshBasicMaterial, RingGeometry, Scene, Sprite, SpriteMaterial}};
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename = "SPECIAL_EFFECT", rename_all = "SCREAMING_SNAKE_CASE")]
@@ -33,6 +35,11 @@ impl Raw for SpecialEffectRaw {
         Box::new(self)
     }
 
+	// get ref to properties
+	fn properties(&self) -> &Properties {
+        &self.properties
+    }
+
 }
 
 impl Intermediary for SpecialEffectRaw {
@@ -54,7 +61,7 @@ impl Intermediary for SpecialEffectRaw {
         let orientation_property = Property::new(Value::String(self.orientation), None);
         self.properties.add("Orientation", orientation_property)?;
 
-        let datafile = Properties::from_datafile_default(datafile, default)?;
+        let datafile = Properties::from_datafile_default(&self.datafile_name, datafile, Self::DEFAULT, default)?;
         let handler = Box::new(SpecialEffect::new());
 
         let new = Object::new(handler, self.properties, Some(datafile), Some(self.datafile_name), None);
@@ -66,14 +73,56 @@ impl Intermediary for SpecialEffectRaw {
 
 }
 
+// effects are drawn as an icon that always faces the camera, with a ring
+// on the floor around it showing how far they reach. handles on the ring
+// drag the radius in and out
+#[derive(Default)]
 pub struct SpecialEffect {
-    mesh: Option<Mesh>
+    sprite: Option<Sprite>,
+    ring: Option<Mesh>,
+    handles: Vec<Mesh>,
 }
 
 impl SpecialEffect {
 
+    // datafile key for how far the effect reaches
+    pub const RADIUS_KEY: &'static str = "Radius";
+    const DEFAULT_RADIUS: f32 = 1.0;
+
     pub fn new() -> SpecialEffect {
-        SpecialEffect { mesh: None }
+        SpecialEffect::default()
+    }
+
+    // get how far the effect reaches from a datafile
+    pub fn radius(datafile: &Properties) -> f32 {
+        datafile.get_value(Self::RADIUS_KEY).ok().and_then(Value::number).unwrap_or(Self::DEFAULT_RADIUS)
+    }
+
+    // draws the icon, a star on a disc
+    fn draw_icon(canvas: &web_sys::HtmlCanvasElement) {
+        let Some(context) = canvas.get_context("2d").ok().flatten()
+            .and_then(|c| c.dyn_into::<web_sys::CanvasRenderingContext2d>().ok()) else { return };
+        let size = f64::from(canvas.width());
+        context.set_fill_style(&"#ff9900".into());
+        context.begin_path();
+        let _ = context.arc(size / 2.0, size / 2.0, size / 2.0, 0.0, std::f64::consts::TAU);
+        context.fill();
+        context.set_fill_style(&"#ffffff".into());
+        context.set_font(&format!("{}px monospace", size * 0.7));
+        context.set_text_align("center");
+        context.set_text_baseline("middle");
+        let _ = context.fill_text("\u{2736}", size / 2.0, size / 2.0);
+    }
+
+    // sizes the ring and moves the handles out to it
+    fn set_radius(&self, radius: f32) {
+        if let Some(ref ring) = self.ring {
+            ring.scale().set(radius, radius, 1.0);
+        }
+        let around = [(1.0, 0.0), (-1.0, 0.0), (0.0, 1.0), (0.0, -1.0)];
+        for (handle, (x, z)) in self.handles.iter().zip(around) {
+            handle.position().set(x * radius, 0.0, z * radius);
+        }
     }
 
 }
@@ -83,7 +132,38 @@ impl ObjectHandler for SpecialEffect {
     // renders object to canvas
 	fn render(&mut self, uuid: &Uuid, properties: &Properties, datafile: &Properties, files: &Filemap, scene: &mut Scene) -> Result<()> {
 
-        self.mesh = Some(render_default_orb(uuid, properties, scene)?);
+        let canvas = web_sys::window().unwrap()
+            .document().unwrap()
+            .create_element("canvas").unwrap()
+            .dyn_into::<web_sys::HtmlCanvasElement>().unwrap();
+        canvas.set_width(64);
+        canvas.set_height(64);
+        Self::draw_icon(&canvas);
+        let mat = SpriteMaterial::new();
+        mat.set_map(&CanvasTexture::new(&canvas));
+        let sprite = Sprite::new(&mat);
+        sprite.position().set(properties.get_float("Position X")?, properties.get_float("Position Y")?, properties.get_float("Position Z")?);
+        sprite.set_name(uuid.to_string());
+
+        // the ring is drawn flat, so lies across the floor once turned
+        let mat = MeshBasicMaterial::new();
+        mat.color().set_rgb(1.0, 0.6, 0.0);
+        let ring = Mesh::new(&RingGeometry::new(0.97, 1.0, 48), &mat);
+        ring.rotation().set_x(-std::f32::consts::FRAC_PI_2);
+        sprite.add(ring.unchecked_ref());
+
+        self.handles = (0..4)
+            .map(|_| {
+                let handle = render_handle(Face::Edge);
+                sprite.add(handle.unchecked_ref());
+                handle
+            })
+            .collect();
+
+        scene.add(sprite.unchecked_ref());
+        self.sprite = Some(sprite);
+        self.ring = Some(ring);
+        self.set_radius(Self::radius(datafile));
         Ok(())
 
 	}
@@ -91,13 +171,19 @@ impl ObjectHandler for SpecialEffect {
 	// handles internal state for property updates
 	fn view_property_update(&mut self, k: &str, v: &Value) -> Result<()> {
 
-        let Some(ref mut mesh) = self.mesh else { return Ok(()) };
+        // the radius is in the datafile, whose updates come through here too
+        if let (Self::RADIUS_KEY, Some(radius)) = (k, v.number()) {
+            self.set_radius(radius);
+            return Ok(())
+        }
+
+        let Some(ref sprite) = self.sprite else { return Ok(()) };
         let Value::Float(f) = v else { return Ok(()) };
 
         match k {
-            "Position X" => { mesh.position().set_x(*f); }
-            "Position Y" => { mesh.position().set_y(*f); }
-            "Position Z" => { mesh.position().set_z(*f); }
+            "Position X" => { sprite.position().set_x(*f); }
+            "Position Y" => { sprite.position().set_y(*f); }
+            "Position Z" => { sprite.position().set_z(*f); }
             _ => {},
         };
 
@@ -118,7 +204,7 @@ impl ObjectHandler for SpecialEffect {
 	fn collapse(&self, mut properties: Properties, datafile: Properties, datafile_name: Option<String>, mut files: Filemap) -> Result<CollapsedObject> {
 
         let datafile_name = datafile_name.ok_or(Error::NoDatafileName)?;
-        files.add(datafile_name.clone(), xmlcleaner::serialize(&datafile)?)?;
+        files.add(datafile_name.clone(), datafile::serialize(&datafile))?;
 
         let Value::String(orientation) = properties.take_value("Orientation")? else {
             return Err(Error::WrongTypeFound("Orientation".into(), "VTYPE_STRING".into()))
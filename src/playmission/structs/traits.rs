@@ -1,12 +1,15 @@
+use std::collections::BTreeMap;
+
 use erased_serde::Serialize;
-use gloo_console::log;
 use uuid::Uuid;
 use wasm_bindgen::JsCast;
 use crate::{
 	playmission::{
-		error::{PlaymissionError as Error, Result},
+		error::{Context, PlaymissionError as Error, Result, ResultExt},
 		filemap::Filemap,
-		structs::player::Player
+		parse_orientation,
+		structs::player::Player,
+		xmlcleaner::{self, Unknown}
 	},
 	three::{
 		BoxGeometry,
@@ -17,7 +20,7 @@ use crate::{
 	}
 };
 
-use super::{active_prop::ActiveProp, character::Character, door::Door, location::Location, media::Media, pickup::Pickup, prop::Prop, rule::Rule, special_effect::{SpecialEffect, SpecialEffectRaw}, trigger::Trigger, user_data::UserData, Properties, Value};
+use super::{active_prop::ActiveProp, character::Character, door::Door, fragment::Fragment, location::Location, media::Media, pickup::Pickup, prop::Prop, rule::Rule, special_effect::{SpecialEffect, SpecialEffectRaw}, trigger::Trigger, user_data::UserData, Properties, Value};
 
 pub trait Raw: Serialize {
 
@@ -27,6 +30,19 @@ pub trait Raw: Serialize {
 
 	// cast self to serialize
 	fn as_serialize(self: Box<Self>) -> Box<dyn Serialize>;
+
+	// get ref to properties
+	fn properties(&self) -> &Properties;
+
+	// get name, if the object has one
+	fn name(&self) -> Option<String> {
+		self.properties().get_value("Name").ok().map(|n| n.to_string())
+	}
+
+	// serializes self to its fragment of the mission file
+	fn serialize_xml(self: Box<Self>) -> Result<Vec<u8>> {
+		xmlcleaner::serialize(&*self.as_serialize())
+	}
 	
 }
 
@@ -60,13 +76,24 @@ impl ConstructedObject {
 pub struct CollapsedObject {
 	pub raw: Box<dyn Raw>,
 	pub files: Filemap,
+	pub unknown: Vec<Unknown>,
 }
 
 impl CollapsedObject {
 
 	// contruct new
 	pub fn new(raw: Box<dyn Raw>, files: Filemap) -> Self {
-		Self{ raw, files }
+		Self{ raw, files, unknown: vec![] }
+	}
+
+	// serializes raw to its fragment of the mission file, putting back
+	// anything it didn't know how to read
+	pub fn serialize_xml(self) -> Result<Vec<u8>> {
+		let xml = self.raw.serialize_xml()?;
+		if self.unknown.is_empty() {
+			return Ok(xml)
+		}
+		Ok(xmlcleaner::insert_children(std::str::from_utf8(&xml)?, &self.unknown)?.into())
 	}
 
 }
@@ -91,7 +118,10 @@ pub struct Object {
 	properties: Properties,
 	datafile: Properties,
 	datafile_name: Option<String>,
+	// the datafile as it was loaded, until it is edited
+	datafile_source: Option<Vec<u8>>,
 	files: Filemap,
+	unknown: Vec<Unknown>,
 }
 
 impl Object {
@@ -104,10 +134,32 @@ impl Object {
 			properties,
 			datafile: datafile.unwrap_or_default(),
 			datafile_name,
-			files: files.unwrap_or_default()
+			datafile_source: None,
+			files: files.unwrap_or_default(),
+			unknown: vec![],
 		}
 	}
 
+	// keeps elements the object was loaded with but couldn't read,
+	// to be written back in place on save
+	pub fn with_unknown(mut self, unknown: Vec<Unknown>) -> Self {
+		self.unknown = unknown;
+		self
+	}
+
+	// keeps the datafile as it was written, to be saved back as it was
+	// unless it is edited
+	pub fn with_datafile_source(mut self, source: Vec<u8>) -> Self {
+		self.datafile_source = Some(source);
+		self
+	}
+
+	// replaces the generated uuid, i.e. to match one from a previous load
+	pub fn with_uuid(mut self, uuid: Uuid) -> Self {
+		self.uuid = uuid;
+		self
+	}
+
 	// various getters
 	// get ref to uuid
 	pub fn uuid(&self) -> &Uuid {
@@ -134,34 +186,103 @@ impl Object {
 		self.properties.get_value("Name").ok().map(|n| n.to_string())
 	}
 
+	// get type, as named in the mission file
+	pub fn r#type(&self) -> &'static str {
+		self.handler.r#type()
+	}
+
+	// get name of datafile, if the object has one
+	pub fn datafile_name(&self) -> Option<&str> {
+		self.datafile_name.as_deref()
+	}
+
+	// renames the datafile, if the object has one
+	pub fn with_datafile_name(mut self, datafile_name: impl Into<String>) -> Self {
+		if self.datafile_name.is_some() {
+			self.datafile_name = Some(datafile_name.into());
+		}
+		self
+	}
+
+	// get the object as its fragment of the mission file, along with the files
+	// that belong only to it, to be loaded back with MissionObject::load_fragment
+	pub fn to_fragment(&self) -> Result<(String, BTreeMap<String, Vec<u8>>)> {
+		let mut collapsed = self.clone().collapse()?;
+		let files = std::mem::take(&mut collapsed.files).into_iter().collect();
+		let markup = String::from_utf8(collapsed.serialize_xml()?).map_err(|e| e.utf8_error())?;
+		Ok((markup, files))
+	}
+
+	// get type and name for error reporting
+	fn context(&self) -> Context {
+		Context::object(self.handler.r#type(), self.name())
+	}
+
 	// pass various setters through to objecthandler to ensure
 	// any additional processing [three.js updates etc.] take place
 	// returns old value
 	pub fn set_property(&mut self, k: impl AsRef<str>, v: impl Into<String>) -> Result<Option<Value>> {
 		let k = k.as_ref();
-		let old = self.properties.replace_or_add_property_value(k, v)?;
+		let old = self.properties.replace_or_add_property_value(k, v).with_context(|| self.context())?;
 		let v = self.properties.get_value(k).unwrap();
-		self.handler.view_property_update(k, v)?;
+		self.handler.view_property_update(k, v).with_context(|| self.context())?;
+		if let ("Orientation", Value::String(orientation), Some(mesh)) = (k, v, self.handler.mesh()) {
+			set_orientation(mesh, orientation);
+		}
 		Ok(old)
 	}
 
 	pub fn set_datafile(&mut self,  k: impl AsRef<str>, v: impl Into<String>) -> Result<Option<Value>> {
 		let k = k.as_ref();
-		let old = self.datafile.replace_or_add_property_value(k, v)?;
+		let old = self.datafile.replace_or_add_property_value(k, v).with_context(|| self.context())?;
+		self.datafile_source = None;
 		let v = self.datafile.get_value(k).unwrap();
-		self.handler.view_property_update(k.as_ref(), v)?;
+		self.handler.view_property_update(k.as_ref(), v).with_context(|| self.context())?;
+		Ok(old)
+	}
+
+	// removes a property or datafile property, returning its value
+	pub fn remove_property(&mut self, k: impl AsRef<str>) -> Result<Value> {
+		self.properties.take_value(k).with_context(|| self.context())
+	}
+
+	pub fn remove_datafile(&mut self, k: impl AsRef<str>) -> Result<Value> {
+		let old = self.datafile.take_value(k).with_context(|| self.context())?;
+		self.datafile_source = None;
 		Ok(old)
 	}
 
 	pub fn set_file(&mut self, k: impl Into<String> + AsRef<str>, v: Vec<u8>) -> Result<Option<Vec<u8>>> {
-		self.handler.view_file_update(k.as_ref(), &v)?;
+		self.handler.view_file_update(k.as_ref(), &v).with_context(|| self.context())?;
 		let old = self.files.insert(k.into(), v);
 		Ok(old)
 	}
 
+	// renames a file the object owns, along with any property naming it
+	pub fn rename_file(&mut self, k: &str, new: impl Into<String>) -> Result<()> {
+		let new = new.into();
+		let v = self.files.remove(k).ok_or_else(|| Error::MissingFile(k.into()))?;
+		self.files.insert(new.clone(), v);
+		let naming: Vec<String> = self.properties.iter()
+			.filter(|(_, p)| matches!(p.value(), Value::String(s) if s == k))
+			.map(|(key, _)| key.clone())
+			.collect();
+		for key in naming {
+			self.set_property(key, new.clone())?;
+		}
+		Ok(())
+	}
+
 	// passthroughs to specific behaviour in handler, see ObjectHandler
 	pub fn collapse(self) -> Result<CollapsedObject> {
-		self.handler.collapse(self.properties, self.datafile, self.datafile_name, self.files)
+		let source = self.datafile_source.zip(self.datafile_name.clone());
+		let mut collapsed = self.handler.collapse(self.properties, self.datafile, self.datafile_name, self.files)?;
+		// untouched datafiles go back as they were, rather than with defaults merged in
+		if let Some((source, datafile_name)) = source {
+			collapsed.files.insert(datafile_name, source);
+		}
+		collapsed.unknown = self.unknown;
+		Ok(collapsed)
 	}
 
 	pub fn render(&mut self, scene: &mut Scene) -> Result<()> {
@@ -173,11 +294,11 @@ impl Object {
 // hacky shit.....  .... .
 impl Clone for Object {
 	fn clone(&self) -> Self {
-		log!(self.handler.r#type());
 		let handler: Box<dyn ObjectHandler> = match self.handler.r#type() {
 			"ACTIVE_PROP" => Box::new(ActiveProp::new()),
 			"CHARACTER" => Box::new(Character::new()),
 			"DOOR" => Box::new(Door::new()),
+			"FRAGMENT" => Box::new(Fragment),
 			"LOCATION" => Box::new(Location::new()),
 			"MEDIA" => Box::new(Media),
 			"PICKUP" => Box::new(Pickup::new()),
@@ -196,7 +317,9 @@ impl Clone for Object {
 			properties: self.properties.clone(),
 			datafile: self.datafile.clone(),
 			datafile_name: self.datafile_name.clone(),
+			datafile_source: self.datafile_source.clone(),
 			files: self.files.clone(),
+			unknown: self.unknown.clone(),
 		}
 	}
 }
@@ -208,7 +331,8 @@ impl PartialEq for Object {
 		(self.properties == other.properties) &&
 		(self.datafile == other.datafile) &&
 		(self.datafile_name == other.datafile_name) &&
-		(self.files == other.files)
+		(self.files == other.files) &&
+		(self.unknown == other.unknown)
 	}
 }
 
@@ -232,6 +356,11 @@ pub trait ObjectHandler {
 	// returns type. handlers should almost certainly be enums in a sane system ....
 	fn r#type(&self) -> &'static str;
 
+	// the mesh it was rendered as, if any, for updates every object shares
+	fn mesh(&self) -> Option<&Mesh> {
+		None
+	}
+
 }
 
 pub fn render_default_orb(uuid: &Uuid, properties: &Properties, scene: &mut Scene) -> Result<Mesh> {
@@ -246,6 +375,9 @@ pub fn render_default_orb(uuid: &Uuid, properties: &Properties, scene: &mut Scen
 	let pos_z = properties.get_float("Position Z")?;
 
 	cube.position().set(pos_x, pos_y, pos_z);
+	if let Ok(Value::String(orientation)) = properties.get_value("Orientation") {
+		set_orientation(&cube, orientation);
+	}
 
 	cube.dyn_ref::<Object3D>()
 		.unwrap()
@@ -254,4 +386,57 @@ pub fn render_default_orb(uuid: &Uuid, properties: &Properties, scene: &mut Scen
 	scene.add(&cube);
 	Ok(cube)
 
-}
\ No newline at end of file
+}
+
+// turns a mesh to an orientation property, leaving it be if malformed
+pub fn set_orientation(mesh: &Mesh, orientation: &str) {
+	if let Some([w, x, y, z]) = parse_orientation(orientation) {
+		mesh.quaternion().set(x, y, z, w);
+	}
+}
+
+// a face of a volume that can be dragged to resize it, i.e. a side of a
+// trigger, by axis and whether it faces along or against it, or the edge of
+// an effect's radius
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Face {
+	Side { axis: usize, positive: bool },
+	Edge,
+}
+
+impl Face {
+
+	pub const SIDES: [Face; 6] = [
+		Face::Side { axis: 0, positive: true },
+		Face::Side { axis: 0, positive: false },
+		Face::Side { axis: 1, positive: true },
+		Face::Side { axis: 1, positive: false },
+		Face::Side { axis: 2, positive: true },
+		Face::Side { axis: 2, positive: false },
+	];
+
+	// get the name of the handle this face is dragged by, i.e. "face x+"
+	pub fn name(&self) -> String {
+		match self {
+			Face::Side { axis, positive } => format!("face {}{}", ["x", "y", "z"][*axis], if *positive { "+" } else { "-" }),
+			Face::Edge => "face edge".into(),
+		}
+	}
+
+	// get the face a handle is for, from its name
+	pub fn from_name(name: &str) -> Option<Self> {
+		Self::SIDES.into_iter().chain([Face::Edge]).find(|face| face.name() == name)
+	}
+
+}
+
+// a small handle to drag a face by, hidden until the viewport shows it
+pub fn render_handle(face: Face) -> Mesh {
+	let mat = MeshBasicMaterial::new();
+	mat.color().set_rgb(1.0, 1.0, 0.0);
+	let handle = Mesh::new(&BoxGeometry::new(0.2, 0.2, 0.2), &mat);
+	let handle_object = handle.dyn_ref::<Object3D>().unwrap();
+	handle_object.set_name(face.name());
+	handle_object.set_visible(false);
+	handle
+}
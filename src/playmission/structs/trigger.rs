@@ -1,11 +1,13 @@
 use serde::{ Serialize, Deserialize };
 use uuid::Uuid;
 
-use super::{ traits::{render_default_orb, ObjectHandler, Prerequisite}, CollapsedObject, ConstructedObject, Intermediary, Object, Properties, Property, Raw, Value };
+use wasm_bindgen::JsCast;
+
+use super::{ traits::{render_handle, set_orientation, Face, ObjectHandler, Prerequisite}, CollapsedObject, ConstructedObject, Intermediary, Object, Properties, Property, Raw, Value };
 use crate::{playmission::{
     error::{PlaymissionError as Error, Result},
-    filemap::Filemap, xmlcleaner
-}, three::{Mesh, Scene}};
+    datafile, filemap::Filemap
+}, three::{BoxGeometry, Mesh, MeshBasicMaterial, Object3D, Scene}};
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename = "TRIGGER", rename_all = "SCREAMING_SNAKE_CASE")]
@@ -33,6 +35,11 @@ impl Raw for TriggerRaw {
         Box::new(self)
     }
 
+	// get ref to properties
+	fn properties(&self) -> &Properties {
+        &self.properties
+    }
+
 }
 
 impl Intermediary for TriggerRaw {
@@ -54,7 +61,7 @@ impl Intermediary for TriggerRaw {
         let orientation_property = Property::new(Value::String(self.orientation), None);
         self.properties.add("Orientation", orientation_property)?;
 
-        let datafile = Properties::from_datafile_default(datafile, default)?;
+        let datafile = Properties::from_datafile_default(&self.datafile_name, datafile, Self::DEFAULT, default)?;
         let handler = Box::new(Trigger::new());
 
         let new = Object::new(handler, self.properties, Some(datafile), Some(self.datafile_name), None);
@@ -65,14 +72,37 @@ impl Intermediary for TriggerRaw {
 
 }
 
+// triggers are drawn as the volume they fire in, see through so whatever
+// is inside shows, with a handle on each side to drag it by
+#[derive(Default)]
 pub struct Trigger {
-    mesh: Option<Mesh>
+    mesh: Option<Mesh>,
+    handles: Vec<Mesh>,
 }
 
 impl Trigger {
 
+    // datafile keys for the size of the volume across x, y and z
+    pub const SIZE_KEYS: [&'static str; 3] = ["Size X", "Size Y", "Size Z"];
+    const DEFAULT_SIZE: f32 = 1.0;
+
     pub fn new() -> Trigger {
-        Trigger { mesh: None }
+        Trigger::default()
+    }
+
+    // get the size of the volume from a datafile, as x, y and z
+    pub fn size(datafile: &Properties) -> [f32; 3] {
+        Self::SIZE_KEYS.map(|k| datafile.get_value(k).ok().and_then(Value::number).unwrap_or(Self::DEFAULT_SIZE))
+    }
+
+    // the volume is a unit box stretched to size, so handles are shrunk back
+    // by as much, and stay the same size whatever the volume is
+    fn fit_handles(&self) {
+        let Some(ref mesh) = self.mesh else { return };
+        let scale = mesh.scale();
+        for handle in &self.handles {
+            handle.scale().set(1.0 / scale.x(), 1.0 / scale.y(), 1.0 / scale.z());
+        }
     }
 
 }
@@ -82,7 +112,36 @@ impl ObjectHandler for Trigger {
     // renders object to canvas
 	fn render(&mut self, uuid: &Uuid, properties: &Properties, datafile: &Properties, files: &Filemap, scene: &mut Scene) -> Result<()> {
 
-        self.mesh = Some(render_default_orb(uuid, properties, scene)?);
+        let mat = MeshBasicMaterial::new();
+        mat.color().set_rgb(0.8, 0.2, 0.8);
+        mat.set_transparent(true);
+        mat.set_opacity(0.25);
+        let volume = Mesh::new(&BoxGeometry::new(1.0, 1.0, 1.0), &mat);
+        let [x, y, z] = Self::size(datafile);
+        volume.scale().set(x, y, z);
+        volume.position().set(properties.get_float("Position X")?, properties.get_float("Position Y")?, properties.get_float("Position Z")?);
+        if let Ok(Value::String(orientation)) = properties.get_value("Orientation") {
+            set_orientation(&volume, orientation);
+        }
+        let volume_object = volume.dyn_ref::<Object3D>().unwrap();
+        volume_object.set_name(uuid.to_string());
+
+        self.handles = Face::SIDES.iter()
+            .map(|face| {
+                let handle = render_handle(*face);
+                if let Face::Side { axis, positive } = face {
+                    let mut offset = [0.0; 3];
+                    offset[*axis] = if *positive { 0.5 } else { -0.5 };
+                    handle.position().set(offset[0], offset[1], offset[2]);
+                }
+                volume_object.add(handle.unchecked_ref());
+                handle
+            })
+            .collect();
+
+        scene.add(&volume);
+        self.mesh = Some(volume);
+        self.fit_handles();
         Ok(())
 
 	}
@@ -91,12 +150,21 @@ impl ObjectHandler for Trigger {
 	fn view_property_update(&mut self, k: &str, v: &Value) -> Result<()> {
 
         let Some(ref mut mesh) = self.mesh else { return Ok(()) };
-        let Value::Float(f) = v else { return Ok(()) };
 
-        match k {
-            "Position X" => { mesh.position().set_x(*f); }
-            "Position Y" => { mesh.position().set_y(*f); }
-            "Position Z" => { mesh.position().set_z(*f); }
+        // sizes are in the datafile, whose updates come through here too
+        if let (Some(axis), Some(size)) = (Self::SIZE_KEYS.iter().position(|key| *key == k), v.number()) {
+            let scale = mesh.scale();
+            let mut sizes = [scale.x(), scale.y(), scale.z()];
+            sizes[axis] = size;
+            scale.set(sizes[0], sizes[1], sizes[2]);
+            self.fit_handles();
+            return Ok(())
+        }
+
+        match (k, v) {
+            ("Position X", Value::Float(f)) => { mesh.position().set_x(*f); }
+            ("Position Y", Value::Float(f)) => { mesh.position().set_y(*f); }
+            ("Position Z", Value::Float(f)) => { mesh.position().set_z(*f); }
             _ => {},
         };
 
@@ -117,7 +185,7 @@ impl ObjectHandler for Trigger {
 	fn collapse(&self, mut properties: Properties, datafile: Properties, datafile_name: Option<String>, mut files: Filemap) -> Result<CollapsedObject> {
 
         let datafile_name = datafile_name.ok_or(Error::NoDatafileName)?;
-        files.add(datafile_name.clone(), xmlcleaner::serialize(&datafile)?)?;
+        files.add(datafile_name.clone(), datafile::serialize(&datafile))?;
 
         let Value::String(orientation) = properties.take_value("Orientation")? else {
             return Err(Error::WrongTypeFound("Orientation".into(), "VTYPE_STRING".into()))
@@ -137,4 +205,8 @@ impl ObjectHandler for Trigger {
         "TRIGGER"
     }
 
+    fn mesh(&self) -> Option<&Mesh> {
+        self.mesh.as_ref()
+    }
+
 }
\ No newline at end of file
@@ -37,6 +37,11 @@ impl Raw for UserDataRaw {
         Box::new(self)
     }
 
+	// get ref to properties
+	fn properties(&self) -> &Properties {
+        &self.properties
+    }
+
 }
 
 pub struct UserData;
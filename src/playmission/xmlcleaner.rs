@@ -1,17 +1,17 @@
 use std::collections::HashSet;
 use std::str;
 
-use fancy_regex::{ Captures, Match, Regex, Replacer };
 use lazy_static::lazy_static;
 use serde::{ Deserialize, Serialize };
-use quick_xml::{ se, de };
+use quick_xml::{ se, de, Reader, Writer };
+use quick_xml::events::{ BytesEnd, BytesStart, BytesText, Event };
 
-use super::error::{ PlaymissionError, Result };
+use super::error::{ Context, PlaymissionError as Error, Result };
 
 lazy_static! {
     static ref OBJECTS: HashSet<&'static str> = {
         let mut m = HashSet::new();
-        m.insert("ACTIVEPROP");
+        m.insert("ACTIVE_PROP");
         m.insert("CHARACTER");
         m.insert("DOOR");
         m.insert("GAME");
@@ -23,99 +23,289 @@ lazy_static! {
         m.insert("PROPERTIES");
         m.insert("PROPERTY");
         m.insert("RULE");
-        m.insert("SPECIALEFFECT");
+        m.insert("SPECIAL_EFFECT");
         m.insert("TRIGGER");
-        m.insert("USERDATA");
+        m.insert("USER_DATA");
         m
     };
 }
 
-// returns position of first byte of matching tag for first found opening tag
-fn find_matching_tag(s: &str) -> Result<Option<Match>> {
-    let mut depth = 0;
-    let tag = Regex::new(r"<(.)(.*?)>").unwrap();
-    let option = tag.captures_iter(s).find(|captures| {
-        if let Ok(captures) = captures {
-            if captures.get(1).unwrap().as_str() == "/" {
-                depth -= 1;
-                depth == 0
-            } else {
-                depth += 1;
-                false
+// single piece of missionmaker markup, with byte offsets into the source
+#[derive(Debug, PartialEq)]
+pub struct Token<'a> {
+    pub kind: TokenKind<'a>,
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum TokenKind<'a> {
+    Open(&'a str),
+    Close,
+    Text(&'a str),
+}
+
+// splits missionmaker markup into tokens in a single pass. both the
+// illegal namespace syntax <OBJECT: NAME > and plain <NAME> open tags
+pub struct Tokenizer<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl<'a> Tokenizer<'a> {
+
+    // create new over string
+    pub fn new(s: &'a str) -> Self {
+        Self { s, pos: 0 }
+    }
+
+    // reads the tag starting at the current position
+    fn tag(&mut self) -> Result<Token<'a>> {
+        let start = self.pos;
+        let len = self.s[start..].find('>').ok_or(Error::MalformedTag(start))?;
+        let end = start + len + 1;
+        let inner = &self.s[start + 1..end - 1];
+        self.pos = end;
+
+        let kind = if inner.starts_with('/') {
+            TokenKind::Close
+        } else {
+            // <OBJECT: NAME > keeps only the name, <NAME> is kept whole
+            let name = match inner.split_once(": ") {
+                Some((_, name)) => name.trim(),
+                None => inner.trim(),
+            };
+            if name.is_empty() || name.contains(char::is_whitespace) {
+                return Err(Error::MalformedTag(start))
             }
+            TokenKind::Open(name)
+        };
+
+        Ok(Token { kind, start, end })
+    }
+
+    // reads text up to the next tag or end of string
+    fn text(&mut self) -> Token<'a> {
+        let start = self.pos;
+        let end = self.s[start..].find('<').map_or(self.s.len(), |i| start + i);
+        self.pos = end;
+        Token { kind: TokenKind::Text(&self.s[start..end]), start, end }
+    }
+
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Result<Token<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos >= self.s.len() {
+            None
+        } else if self.s[self.pos..].starts_with('<') {
+            Some(self.tag())
         } else {
-            true
+            Some(Ok(self.text()))
         }
-    });
+    }
+}
 
-    option.map(|result|
-        result.map(|captures|
-            captures.get(0).unwrap()
-        ).map_err(|e|
-            PlaymissionError::from(e)
-        )
-    ).transpose()
+// direct child of the root element, with byte offsets into the source
+#[derive(Debug, PartialEq)]
+pub struct Element<'a> {
+    pub name: &'a str,
+    pub start: usize,
+    pub end: usize,
 }
 
-// replaces missionmaker illegal namespace syntax with xml-compliant elements,
-// well-suited for quick-xml parsing
-fn clean<T: Into<String>>(s: T) -> Result<String> {
+// splits missionmaker markup into the direct children of its root element
+pub fn children(s: &str) -> Result<Vec<Element<'_>>> {
+
+    let mut elements = vec![];
+    let mut open: Vec<(&str, usize)> = vec![];
 
-    let opening_tag = Regex::new(r"<\w+: (\w+) >").unwrap();
-    let mut s = s.into();
-    let mut result: String = String::from("");
+    for token in Tokenizer::new(s) {
+        let token = token.map_err(|e| locate(s, e))?;
+        match token.kind {
+            TokenKind::Open(name) => open.push((name, token.start)),
+            TokenKind::Close => {
+                let (name, start) = open.pop().ok_or_else(|| locate(s, Error::UnexpectedEndTag(token.start)))?;
+                if open.len() == 1 {
+                    elements.push(Element { name, start, end: token.end });
+                }
+            },
+            TokenKind::Text(_) => {},
+        }
+    }
+
+    if let Some((name, start)) = open.pop() {
+        return Err(locate(s, Error::NoMatchingTag(name.into(), start)))
+    }
+
+    Ok(elements)
+
+}
+
+// get the name of the outermost element of some markup
+pub fn root(s: &str) -> Result<&str> {
+    for token in Tokenizer::new(s) {
+        if let TokenKind::Open(name) = token.map_err(|e| locate(s, e))?.kind {
+            return Ok(name)
+        }
+    }
+    Err(Error::NoMatchingTag("{root}".into(), 0))
+}
+
+// child element that nothing knows how to read, kept as markup along with
+// how many known siblings came before it
+#[derive(Debug, PartialEq, Clone)]
+pub struct Unknown {
+    pub index: usize,
+    pub markup: String,
+}
 
-    while let Some(captures) = opening_tag.captures(&s)? {
+// compares the children of two roots, returning those in the original
+// which were dropped in the known version, i.e. after a serde round trip
+pub fn unknown_children(original: &str, known: &str) -> Result<Vec<Unknown>> {
 
-        let open = captures.get(0).unwrap();
-        let name = captures.get(1).unwrap().as_str();
-        let new_open = &*format!("<{name}>");
-        let new_close = &*format!("</{name}>");
+    let mut remaining: Vec<&str> = children(known)?.into_iter().map(|e| e.name).collect();
+    let mut unknown = vec![];
+    let mut index = 0;
 
-        if let Some(end) = find_matching_tag(&s)? {
-            result.push_str(&s[..open.start()]);
-            result.push_str(new_open);
-            result.push_str(&*clean(&s[open.end()..end.start()])?);
-            result.push_str(new_close);
-            s = s[end.end()..].to_string()
+    for element in children(original)? {
+        if let Some(i) = remaining.iter().position(|name| *name == element.name) {
+            remaining.remove(i);
+            index += 1;
         } else {
-            return Err(PlaymissionError::NoMatchingTag(open.as_str().into()))
+            unknown.push(Unknown { index, markup: original[element.start..element.end].to_owned() });
         }
+    }
 
-    };
+    Ok(unknown)
+
+}
+
+// inserts unknown children back under the root, each before the known
+// child at its index or at the end
+pub fn insert_children(s: &str, unknown: &[Unknown]) -> Result<String> {
 
-    result.push_str(&s);
+    let known = children(s)?;
+    let root_end = s.rfind("</").ok_or(Error::NoMatchingTag("{root}".into(), 0))?;
+
+    let mut result = String::with_capacity(s.len());
+    let mut last = 0;
+    for u in unknown {
+        let offset = known.get(u.index).map_or(root_end, |e| e.start);
+        let offset = s[..offset].trim_end_matches([' ', '\t']).len();
+        result.push_str(&s[last..offset]);
+        result.push_str(&u.markup);
+        result.push('\n');
+        last = offset;
+    }
+    result.push_str(&s[last..]);
 
     Ok(result)
 
 }
 
-// replace overlapping regex, assuming the replacement pattern will not
-// shift any characters before the index
-fn replace_overlapping<T: Into<String>, R: Replacer>(s: T, re: &str, mut replacement: R) -> Result<String> {
-    let re = Regex::new(re).unwrap();
-    let mut s = s.into();
-    let mut result = String::from("");
-    while let Some(mtch) = re.find(s.as_ref())? {
-        let i = mtch.start();
-        result.push_str(&s[0..i]);
-        s = re.replace(&s[i..], replacement.by_ref()).into();
+// adds line and column to errors that only know their byte offset
+fn locate(s: &str, e: Error) -> Error {
+    let offset = match &e {
+        Error::MalformedTag(offset) | Error::NoMatchingTag(_, offset) | Error::UnexpectedEndTag(offset) => *offset,
+        _ => return e,
+    };
+    e.context(Context::position(s, offset))
+}
+
+// replaces missionmaker illegal namespace syntax with xml-compliant elements,
+// well-suited for quick-xml parsing
+pub fn clean(s: &str) -> Result<String> {
+
+    let mut writer = Writer::new(Vec::with_capacity(s.len()));
+    let mut open: Vec<(&str, usize)> = vec![];
+
+    for token in Tokenizer::new(s) {
+        let token = token?;
+        let event = match token.kind {
+            TokenKind::Open(name) => {
+                open.push((name, token.start));
+                Event::Start(BytesStart::new(name))
+            },
+            TokenKind::Close => {
+                let (name, _) = open.pop().ok_or(Error::UnexpectedEndTag(token.start))?;
+                Event::End(BytesEnd::new(name))
+            },
+            TokenKind::Text(text) => Event::Text(BytesText::from_escaped(text)),
+        };
+        writer.write_event(event)?;
     }
-    result.push_str(&s);
-    Ok(result)
+
+    if let Some((name, start)) = open.pop() {
+        return Err(Error::NoMatchingTag(name.into(), start))
+    }
+
+    Ok(String::from_utf8(writer.into_inner()).map_err(|e| e.utf8_error())?)
+
 }
 
-// replaces xml-compliant elements with missionmaker illegal namespace syntax
-fn dirty<T: Into<String>>(s: T) -> Result<String> {
+// replaces xml-compliant elements with missionmaker illegal namespace syntax.
+// elements holding other elements become OBJECTs and leaves become ATTRs,
+// so an element is held back until we know which one it is
+pub fn dirty(s: &str) -> Result<String> {
 
-    let legal_tag = r"(?s)<(\w+)>(.*?)</\1>";
-    let illegal_tag = |captures: &Captures| {
-        let subtype = captures.get(1).unwrap().as_str();
-        let contents = captures.get(2).unwrap().as_str();
-        let tag = if OBJECTS.contains(subtype) { "OBJECT" } else { "ATTR" };
-        format!("<{tag}: {subtype} >{contents}</{tag}>")
-    };
-    replace_overlapping(s, legal_tag, illegal_tag)
+    let mut reader = Reader::from_str(s);
+    let mut result = String::with_capacity(s.len() * 2);
+    let mut open: Vec<(String, usize)> = vec![];
+    let mut pending: Option<(String, usize, String)> = None;
+
+    let name_of = |bytes: &[u8]| -> Result<String> { Ok(str::from_utf8(bytes)?.to_owned()) };
+
+    loop {
+        let start = reader.buffer_position();
+        match reader.read_event()? {
+            Event::Start(e) => {
+                if let Some((name, start, text)) = pending.take() {
+                    result.push_str(&format!("<OBJECT: {name} >{text}"));
+                    open.push((name, start));
+                }
+                pending = Some((name_of(e.name().as_ref())?, start, String::new()));
+            },
+            Event::Empty(e) => {
+                if let Some((name, start, text)) = pending.take() {
+                    result.push_str(&format!("<OBJECT: {name} >{text}"));
+                    open.push((name, start));
+                }
+                let name = name_of(e.name().as_ref())?;
+                let tag = if OBJECTS.contains(&*name) { "OBJECT" } else { "ATTR" };
+                result.push_str(&format!("<{tag}: {name} ></{tag}>"));
+            },
+            Event::End(_) => {
+                if let Some((name, _, text)) = pending.take() {
+                    let tag = if OBJECTS.contains(&*name) { "OBJECT" } else { "ATTR" };
+                    result.push_str(&format!("<{tag}: {name} >{text}</{tag}>"));
+                } else {
+                    open.pop().ok_or(Error::UnexpectedEndTag(start))?;
+                    result.push_str("</OBJECT>");
+                }
+            },
+            Event::Text(e) => {
+                let text = str::from_utf8(&e)?;
+                match pending {
+                    Some((_, _, ref mut buffered)) => buffered.push_str(text),
+                    None => result.push_str(text),
+                }
+            },
+            Event::Eof => break,
+            _ => {},
+        }
+    }
+
+    if let Some((name, start, _)) = pending {
+        return Err(Error::NoMatchingTag(name, start))
+    }
+    if let Some((name, start)) = open.pop() {
+        return Err(Error::NoMatchingTag(name, start))
+    }
+
+    Ok(result)
 
 }
 
@@ -123,17 +313,25 @@ fn dirty<T: Into<String>>(s: T) -> Result<String> {
 pub fn deserialize<T: for<'de> Deserialize<'de>>(v: &[u8]) -> Result<T> {
 
     let s = str::from_utf8(v)?;
-    let clean = clean(s)?;
+    let clean = clean(s).map_err(|e| locate(s, e))?;
     Ok(de::from_str(&clean)?)
 
 }
 
-pub fn serialize(v: &impl Serialize) -> Result<Vec<u8>> {
+pub fn serialize(v: &(impl Serialize + ?Sized)) -> Result<Vec<u8>> {
     let mut buf = String::new();
     let mut se = se::Serializer::new(&mut buf);
     se.indent(' ', 4);
     v.serialize(se)?;
-    let dirty = dirty(buf)?;
+    let dirty = dirty(&buf)?;
+    Ok(dirty.into())
+}
+
+// sama, for values with no name of their own i.e. mission attributes
+pub fn serialize_with_root(root: &str, v: &(impl Serialize + ?Sized)) -> Result<Vec<u8>> {
+    let mut buf = String::new();
+    v.serialize(se::Serializer::with_root(&mut buf, Some(root))?)?;
+    let dirty = dirty(&buf)?;
     Ok(dirty.into())
 }
 
@@ -147,7 +345,7 @@ mod tests {
         let expected = get_test_str("cleaner_clean.txt");
         let dirty = get_test_str("cleaner_dirty.txt");
 
-        let found = clean(dirty).unwrap();
+        let found = clean(&dirty).unwrap();
         assert_eq!(expected, found)
     }
 
@@ -156,7 +354,74 @@ mod tests {
         let expected = get_test_str("cleaner_dirty.txt");
         let clean = get_test_str("cleaner_clean.txt");
 
-        let found = dirty(clean).unwrap();
+        let found = dirty(&clean).unwrap();
         assert_eq!(expected, found)
     }
+
+    #[test]
+    fn tokens_keep_offsets() {
+        let s = "<OBJECT: GAME >\n<ATTR: Meta >a: b</ATTR>\n</OBJECT>";
+        let found: Vec<Token> = Tokenizer::new(s).collect::<Result<_>>().unwrap();
+        let expected = vec![
+            Token { kind: TokenKind::Open("GAME"), start: 0, end: 15 },
+            Token { kind: TokenKind::Text("\n"), start: 15, end: 16 },
+            Token { kind: TokenKind::Open("Meta"), start: 16, end: 29 },
+            Token { kind: TokenKind::Text("a: b"), start: 29, end: 33 },
+            Token { kind: TokenKind::Close, start: 33, end: 40 },
+            Token { kind: TokenKind::Text("\n"), start: 40, end: 41 },
+            Token { kind: TokenKind::Close, start: 41, end: 50 },
+        ];
+        assert_eq!(expected, found)
+    }
+
+    #[test]
+    fn clean_unclosed() {
+        let found = clean("\n<OBJECT: GAME >\n<ATTR: Meta >a</ATTR>\n");
+        assert!(matches!(found, Err(Error::NoMatchingTag(name, 1)) if name == "GAME"))
+    }
+
+    #[test]
+    fn clean_unopened() {
+        let found = clean("<ATTR: Meta >a</ATTR></OBJECT>");
+        assert!(matches!(found, Err(Error::UnexpectedEndTag(21))))
+    }
+
+    #[test]
+    fn dirty_empty_and_unknown() {
+        let found = dirty("<GAME><NEW_THING><Foo>1</Foo></NEW_THING><VALUE/><PROPERTIES/></GAME>").unwrap();
+        let expected = "<OBJECT: GAME ><OBJECT: NEW_THING ><ATTR: Foo >1</ATTR></OBJECT><ATTR: VALUE ></ATTR><OBJECT: PROPERTIES ></OBJECT></OBJECT>";
+        assert_eq!(expected, found)
+    }
+
+    #[test]
+    fn children_of_root() {
+        let s = "<OBJECT: GAME >\n<ATTR: Meta >a</ATTR>\n<OBJECT: PROP ><ATTR: X >1</ATTR></OBJECT>\n</OBJECT>";
+        let expected = vec![
+            Element { name: "Meta", start: 16, end: 37 },
+            Element { name: "PROP", start: 38, end: 80 },
+        ];
+        assert_eq!(expected, children(s).unwrap())
+    }
+
+    #[test]
+    fn unknown_children_in_position() {
+        let original = "<OBJECT: PROP ><ATTR: New >1</ATTR><OBJECT: PROPERTIES ></OBJECT><ATTR: DATAFILE >a.prop</ATTR><OBJECT: THING ><ATTR: X >2</ATTR></OBJECT></OBJECT>";
+        let known = "<OBJECT: PROP >\n<OBJECT: PROPERTIES ></OBJECT>\n<ATTR: DATAFILE >b.prop</ATTR>\n</OBJECT>";
+
+        let unknown = unknown_children(original, known).unwrap();
+        let expected = vec![
+            Unknown { index: 0, markup: "<ATTR: New >1</ATTR>".into() },
+            Unknown { index: 2, markup: "<OBJECT: THING ><ATTR: X >2</ATTR></OBJECT>".into() },
+        ];
+        assert_eq!(expected, unknown);
+
+        let expected = "<OBJECT: PROP >\n<ATTR: New >1</ATTR>\n<OBJECT: PROPERTIES ></OBJECT>\n<ATTR: DATAFILE >b.prop</ATTR>\n<OBJECT: THING ><ATTR: X >2</ATTR></OBJECT>\n</OBJECT>";
+        assert_eq!(expected, insert_children(known, &unknown).unwrap());
+    }
+
+    #[test]
+    fn deserialize_locates_errors() {
+        let found = deserialize::<String>("<OBJECT: GAME >\n\n  </OBJECT></OBJECT>".as_bytes()).unwrap_err();
+        assert_eq!(vec![Context::Position { line: 3, column: 12 }], found.contexts());
+    }
 }
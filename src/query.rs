@@ -0,0 +1,210 @@
+// searches over objects, from text like `bookcase type:PROP Position Y>1`.
+// plain words match names and property values, and `key op value` terms
+// filter on a property, or on the object type with `type`
+
+use std::collections::BTreeSet;
+
+use thiserror::Error;
+
+use crate::playmission::{Object, Value};
+
+// longer operators first, so ">=" isn't read as ">"
+const OPERATORS: [(&str, Operator); 7] = [
+    ("!=", Operator::NotEqual),
+    (">=", Operator::AtLeast),
+    ("<=", Operator::AtMost),
+    (":", Operator::Equal),
+    ("=", Operator::Equal),
+    (">", Operator::Greater),
+    ("<", Operator::Less),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operator {
+    Equal,
+    NotEqual,
+    Greater,
+    Less,
+    AtLeast,
+    AtMost,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Term {
+    Text(String),
+    Type { r#type: String, operator: Operator },
+    Property { key: String, operator: Operator, value: String },
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Query(Vec<Term>);
+
+impl Query {
+
+    // parses a query. keys may contain spaces, so words before a filter
+    // are taken into its key for as long as they make up a known key
+    pub fn parse(text: &str, keys: &BTreeSet<String>) -> Result<Self> {
+
+        let mut terms = vec![];
+        for token in tokens(text) {
+
+            let Some((at, symbol, operator)) = find_operator(&token) else {
+                terms.push(Term::Text(token.to_lowercase()));
+                continue
+            };
+            let (mut key, value) = (token[..at].to_string(), token[at + symbol.len()..].to_string());
+            if key.is_empty() || value.is_empty() {
+                return Err(QueryError::MalformedFilter(token))
+            }
+
+            while let Some(Term::Text(word)) = terms.last() {
+                let longer = format!("{word} {key}");
+                if !keys.iter().any(|k| k.eq_ignore_ascii_case(&longer)) { break }
+                key = longer;
+                terms.pop();
+            }
+
+            // type names the object type, even where objects have a Type property
+            if key.eq_ignore_ascii_case("type") {
+                if !matches!(operator, Operator::Equal | Operator::NotEqual) {
+                    return Err(QueryError::OrderedType(token))
+                }
+                terms.push(Term::Type { r#type: value, operator });
+                continue
+            }
+            if !matches!(operator, Operator::Equal | Operator::NotEqual) && value.parse::<f32>().is_err() {
+                return Err(QueryError::NotANumber(value))
+            }
+            let known = keys.iter().find(|k| k.eq_ignore_ascii_case(&key)).ok_or(QueryError::UnknownKey(key))?;
+            terms.push(Term::Property { key: known.clone(), operator, value });
+
+        }
+        Ok(Self(terms))
+
+    }
+
+    // whether an object matches every term
+    pub fn matches(&self, object: &Object) -> bool {
+        self.0.iter().all(|term| match term {
+            Term::Text(word) => object.properties().values().any(|p| p.value().to_string().to_lowercase().contains(word)),
+            Term::Type { r#type, operator } => object.r#type().eq_ignore_ascii_case(r#type) == (*operator == Operator::Equal),
+            Term::Property { key, operator, value } => {
+                let found = object.properties().get_value(key).or_else(|_| object.datafile().get_value(key));
+                found.is_ok_and(|found| compare(found, *operator, value))
+            },
+        })
+    }
+
+}
+
+// compares numbers as numbers, and anything else as text ignoring case
+fn compare(found: &Value, operator: Operator, value: &str) -> bool {
+
+    let number = match found {
+        Value::Float(f) => Some(*f),
+        Value::Int(i) => Some(*i as f32),
+        _ => None,
+    };
+
+    match (number, value.parse::<f32>().ok()) {
+        (Some(found), Some(value)) => match operator {
+            Operator::Equal => found == value,
+            Operator::NotEqual => found != value,
+            Operator::Greater => found > value,
+            Operator::Less => found < value,
+            Operator::AtLeast => found >= value,
+            Operator::AtMost => found <= value,
+        },
+        _ => match operator {
+            Operator::Equal => found.to_string().eq_ignore_ascii_case(value),
+            Operator::NotEqual => !found.to_string().eq_ignore_ascii_case(value),
+            _ => false,
+        },
+    }
+
+}
+
+// finds the first operator in a token, with where it starts
+fn find_operator(token: &str) -> Option<(usize, &'static str, Operator)> {
+    let at = token.find(|c| matches!(c, ':' | '=' | '!' | '<' | '>'))?;
+    let (symbol, operator) = OPERATORS.iter().find(|(symbol, _)| token[at..].starts_with(symbol))?;
+    Some((at, symbol, *operator))
+}
+
+// splits on whitespace, except inside double quotes, i.e. `Name:"Rule 1"`
+fn tokens(text: &str) -> Vec<String> {
+
+    let mut tokens = vec![];
+    let mut token = String::new();
+    let mut quoted = false;
+
+    for c in text.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !token.is_empty() { tokens.push(std::mem::take(&mut token)) }
+            },
+            c => token.push(c),
+        }
+    }
+    if !token.is_empty() { tokens.push(token) }
+    tokens
+
+}
+
+pub type Result<T> = std::result::Result<T, QueryError>;
+
+#[derive(Debug, Error)]
+pub enum QueryError {
+    #[error("filter {0} is not in the form 'key:value' or 'key>value'")]
+    MalformedFilter(String),
+    #[error("no property named {0} to filter on")]
+    UnknownKey(String),
+    #[error("can't compare against {0}, which is not a number")]
+    NotANumber(String),
+    #[error("filter {0} can only match a type with ':' or '!='")]
+    OrderedType(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tea::TeaHandler;
+    use crate::utils::testcase;
+
+    fn search(th: &TeaHandler, text: &str) -> Vec<String> {
+        let query = th.query(text).unwrap();
+        th.display_object_groups(&query).into_iter()
+            .flat_map(|(_, objects)| objects)
+            .map(|(_, name)| name)
+            .collect()
+    }
+
+    #[test]
+    fn keys_take_preceding_words() {
+        let keys = ["Position Y", "Active"].into_iter().map(String::from).collect();
+        let query = Query::parse("bookcase position y>=1 Active:false", &keys).unwrap();
+        assert_eq!(Query(vec![
+            Term::Text("bookcase".into()),
+            Term::Property { key: "Position Y".into(), operator: Operator::AtLeast, value: "1".into() },
+            Term::Property { key: "Active".into(), operator: Operator::Equal, value: "false".into() },
+        ]), query);
+
+        assert!(matches!(Query::parse("Positon Y>1", &keys), Err(QueryError::UnknownKey(k)) if k == "Y"));
+        assert!(matches!(Query::parse("Position Y>high", &keys), Err(QueryError::NotANumber(_))));
+        assert!(matches!(Query::parse("type>prop", &keys), Err(QueryError::OrderedType(_))));
+    }
+
+    #[test]
+    fn filters_objects() {
+        let th = testcase();
+        assert_eq!(vec!["Rule 1"], search(&th, "Name:\"rule 1\""));
+        assert_eq!(vec!["Wheeled Suitcase"], search(&th, "type:prop"));
+        let placed = search(&th, "Position X>-1000");
+        assert!(placed.contains(&"Television".to_string()) && placed.contains(&"Wheeled Suitcase".to_string()));
+        assert!(search(&th, "Position X>-1000 type:prop type:active_prop").is_empty());
+        assert_eq!(vec!["Wheeled Suitcase"], search(&th, "wheeled"));
+        let unlike = search(&th, "type!=prop");
+        assert!(unlike.contains(&"Television".to_string()) && !unlike.contains(&"Wheeled Suitcase".to_string()));
+    }
+}
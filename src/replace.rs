@@ -0,0 +1,202 @@
+// finds and replaces text in the values of every object and the mission
+// itself, i.e. to fix every reference to something that has been renamed
+
+use std::collections::HashMap;
+
+use regex::{NoExpand, Regex, RegexBuilder};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::playmission::{MissionObject, Object, Properties, Value};
+use crate::tea::Edit;
+
+// the mission itself, for scoping by type
+const MISSION_TYPE: &str = "GAME";
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Replace {
+    pub find: String,
+    pub with: String,
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+    // find is a regex, and with may refer to its groups, i.e. "$1"
+    pub regex: bool,
+    // only values under this key, i.e. "Effect Method Parameter"
+    pub key: Option<String>,
+    // only objects of this type, i.e. "RULE", or "GAME" for the mission
+    pub r#type: Option<String>,
+}
+
+// where a value to be replaced lives
+#[derive(Debug, Clone, PartialEq)]
+pub enum Target {
+    Mission,
+    Property(Uuid),
+    Datafile(Uuid),
+}
+
+// a value that would change, to be previewed before replacing
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replacement {
+    pub target: Target,
+    pub name: String,
+    pub key: String,
+    pub before: String,
+    pub after: String,
+}
+
+impl Replacement {
+
+    // get the edit that makes this replacement
+    pub fn edit(&self) -> Edit {
+        let (key, value) = (self.key.clone(), self.after.clone());
+        match self.target {
+            Target::Mission => Edit::UpdateMission { key, value },
+            Target::Property(uuid) => Edit::UpdateProperty { uuid, key, value },
+            Target::Datafile(uuid) => Edit::UpdateDatafile { uuid, key, value },
+        }
+    }
+
+}
+
+impl Replace {
+
+    // finds every value that would change, in the mission and then in objects
+    // in the order given. only text values are searched, so replacements can't
+    // turn a number into something that doesn't parse
+    pub fn preview(&self, mission: &MissionObject, objects: &HashMap<Uuid, Object>, order: &[Uuid]) -> Result<Vec<Replacement>> {
+
+        if self.find.is_empty() {
+            return Err(ReplaceError::NothingToFind)
+        }
+        let pattern = self.pattern()?;
+
+        let mut replacements = vec![];
+        if self.in_scope(MISSION_TYPE) {
+            let name = mission.properties().get_value("Name").map_or("mission".into(), Value::to_string);
+            self.find_in(&pattern, mission.properties(), Target::Mission, &name, &mut replacements);
+        }
+        for uuid in order {
+            let Some(object) = objects.get(uuid) else { continue };
+            if !self.in_scope(object.r#type()) { continue }
+            let name = object.name().unwrap_or("{unnamed object}".into());
+            self.find_in(&pattern, object.properties(), Target::Property(*uuid), &name, &mut replacements);
+            self.find_in(&pattern, object.datafile(), Target::Datafile(*uuid), &name, &mut replacements);
+        }
+        Ok(replacements)
+
+    }
+
+    // builds the regex to search with from the options
+    fn pattern(&self) -> Result<Regex> {
+        let pattern = if self.regex { self.find.clone() } else { regex::escape(&self.find) };
+        let pattern = if self.whole_word { format!(r"\b(?:{pattern})\b") } else { pattern };
+        Ok(RegexBuilder::new(&pattern).case_insensitive(!self.case_sensitive).build()?)
+    }
+
+    // whether objects of a type are searched
+    fn in_scope(&self, r#type: &str) -> bool {
+        self.r#type.as_ref().is_none_or(|t| t.eq_ignore_ascii_case(r#type))
+    }
+
+    // collects replacements in one set of properties, sorted by key
+    fn find_in(&self, pattern: &Regex, properties: &Properties, target: Target, name: &str, replacements: &mut Vec<Replacement>) {
+
+        let mut keys: Vec<&String> = properties.keys()
+            .filter(|k| self.key.as_ref().is_none_or(|key| key.eq_ignore_ascii_case(k)))
+            .collect();
+        keys.sort();
+
+        for key in keys {
+            let Value::String(before) = properties[key].value() else { continue };
+            let after = if self.regex {
+                pattern.replace_all(before, self.with.as_str())
+            } else {
+                pattern.replace_all(before, NoExpand(&self.with))
+            };
+            if after != *before {
+                replacements.push(Replacement {
+                    target: target.clone(),
+                    name: name.into(),
+                    key: key.clone(),
+                    before: before.clone(),
+                    after: after.into_owned(),
+                });
+            }
+        }
+
+    }
+
+}
+
+pub type Result<T> = std::result::Result<T, ReplaceError>;
+
+#[derive(Debug, Error)]
+pub enum ReplaceError {
+    #[error("nothing to find")]
+    NothingToFind,
+    #[error("invalid regex")]
+    Regex {
+        #[from]
+        source: regex::Error,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tea::{Event, TeaHandler};
+    use crate::utils::testcase;
+
+    fn replace(find: &str, with: &str) -> Replace {
+        Replace { find: find.into(), with: with.into(), ..Default::default() }
+    }
+
+    fn keys(th: &TeaHandler, replace: &Replace) -> Vec<(String, String)> {
+        th.display_replacements(replace).unwrap().into_iter().map(|r| (r.name, r.key)).collect()
+    }
+
+    #[test]
+    fn options_narrow_matches() {
+        let th = testcase();
+
+        // "My Game" is the mission name, and the cause entity of rules
+        let all = keys(&th, &replace("my game", "Our Game"));
+        assert!(all.contains(&("My Game".into(), "Name".into())));
+        assert!(all.contains(&("Default Game Over Rule".into(), "Cause Entity".into())));
+
+        assert!(keys(&th, &Replace { case_sensitive: true, ..replace("my game", "Our Game") }).is_empty());
+        assert!(keys(&th, &Replace { whole_word: true, ..replace("My Gam", "Our Game") }).is_empty());
+        assert_eq!(
+            vec![("Default Game Over Rule".to_string(), "Cause Entity".to_string()), ("Rule 1".to_string(), "Cause Entity".to_string())],
+            keys(&th, &Replace { key: Some("Cause Entity".into()), r#type: Some("RULE".into()), ..replace("My Game", "Our Game") }),
+        );
+
+        let swapped = th.display_replacements(&Replace { regex: true, key: Some("Name".into()), r#type: Some("GAME".into()), ..replace(r"(\w+) (\w+)", "$2 $1") }).unwrap();
+        assert_eq!("Game My", swapped[0].after);
+    }
+
+    #[test]
+    fn replace_undoes_as_one_step() {
+        let mut th = testcase();
+        let replace = replace("My Game", "Our Game");
+        let count = th.display_replacements(&replace).unwrap().len();
+
+        th.event(Event::Replace { replace: replace.clone() });
+        assert_eq!(Some(format!("replaced {count} values").as_str()), th.display_status());
+        assert!(th.display_replacements(&replace).unwrap().is_empty());
+
+        th.event(Event::Undo);
+        assert_eq!(count, th.display_replacements(&replace).unwrap().len());
+    }
+
+    #[test]
+    fn unmatched_replace_leaves_nothing_to_undo() {
+        let mut th = testcase();
+        th.event(Event::Replace { replace: replace("no such text", "anything") });
+        assert_eq!(Some("nothing to replace"), th.display_status());
+
+        th.event(Event::Undo);
+        assert_eq!(Some("nothing to undo"), th.display_status());
+    }
+}
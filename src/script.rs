@@ -0,0 +1,349 @@
+// runs rhai scripts over a mission, collecting what they change as edits
+// so the whole run can be applied and undone as a single step
+
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use rhai::{Array, Dynamic, Engine, EvalAltResult, Map};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::playmission::{error::chain, unique_file_name, unique_name, Object, Properties, Value};
+use crate::tea::Edit;
+
+// stops runaway loops from locking up the editor
+const MAX_OPERATIONS: u64 = 10_000_000;
+
+type ScriptResult<T> = std::result::Result<T, Box<EvalAltResult>>;
+
+// what a finished script did
+pub struct Outcome {
+    pub edits: Vec<Edit>,
+    pub output: Vec<String>,
+}
+
+// scripts work on a copy of the objects, so anything they read
+// reflects what they have already changed
+struct State {
+    objects: HashMap<Uuid, Object>,
+    order: Vec<Uuid>,
+    edits: Vec<Edit>,
+    output: Vec<String>,
+}
+
+type Shared = Rc<RefCell<State>>;
+
+// an object as seen from scripts
+#[derive(Clone)]
+struct ScriptObject {
+    uuid: Uuid,
+    state: Shared,
+}
+
+impl ScriptObject {
+
+    // runs f over the object, failing if the script has deleted it
+    fn with<T>(&self, f: impl FnOnce(&Object) -> T) -> ScriptResult<T> {
+        let state = self.state.borrow();
+        let object = state.objects.get(&self.uuid).ok_or_else(|| deleted(self.uuid))?;
+        Ok(f(object))
+    }
+
+    fn uuid(&mut self) -> String {
+        self.uuid.to_string()
+    }
+
+    fn name(&mut self) -> ScriptResult<Dynamic> {
+        self.with(|o| o.name().map_or(Dynamic::UNIT, Dynamic::from))
+    }
+
+    fn r#type(&mut self) -> ScriptResult<String> {
+        self.with(|o| o.r#type().to_string())
+    }
+
+    fn properties(&mut self) -> ScriptResult<Map> {
+        self.with(|o| to_map(o.properties()))
+    }
+
+    fn datafile(&mut self) -> ScriptResult<Map> {
+        self.with(|o| to_map(o.datafile()))
+    }
+
+    // get a property, or () if the object doesn't have it
+    fn get(&mut self, k: &str) -> ScriptResult<Dynamic> {
+        self.with(|o| o.properties().get_value(k).map_or(Dynamic::UNIT, to_dynamic))
+    }
+
+    fn has(&mut self, k: &str) -> ScriptResult<bool> {
+        self.with(|o| o.properties().contains_key(k))
+    }
+
+    fn set(&mut self, k: &str, v: Dynamic) -> ScriptResult<()> {
+        let value = to_text(v)?;
+        let state = &mut *self.state.borrow_mut();
+        let object = state.objects.get_mut(&self.uuid).ok_or_else(|| deleted(self.uuid))?;
+        object.set_property(k, value.clone()).map_err(|e| chain(&e))?;
+        state.edits.push(Edit::UpdateProperty { uuid: self.uuid, key: k.into(), value });
+        Ok(())
+    }
+
+    fn set_datafile(&mut self, k: &str, v: Dynamic) -> ScriptResult<()> {
+        let value = to_text(v)?;
+        let state = &mut *self.state.borrow_mut();
+        let object = state.objects.get_mut(&self.uuid).ok_or_else(|| deleted(self.uuid))?;
+        object.set_datafile(k, value.clone()).map_err(|e| chain(&e))?;
+        state.edits.push(Edit::UpdateDatafile { uuid: self.uuid, key: k.into(), value });
+        Ok(())
+    }
+
+    // describes the object when printed
+    fn describe(&mut self) -> String {
+        self.with(|o| match o.name() {
+            Some(name) => format!("{} '{name}'", o.r#type()),
+            None => format!("unnamed {}", o.r#type()),
+        }).unwrap_or_else(|_| format!("deleted object {}", self.uuid))
+    }
+
+}
+
+// runs a script over the objects of a mission, listed in order
+pub fn run(source: &str, objects: &HashMap<Uuid, Object>, order: Vec<Uuid>) -> Result<Outcome> {
+
+    let state = Rc::new(RefCell::new(State {
+        objects: objects.clone(),
+        order,
+        edits: vec![],
+        output: vec![],
+    }));
+
+    let engine = engine(&state);
+    engine.run(source)?;
+    drop(engine);
+
+    let state = Rc::try_unwrap(state).ok().expect("engine still holds script state").into_inner();
+    Ok(Outcome { edits: state.edits, output: state.output })
+
+}
+
+// builds an engine with the mission api bound over state
+fn engine(state: &Shared) -> Engine {
+
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+
+    let output = state.clone();
+    engine.on_print(move |s| output.borrow_mut().output.push(s.into()));
+
+    engine.register_type_with_name::<ScriptObject>("Object")
+        .register_get("uuid", ScriptObject::uuid)
+        .register_get("name", ScriptObject::name)
+        .register_get("type", ScriptObject::r#type)
+        .register_get("properties", ScriptObject::properties)
+        .register_get("datafile", ScriptObject::datafile)
+        .register_indexer_get_set(ScriptObject::get, ScriptObject::set)
+        .register_fn("get", ScriptObject::get)
+        .register_fn("set", ScriptObject::set)
+        .register_fn("has", ScriptObject::has)
+        .register_fn("set_datafile", ScriptObject::set_datafile)
+        .register_fn("to_string", ScriptObject::describe)
+        .register_fn("to_debug", ScriptObject::describe)
+        .register_fn("==", |a: ScriptObject, b: ScriptObject| a.uuid == b.uuid)
+        .register_fn("!=", |a: ScriptObject, b: ScriptObject| a.uuid != b.uuid);
+
+    // every object, in the order they are saved
+    let s = state.clone();
+    engine.register_fn("objects", move || -> Array {
+        let order = s.borrow().order.clone();
+        order.into_iter().map(|uuid| Dynamic::from(ScriptObject { uuid, state: s.clone() })).collect()
+    });
+
+    // every object of a type, i.e. "PROP"
+    let s = state.clone();
+    engine.register_fn("objects", move |r#type: &str| -> Array {
+        let state = s.borrow();
+        state.order.iter()
+            .filter(|uuid| state.objects[uuid].r#type() == r#type)
+            .map(|uuid| Dynamic::from(ScriptObject { uuid: *uuid, state: s.clone() }))
+            .collect()
+    });
+
+    // the object with a name, or () if there is none
+    let s = state.clone();
+    engine.register_fn("find", move |name: &str| -> Dynamic {
+        let state = s.borrow();
+        state.order.iter()
+            .find(|uuid| state.objects[uuid].name().as_deref() == Some(name))
+            .map_or(Dynamic::UNIT, |uuid| Dynamic::from(ScriptObject { uuid: *uuid, state: s.clone() }))
+    });
+
+    // a copy of an object under a new name
+    let s = state.clone();
+    engine.register_fn("create", move |template: ScriptObject| -> ScriptResult<ScriptObject> {
+        let uuid = create(&mut s.borrow_mut(), template.uuid)?;
+        Ok(ScriptObject { uuid, state: s.clone() })
+    });
+
+    let s = state.clone();
+    engine.register_fn("delete", move |object: ScriptObject| -> ScriptResult<()> {
+        let state = &mut *s.borrow_mut();
+        state.objects.remove(&object.uuid).ok_or_else(|| deleted(object.uuid))?;
+        state.order.retain(|uuid| *uuid != object.uuid);
+        state.edits.push(Edit::DeleteObject { uuid: object.uuid });
+        Ok(())
+    });
+
+    engine
+
+}
+
+// copies an object, keeping its name and datafile name unique
+fn create(state: &mut State, template: Uuid) -> ScriptResult<Uuid> {
+
+    let template = state.objects.get(&template).ok_or_else(|| deleted(template))?;
+    if !template.files().is_empty() {
+        return Err(format!("can't create copies of {}, which has files of its own", template.r#type()).into())
+    }
+
+    let uuid = Uuid::new_v4();
+    let mut object = template.clone().with_uuid(uuid);
+    if let Some(name) = object.name() {
+        let name = unique_name(&name, |n| state.objects.values().any(|o| o.name().as_deref() == Some(n)));
+        object.set_property("Name", name).map_err(|e| chain(&e))?;
+    }
+    if let Some(datafile_name) = object.datafile_name() {
+        let datafile_name = unique_file_name(datafile_name, |n| state.objects.values().any(|o| o.datafile_name() == Some(n)));
+        object = object.with_datafile_name(datafile_name);
+    }
+
+    let (markup, files) = object.to_fragment().map_err(|e| chain(&e))?;
+    state.edits.push(Edit::InsertObject { uuid, markup, files, lenient: false });
+    state.objects.insert(uuid, object);
+    state.order.push(uuid);
+    Ok(uuid)
+
+}
+
+// converts a property value for scripts
+fn to_dynamic(v: &Value) -> Dynamic {
+    match v {
+        Value::Bool(b) => Dynamic::from(*b),
+        Value::Float(f) => Dynamic::from(*f as f64),
+        Value::Int(i) => Dynamic::from(*i as i64),
+        Value::String(s) => Dynamic::from(s.clone()),
+    }
+}
+
+// converts all properties for scripts
+fn to_map(properties: &Properties) -> Map {
+    properties.iter()
+        .map(|(k, property)| (k.into(), to_dynamic(property.value())))
+        .collect()
+}
+
+// converts a value from scripts to the text a property is set from
+fn to_text(v: Dynamic) -> ScriptResult<String> {
+    if let Ok(b) = v.as_bool() {
+        Ok(Value::Bool(b).to_string())
+    } else if let Ok(f) = v.as_float() {
+        Ok(Value::Float(f as f32).to_string())
+    } else if let Ok(i) = v.as_int() {
+        Ok(i.to_string())
+    } else if v.is_string() {
+        Ok(v.into_string()?)
+    } else {
+        Err(format!("can't set a property to a value of type {}", v.type_name()).into())
+    }
+}
+
+fn deleted(uuid: Uuid) -> Box<EvalAltResult> {
+    format!("object {uuid} has been deleted").into()
+}
+
+pub type Result<T> = std::result::Result<T, ScriptError>;
+
+#[derive(Debug, Error)]
+pub enum ScriptError {
+    #[error("script failed")]
+    Eval {
+        #[from]
+        source: Box<EvalAltResult>,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tea::TeaHandler;
+    use crate::utils::{testcase, uuid_of};
+
+    fn names(th: &TeaHandler) -> Vec<String> {
+        let mut names: Vec<String> = th.display_objects().into_iter().map(|(_, name)| name).collect();
+        names.sort();
+        names
+    }
+
+    fn value(th: &TeaHandler, name: &str, k: &str) -> String {
+        th.display_properties(uuid_of(th, name)).unwrap().into_iter().find(|(n, _)| n == k).unwrap().1.to_string()
+    }
+
+    #[test]
+    fn script_runs_as_one_step() {
+        let mut th = testcase();
+        let before = names(&th);
+        let x = value(&th, "Wheeled Suitcase", "Position X").parse::<f32>().unwrap();
+
+        let output = th.run_script(r#"
+            let suitcase = find("Wheeled Suitcase");
+            suitcase["Position X"] += 1.0;
+            let copy = create(suitcase);
+            copy["Position Y"] = 2;
+            delete(find("Television"));
+            print(copy);
+            print(objects("PROP").len());
+        "#).unwrap();
+
+        assert_eq!(vec!["PROP 'Wheeled Suitcase 2'".to_string(), "2".to_string()], output);
+        assert_eq!(Value::Float(x + 1.0).to_string(), value(&th, "Wheeled Suitcase", "Position X"));
+        assert_eq!("2.0", value(&th, "Wheeled Suitcase 2", "Position Y"));
+        assert!(!names(&th).contains(&"Television".to_string()));
+
+        // the copy survives a save with its own datafile
+        let saved = TeaHandler::from_buffer_lenient(th.serialize().unwrap()).unwrap();
+        assert_eq!("2.0", value(&saved, "Wheeled Suitcase 2", "Position Y"));
+
+        th.event(crate::tea::Event::Undo);
+        assert_eq!(before, names(&th));
+        assert_eq!(Value::Float(x).to_string(), value(&th, "Wheeled Suitcase", "Position X"));
+    }
+
+    #[test]
+    fn failed_script_changes_nothing() {
+        let mut th = testcase();
+        let before = value(&th, "Wheeled Suitcase", "Position X");
+        let e = th.run_script(r#"
+            let suitcase = find("Wheeled Suitcase");
+            suitcase["Position X"] = 5.0;
+            suitcase["Position X"] = [];
+        "#).unwrap_err();
+        assert!(chain(&e).contains("can't set a property to a value of type array"), "{}", chain(&e));
+        assert_eq!(before, value(&th, "Wheeled Suitcase", "Position X"));
+    }
+
+    #[test]
+    fn new_keys_are_taken_away_on_undo() {
+        let mut th = testcase();
+        th.run_script(r#"
+            let suitcase = find("Wheeled Suitcase");
+            suitcase["Owner"] = "Bob";
+            suitcase.set_datafile("Owner", 1);
+        "#).unwrap();
+        assert_eq!("Bob", value(&th, "Wheeled Suitcase", "Owner"));
+
+        th.event(crate::tea::Event::Undo);
+        let uuid = uuid_of(&th, "Wheeled Suitcase");
+        assert!(th.display_properties(uuid).unwrap().iter().all(|(k, _)| k != "Owner"));
+        assert!(th.display_datafile(uuid).unwrap().iter().all(|(k, _)| *k != "Owner"));
+
+        th.event(crate::tea::Event::Redo);
+        assert_eq!("Bob", value(&th, "Wheeled Suitcase", "Owner"));
+    }
+}
@@ -0,0 +1,93 @@
+// objects selected in the editor, in the order they were selected
+
+use uuid::Uuid;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Selection(Vec<Uuid>);
+
+impl Selection {
+
+    // creates empty selection
+    pub fn new() -> Self {
+        Self(vec![])
+    }
+
+    // selects a clicked object. additive clicks toggle it in or out
+    // of the selection, others replace the selection with it
+    pub fn click(&mut self, uuid: Uuid, additive: bool) {
+        if !additive {
+            self.0 = vec![uuid];
+        } else if let Some(i) = self.0.iter().position(|u| *u == uuid) {
+            self.0.remove(i);
+        } else {
+            self.0.push(uuid);
+        }
+    }
+
+    // selects many objects at once, i.e. from a box select. additive
+    // selects add to the selection, others replace it
+    pub fn select_all(&mut self, uuids: impl IntoIterator<Item = Uuid>, additive: bool) {
+        if !additive {
+            self.0.clear();
+        }
+        for uuid in uuids {
+            if !self.0.contains(&uuid) {
+                self.0.push(uuid);
+            }
+        }
+    }
+
+    // whether an object is selected
+    pub fn contains(&self, uuid: &Uuid) -> bool {
+        self.0.contains(uuid)
+    }
+
+    // get all selected uuids
+    pub fn uuids(&self) -> &[Uuid] {
+        &self.0
+    }
+
+    // get the selected uuid, if exactly one is selected
+    pub fn single(&self) -> Option<Uuid> {
+        match self.0.as_slice() {
+            [uuid] => Some(*uuid),
+            _ => None,
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn click_replaces_or_toggles() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut selection = Selection::new();
+
+        selection.click(a, false);
+        selection.click(b, true);
+        assert_eq!(&[a, b], selection.uuids());
+        assert_eq!(None, selection.single());
+
+        selection.click(a, true);
+        assert_eq!(Some(b), selection.single());
+
+        selection.click(a, false);
+        assert_eq!(Some(a), selection.single());
+    }
+
+    #[test]
+    fn select_all_keeps_order_without_duplicates() {
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let mut selection = Selection::new();
+
+        selection.click(b, false);
+        selection.select_all([a, b, c], true);
+        assert_eq!(&[b, a, c], selection.uuids());
+
+        selection.select_all([c], false);
+        assert_eq!(&[c], selection.uuids());
+    }
+}
@@ -1,24 +1,33 @@
-use std::{collections::{HashMap, VecDeque}, io::Cursor};
+use std::{collections::{BTreeMap, HashMap, VecDeque}, io::{Cursor, Read, Write}};
 
 use dioxus::{events::{keyboard_types::KeyboardEvent, Key, ModifiersInteraction}, html::KeyboardData};
 use dioxus::events::Code;
 use gloo_console::log;
 use gloo_file::{Blob, ObjectUrl};
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use wasm_bindgen::JsCast;
 use web_sys::{HtmlElement};
+use zip::{write::FileOptions, ZipArchive, ZipWriter};
 
-use crate::{playmission::{
-    error::PlaymissionError, MissionObject, Object, Value
-}, three::Scene};
+use crate::{clipboard::{self, ClipboardError}, diff::Diff, journal::{Journal, JournalError}, layers::{Flags, Layer, Layers}, lighting::{self, Preview}, links::{self, Link}, map::{self, Icon}, merge::{self, MergeError}, placement::{self, Placed, Placement, Snap}, playmission::{
+    door_sides, error::{chain, PlaymissionError}, render_doors, BlankingPlate, BoundingBox, Face, Location, MissionObject, Object, Properties, Side, Value
+}, query::{Query, QueryError}, replace::{Replace, ReplaceError, Replacement}, script::{self, ScriptError}, three::Scene, volumes::{self, Resized, Volume}};
 
 // manages The Elm Architecture for interfacing with the inner project
 pub struct TeaHandler {
     missionobject: MissionObject,
     objects: HashMap<Uuid, Object>,
     status: Option<String>,
+    warnings: Vec<String>,
     undo_buffer: VecDeque<InverseEvent>,
-    redo_buffer: VecDeque<Event>,
+    redo_buffer: VecDeque<Edit>,
+    transactions: Vec<Vec<Edit>>,
+    journal: Journal,
+    // counts every change, so that autosaves can tell when there is something new
+    revision: u64,
+    layers: Layers,
+    scene: Option<Scene>,
 }
 
 impl TeaHandler {
@@ -27,13 +36,87 @@ impl TeaHandler {
     pub fn from_buffer(data: Vec<u8>) -> std::result::Result<Self, TeaError> {
         let cursor = Cursor::new(data);
         let (missionobject, objects) = MissionObject::deserialize(cursor)?;
-        Ok(Self {
+        Ok(Self::new(missionobject, objects, vec![]))
+    }
+
+    // import a mission from a file, keeping objects that fail to load as-is
+    pub fn from_buffer_lenient(data: Vec<u8>) -> std::result::Result<Self, TeaError> {
+        let cursor = Cursor::new(data);
+        let (missionobject, objects, warnings) = MissionObject::deserialize_lenient(cursor)?;
+        let warnings = warnings.iter().map(|e| chain(e)).collect();
+        Ok(Self::new(missionobject, objects, warnings))
+    }
+
+    // create new around loaded mission
+    fn new(missionobject: MissionObject, objects: HashMap<Uuid, Object>, warnings: Vec<String>) -> Self {
+        Self {
             missionobject,
             objects,
             status: None,
+            warnings,
             undo_buffer: VecDeque::new(),
             redo_buffer: VecDeque::new(),
-        })
+            transactions: vec![],
+            journal: Journal::new(),
+            revision: 0,
+            layers: Layers::default(),
+            scene: None,
+        }
+    }
+
+    // restore a mission and its undo history from a snapshot
+    pub fn from_snapshot(data: Vec<u8>) -> std::result::Result<Self, TeaError> {
+
+        let mut zip = ZipArchive::new(Cursor::new(data))?;
+        let mut read = |name: &str| -> std::result::Result<Vec<u8>, TeaError> {
+            let mut buf = vec![];
+            zip.by_name(name)?.read_to_end(&mut buf)?;
+            Ok(buf)
+        };
+        let mission = read(SNAPSHOT_MISSION)?;
+        let history: History = serde_json::from_slice(&read(SNAPSHOT_HISTORY)?)?;
+        // snapshots from before layers were kept have none
+        let layers: Layers = match read(SNAPSHOT_LAYERS) {
+            Ok(layers) => serde_json::from_slice(&layers)?,
+            Err(_) => Layers::default(),
+        };
+
+        // the history refers to objects by uuid, so they need to be the same as before
+        let mut th = Self::from_buffer_lenient(mission)?;
+        th.objects = th.missionobject.restore_uuids(std::mem::take(&mut th.objects), &history.order)?;
+        th.undo_buffer = history.undo.into_iter().map(InverseEvent).collect();
+        th.redo_buffer = history.redo.into();
+        th.journal = history.journal;
+        th.layers = layers;
+        Ok(th)
+
+    }
+
+    // export current mission and undo history, to be restored from later
+    pub fn snapshot(&self) -> std::result::Result<Vec<u8>, TeaError> {
+
+        let history = History {
+            order: self.missionobject.order(&self.objects),
+            undo: self.undo_buffer.iter().map(|e| e.0.clone()).collect(),
+            redo: self.redo_buffer.iter().cloned().collect(),
+            journal: self.journal.clone(),
+        };
+        let mission = self.missionobject.clone().serialize(self.objects.clone())?;
+
+        let mut zip = ZipWriter::new(Cursor::new(vec![]));
+        zip.start_file(SNAPSHOT_MISSION, FileOptions::default())?;
+        zip.write_all(&mission)?;
+        zip.start_file(SNAPSHOT_HISTORY, FileOptions::default())?;
+        zip.write_all(&serde_json::to_vec(&history)?)?;
+        zip.start_file(SNAPSHOT_LAYERS, FileOptions::default())?;
+        zip.write_all(&serde_json::to_vec(&self.layers)?)?;
+        Ok(zip.finish()?.into_inner())
+
+    }
+
+    // get how many changes have been made since loading, to compare against
+    pub fn display_revision(&self) -> u64 {
+        self.revision
     }
 
     // entry point to start event chain
@@ -42,45 +125,237 @@ impl TeaHandler {
         let result = self.run_event(event);
         match result {
             Ok(Some(inverse_event)) => { self.push_new_undo(inverse_event) },
-            Err(e) => { self.status = Some(e.to_string()) },
+            Err(e) => { self.status = Some(chain(&e)) },
             _ => {},
         }
     }
 
+    // shows an error from outside the mission, i.e. the browser's clipboard,
+    // where failed events are shown
+    pub fn report(&mut self, e: &dyn std::error::Error) {
+        self.status = Some(chain(e));
+    }
+
+    // starts grouping events, so that everything up to the matching
+    // commit is undone as a single step. transactions can be nested
+    pub fn begin(&mut self) {
+        self.transactions.push(vec![]);
+    }
+
+    // ends the innermost transaction, folding its events into one undo step
+    pub fn commit(&mut self) {
+        let Some(mut inverses) = self.transactions.pop() else { return };
+        if inverses.is_empty() { return }
+        inverses.reverse();
+        self.push_new_undo(InverseEvent(Edit::Batch { edits: inverses }));
+    }
+
+    // ends the innermost transaction, undoing everything done during it
+    pub fn rollback(&mut self) {
+        let Some(mut inverses) = self.transactions.pop() else { return };
+        if inverses.is_empty() { return }
+        inverses.reverse();
+        if let Err(e) = self.edit(Edit::Batch { edits: inverses }) {
+            self.status = Some(chain(&e));
+        }
+    }
+
+    // applies a journal recorded on another load of this or a similar
+    // mission, as a single step
+    pub fn replay(&mut self, journal: &Journal) -> std::result::Result<(), TeaError> {
+        self.reset_state();
+        if let Some(inverse_event) = self.run_replay(journal)? {
+            self.push_new_undo(inverse_event);
+        }
+        Ok(())
+    }
+
+    // runs a script over the mission as a single step, returning what it printed
+    pub fn run_script(&mut self, source: &str) -> std::result::Result<Vec<String>, TeaError> {
+        self.reset_state();
+        let (inverse_event, output) = self.script(source)?;
+        if let Some(inverse_event) = inverse_event {
+            self.push_new_undo(inverse_event);
+        }
+        Ok(output)
+    }
+
+    // merges every object of another mission into this one as a single
+    // step, returning notes on anything renamed
+    pub fn merge(&mut self, buffer: &[u8]) -> std::result::Result<Vec<String>, TeaError> {
+        self.reset_state();
+        let (inverse_event, notes) = self.merged(buffer)?;
+        if let Some(inverse_event) = inverse_event {
+            self.push_new_undo(inverse_event);
+        }
+        Ok(notes)
+    }
+
+    // get every edit applied since loading
+    pub fn journal(&self) -> &Journal {
+        &self.journal
+    }
+
+    // export current mission to serialized Vec buffer
+    pub fn serialize(&self) -> std::result::Result<Vec<u8>, TeaError> {
+        // clone is kind of very gross
+        Ok(self.missionobject.clone().serialize(self.objects.clone())?)
+    }
+
     // matches and handles events
     fn run_event(&mut self, event: Event) -> UpdateResult {
 
         match event {
             Event::Save => self.save(),
+            Event::ExportJournal => self.export_journal(),
             Event::Keypress{e} => self.keypress(e),
-            Event::UpdateProperty{uuid, key, value} => self.update_property(uuid, key, value),
-            Event::UpdateDatafile{uuid, key, value} => self.update_datafile(uuid, key, value),
-            Event::UpdateFile{uuid, key, buffer} => self.update_file(uuid, key, buffer),
+            Event::Edit(edit) => self.edit(edit),
+            Event::ImportJournal{buffer} => self.run_replay(&Journal::from_json(&buffer)?),
+            Event::Replace{replace} => self.replace(&replace),
+            Event::Paste{text} => {
+                let imported = clipboard::paste(&text, &self.missionobject, &self.objects)?;
+                let (inverse_event, notes) = self.import(imported, "pasted")?;
+                self.status = Some(notes.join("\n"));
+                Ok(inverse_event)
+            },
+            Event::Merge{buffer} => {
+                let (inverse_event, notes) = self.merged(&buffer)?;
+                self.status = Some(notes.join("\n"));
+                Ok(inverse_event)
+            },
+            Event::RunScript{source} => {
+                let (inverse_event, output) = self.script(&source)?;
+                self.status = (!output.is_empty()).then(|| output.join("\n"));
+                Ok(inverse_event)
+            },
+            Event::SetLayer{layer, flags} => {
+                self.layers.set(layer, flags);
+                self.revision += 1;
+                Ok(None)
+            },
             Event::Undo => self.undo(),
             Event::Redo => self.redo(),
         }
 
     }
 
+    // applies journal edits to the objects with the same names here
+    fn run_replay(&mut self, journal: &Journal) -> UpdateResult {
+        let edits = journal.resolve(&self.objects)?;
+        self.edit(Edit::Batch { edits })
+    }
+
+    // replaces text across the mission as a single step
+    fn replace(&mut self, replace: &Replace) -> UpdateResult {
+        let edits: Vec<Edit> = self.display_replacements(replace)?.iter().map(Replacement::edit).collect();
+        let count = edits.len();
+        // nothing is left to undo, or in the journal, when nothing matched
+        if count == 0 {
+            self.status = Some("nothing to replace".into());
+            return Ok(None)
+        }
+        let inverse_event = self.edit(Edit::Batch { edits })?;
+        self.status = Some(format!("replaced {count} values"));
+        Ok(inverse_event)
+    }
+
+    // merges every object of another mission into this one, returning notes on anything renamed
+    fn merged(&mut self, buffer: &[u8]) -> std::result::Result<(Option<InverseEvent>, Vec<String>), TeaError> {
+        let imported = merge::merge(buffer, &self.missionobject, &self.objects)?;
+        self.import(imported, "merged")
+    }
+
+    // adds copied or merged objects, returning notes on anything renamed on the way
+    fn import(&mut self, imported: merge::Imported, verb: &str) -> std::result::Result<(Option<InverseEvent>, Vec<String>), TeaError> {
+        let count = imported.uuids.len();
+        let inverse_event = self.edit(Edit::Batch { edits: imported.edits })?;
+        let mut notes = imported.notes;
+        notes.push(format!("{verb} {count} objects"));
+        Ok((inverse_event, notes))
+    }
+
+    // runs a script against the objects, then applies everything it changed
+    fn script(&mut self, source: &str) -> std::result::Result<(Option<InverseEvent>, Vec<String>), TeaError> {
+        let order = self.missionobject.order(&self.objects);
+        let outcome = script::run(source, &self.objects, order)?;
+        let inverse_event = self.edit(Edit::Batch { edits: outcome.edits })?;
+        Ok((inverse_event, outcome.output))
+    }
+
+    // applies an edit, recording it to the journal
+    fn edit(&mut self, edit: Edit) -> UpdateResult {
+        let names = self.journal.names(&edit, &self.objects);
+        let inverse = self.apply(edit.clone())?;
+        self.journal.record(names, edit);
+        Ok(inverse)
+    }
+
+    // matches and handles edits
+    fn apply(&mut self, edit: Edit) -> UpdateResult {
+
+        match edit {
+            Edit::UpdateProperty{uuid, key, value} => self.update_property(uuid, key, value),
+            Edit::UpdateDatafile{uuid, key, value} => self.update_datafile(uuid, key, value),
+            Edit::RemoveProperty{uuid, key} => self.remove_property(uuid, key),
+            Edit::RemoveDatafile{uuid, key} => self.remove_datafile(uuid, key),
+            Edit::UpdateFile{uuid, key, buffer} => self.update_file(uuid, key, buffer),
+            Edit::UpdateMission{key, value} => self.update_mission(key, value),
+            Edit::RemoveMission{key} => self.remove_mission(key),
+            Edit::UpdateMissionFile{key, buffer} => self.update_mission_file(key, buffer),
+            Edit::InsertObject{uuid, markup, files, lenient} => self.insert_object(uuid, markup, files, lenient),
+            Edit::DeleteObject{uuid} => self.delete_object(uuid),
+            Edit::Batch{edits} => self.batch(edits),
+        }
+
+    }
+
+    // applies edits in order as a single step. if any fail, those
+    // already applied are undone before returning the error
+    fn batch(&mut self, edits: Vec<Edit>) -> UpdateResult {
+
+        let mut inverses = vec![];
+        for edit in edits {
+            match self.apply(edit) {
+                Ok(Some(inverse)) => inverses.push(inverse.unwrap()),
+                Ok(None) => {},
+                Err(e) => {
+                    self.roll_back(inverses);
+                    return Err(e)
+                },
+            }
+        }
+
+        if inverses.is_empty() { return Ok(None) }
+        inverses.reverse();
+        Ok(Some(InverseEvent(Edit::Batch { edits: inverses })))
+
+    }
+
+    // reverts applied edits from their inverses, newest first
+    fn roll_back(&mut self, inverses: Vec<Edit>) {
+        for inverse in inverses.into_iter().rev() {
+            if let Err(e) = self.apply(inverse) {
+                self.status = Some(chain(&e));
+            }
+        }
+    }
+
     // resets anything that should not persist between states
     fn reset_state(&mut self) {
         self.status = None
     }
 
-    // export current mission to serialized Vec buffer
+    // export current mission as a download
     fn save(&mut self) -> UpdateResult {
-        // clone is kind of very gross
-        let buf = self.missionobject.clone().serialize(self.objects.clone())?;
-        let blob = Blob::new_with_options(&*buf, Some("application/zip"));
-        let object_url = ObjectUrl::from(blob);
-        let window = web_sys::window().expect("missing window");
-        let document = window.document().expect("missing document");
-        let link = document.create_element("a").map_err(|_| TeaError::FailedLinkCreation)?;
-        link.set_attribute("download", "export.playmission").unwrap();
-        link.set_attribute("href", &object_url).unwrap();
-        let link_as_html: HtmlElement = link.dyn_into().unwrap();
-        link_as_html.click();
-        link_as_html.remove();
+        let buf = self.serialize()?;
+        download(&buf, "application/zip", "export.playmission")?;
+        Ok(None)
+    }
+
+    // export journal of edits as a download
+    fn export_journal(&mut self) -> UpdateResult {
+        let buf = self.journal.to_json()?;
+        download(&buf, "application/json", "journal.json")?;
         Ok(None)
     }
 
@@ -104,9 +379,18 @@ impl TeaHandler {
     fn update_property(&mut self, uuid: Uuid, key: String, value: impl Into<String>) -> UpdateResult {
 
         let object = self.get_object_mut(uuid)?;
+        let layout = moves_doors(object, &key);
 
-        let old = object.set_property(key.clone(), value)?.unwrap();
-        let inverse_event = Event::UpdateProperty { uuid, key: key.into(), value: old.to_string() };
+        let old = object.set_property(&key, value)?;
+        self.revision += 1;
+        if layout {
+            self.render_doors();
+        }
+        // a key that wasn't there before is taken away again
+        let inverse_event = match old {
+            Some(old) => Edit::UpdateProperty { uuid, key, value: old.to_string() },
+            None => Edit::RemoveProperty { uuid, key },
+        };
         Ok(Some(InverseEvent(inverse_event)))
 
     }
@@ -116,8 +400,40 @@ impl TeaHandler {
 
         let object = self.get_object_mut(uuid)?;
 
-        let old = object.set_datafile(key.clone(), value)?.unwrap();
-        let inverse_event = Event::UpdateDatafile { uuid, key: key.into(), value: old.to_string() };
+        let old = object.set_datafile(&key, value)?;
+        self.revision += 1;
+        let inverse_event = match old {
+            Some(old) => Edit::UpdateDatafile { uuid, key, value: old.to_string() },
+            None => Edit::RemoveDatafile { uuid, key },
+        };
+        Ok(Some(InverseEvent(inverse_event)))
+
+    }
+
+    // removes a property from an object by uuid
+    fn remove_property(&mut self, uuid: Uuid, key: String) -> UpdateResult {
+
+        let object = self.get_object_mut(uuid)?;
+        let layout = moves_doors(object, &key);
+
+        let old = object.remove_property(&key)?;
+        self.revision += 1;
+        if layout {
+            self.render_doors();
+        }
+        let inverse_event = Edit::UpdateProperty { uuid, key, value: old.to_string() };
+        Ok(Some(InverseEvent(inverse_event)))
+
+    }
+
+    // removes a datafile property from an object by uuid
+    fn remove_datafile(&mut self, uuid: Uuid, key: String) -> UpdateResult {
+
+        let object = self.get_object_mut(uuid)?;
+
+        let old = object.remove_datafile(&key)?;
+        self.revision += 1;
+        let inverse_event = Edit::UpdateDatafile { uuid, key, value: old.to_string() };
         Ok(Some(InverseEvent(inverse_event)))
 
     }
@@ -128,15 +444,99 @@ impl TeaHandler {
         let object = self.get_object_mut(uuid)?;
 
         let old = object.set_file(key.as_ref(), buffer)?.unwrap();
-        let inverse_event = Event::UpdateFile { uuid, key: key.into(), buffer: old };
+        self.revision += 1;
+        let inverse_event = Edit::UpdateFile { uuid, key: key.into(), buffer: old };
+        Ok(Some(InverseEvent(inverse_event)))
+
+    }
+
+    // updates property on the mission itself
+    fn update_mission(&mut self, key: String, value: impl Into<String>) -> UpdateResult {
+
+        let old = self.missionobject.set_property(&key, value)?;
+        self.revision += 1;
+        if key == BlankingPlate::KEY {
+            self.render_doors();
+        }
+        let inverse_event = match old {
+            Some(old) => Edit::UpdateMission { key, value: old.to_string() },
+            None => Edit::RemoveMission { key },
+        };
         Ok(Some(InverseEvent(inverse_event)))
 
     }
 
+    // removes a property from the mission itself
+    fn remove_mission(&mut self, key: String) -> UpdateResult {
+
+        let old = self.missionobject.remove_property(&key)?;
+        self.revision += 1;
+        if key == BlankingPlate::KEY {
+            self.render_doors();
+        }
+        let inverse_event = Edit::UpdateMission { key, value: old.to_string() };
+        Ok(Some(InverseEvent(inverse_event)))
+
+    }
+
+    // sets or removes a file not owned by any object
+    fn update_mission_file(&mut self, key: String, buffer: Option<Vec<u8>>) -> UpdateResult {
+
+        let old = self.missionobject.set_file(&key, buffer);
+        self.revision += 1;
+        let inverse_event = Edit::UpdateMissionFile { key, buffer: old };
+        Ok(Some(InverseEvent(inverse_event)))
+
+    }
+
+    // loads an object from its markup and files into the mission
+    fn insert_object(&mut self, uuid: Uuid, markup: String, files: BTreeMap<String, Vec<u8>>, lenient: bool) -> UpdateResult {
+
+        if self.objects.contains_key(&uuid) {
+            return Err(TeaError::TakenUuid(uuid))
+        }
+
+        let mut object = self.missionobject.load_fragment(&markup, files, lenient)?.with_uuid(uuid);
+        if let Some(scene) = &mut self.scene {
+            object.render(scene)?;
+        }
+        let tile = object.r#type() == "LOCATION";
+        self.objects.insert(uuid, object);
+        if tile {
+            self.render_doors();
+        }
+        self.revision += 1;
+        Ok(Some(InverseEvent(Edit::DeleteObject { uuid })))
+
+    }
+
+    // removes an object from the mission by uuid
+    fn delete_object(&mut self, uuid: Uuid) -> UpdateResult {
+
+        let object = self.get_object(uuid)?;
+        let (markup, files) = object.to_fragment()?;
+        let lenient = object.r#type() == "FRAGMENT";
+        let tile = object.r#type() == "LOCATION";
+
+        self.objects.remove(&uuid);
+        if let Some(scene) = &self.scene {
+            while let Some(rendered) = scene.get_object_by_name(&uuid.to_string()) {
+                scene.remove(&rendered);
+            }
+        }
+        if tile {
+            self.render_doors();
+        }
+        self.revision += 1;
+        Ok(Some(InverseEvent(Edit::InsertObject { uuid, markup, files, lenient })))
+
+    }
+
     // undoes an event, if available
     fn undo(&mut self) -> UpdateResult {
+        if !self.transactions.is_empty() { return Err(TeaError::OpenTransaction) }
         let event = self.undo_buffer.pop_front().ok_or(TeaError::NoUndo)?;
-        let inverse_event = self.run_event(event.unwrap())?;
+        let inverse_event = self.edit(event.unwrap())?;
         if let Some(inverse) = inverse_event {
             self.push_redo(inverse.unwrap());
         }
@@ -145,8 +545,9 @@ impl TeaHandler {
 
     // redoes an event, if available
     fn redo(&mut self) -> UpdateResult {
+        if !self.transactions.is_empty() { return Err(TeaError::OpenTransaction) }
         let event = self.redo_buffer.pop_front().ok_or(TeaError::NoUndo)?;
-        let inverse_event = self.run_event(event)?;
+        let inverse_event = self.edit(event)?;
         if let Some(inverse) = inverse_event {
             self.push_undo(inverse);
         }
@@ -159,15 +560,19 @@ impl TeaHandler {
         self.undo_buffer.truncate(200);
     }
 
-    // pushes an inverseevent to the undo buffer,
+    // pushes an inverseevent to the undo buffer, or the open transaction,
     // clearing the redo buffer in the process
     fn push_new_undo(&mut self, event: InverseEvent) {
+        if let Some(transaction) = self.transactions.last_mut() {
+            transaction.push(event.unwrap());
+            return
+        }
         self.push_undo(event);
         self.redo_buffer.clear();
     }
 
-    // pushes event to redo buffer
-    fn push_redo(&mut self, event: Event) {
+    // pushes edit to redo buffer
+    fn push_redo(&mut self, event: Edit) {
         self.redo_buffer.push_front(event);
         self.redo_buffer.truncate(200);
     }
@@ -182,11 +587,56 @@ impl TeaHandler {
         self.objects.get_mut(&uuid).ok_or(TeaError::NoUuid(uuid))
     }
 
-    // returns vec of object names and uuids
+    // returns vec of object names and uuids, in the order they are listed
     pub fn display_objects(&self) -> Vec<(Uuid, String)> {
+        self.display_object_groups(&Query::default())
+            .into_iter()
+            .flat_map(|(_, objects)| objects)
+            .collect()
+    }
+
+    // returns names and uuids of objects matching a query, grouped by type.
+    // groups are sorted by type and objects by name, so the list keeps still
+    pub fn display_object_groups(&self, query: &Query) -> Vec<(&'static str, Vec<(Uuid, String)>)> {
+
+        let mut matching: Vec<(&'static str, String, Uuid)> = self.objects.iter()
+            .filter(|(_, v)| query.matches(v))
+            .map(|(k, v)| (v.r#type(), v.name().unwrap_or("{unnamed object}".into()), *k))
+            .collect();
+        matching.sort();
+
+        let mut groups: Vec<(&'static str, Vec<(Uuid, String)>)> = vec![];
+        for (r#type, name, uuid) in matching {
+            match groups.last_mut() {
+                Some((last, objects)) if *last == r#type => objects.push((uuid, name)),
+                _ => groups.push((r#type, vec![(uuid, name)])),
+            }
+        }
+        groups
+
+    }
+
+    // returns uuids of objects matching a query
+    pub fn search(&self, query: &Query) -> Vec<Uuid> {
         self.objects.iter()
-        .map(|(k, v)| (k.clone(), v.name().unwrap_or("{unnamed object}".into())))
-        .collect()
+            .filter(|(_, v)| query.matches(v))
+            .map(|(k, _)| *k)
+            .collect()
+    }
+
+    // returns every value a replace would change, in listing order
+    pub fn display_replacements(&self, replace: &Replace) -> ViewResult<Vec<Replacement>> {
+        let order: Vec<Uuid> = self.display_objects().into_iter().map(|(uuid, _)| uuid).collect();
+        Ok(replace.preview(&self.missionobject, &self.objects, &order)?)
+    }
+
+    // parses search text, knowing which keys objects have
+    pub fn query(&self, text: &str) -> ViewResult<Query> {
+        let keys = self.objects.values()
+            .flat_map(|v| v.properties().keys().chain(v.datafile().keys()))
+            .cloned()
+            .collect();
+        Ok(Query::parse(text, &keys)?)
     }
 
     // returns (k, v) of property names and values
@@ -211,6 +661,23 @@ impl TeaHandler {
         )
     }
 
+    // returns (k, v) of property names shared by every given object, with
+    // their value if it is the same on all of them or none if mixed
+    pub fn display_shared_properties(&self, uuids: &[Uuid]) -> ViewResult<Vec<(String, Option<&Value>)>> {
+        let properties = uuids.iter()
+            .map(|uuid| Ok(self.get_object(*uuid)?.properties()))
+            .collect::<ViewResult<Vec<_>>>()?;
+        Ok(shared(&properties))
+    }
+
+    // sama datafile
+    pub fn display_shared_datafile(&self, uuids: &[Uuid]) -> ViewResult<Vec<(String, Option<&Value>)>> {
+        let datafiles = uuids.iter()
+            .map(|uuid| Ok(self.get_object(*uuid)?.datafile()))
+            .collect::<ViewResult<Vec<_>>>()?;
+        Ok(shared(&datafiles))
+    }
+
     // returns names of files on object by uuid
     pub fn display_files(&self, uuid: Uuid) -> ViewResult<Vec<String>> {
         Ok(
@@ -231,36 +698,317 @@ impl TeaHandler {
             .ok_or(TeaError::NoFile)
     }
 
+    // get objects as text for the clipboard, along with rules
+    // connecting only them and everything they need to be pasted
+    pub fn copy(&self, uuids: &[Uuid]) -> ViewResult<String> {
+        Ok(clipboard::copy(&self.missionobject, &self.objects, uuids)?)
+    }
+
+    // compares this mission against another version of it, i.e. a teammate's
+    pub fn diff(&self, buffer: &[u8]) -> ViewResult<Diff> {
+        let (other, other_objects, _) = MissionObject::deserialize_lenient(Cursor::new(buffer))?;
+        Ok(Diff::between((&self.missionobject, &self.objects), (&other, &other_objects)))
+    }
+
+    // get where an object would end up if put somewhere, after snapping
+    // and clamping, with none if it has no position to move
+    pub fn display_placement(&self, uuid: Uuid, placement: Placement, snap: &Snap) -> Option<Placed> {
+        placement::place(uuid, &self.objects, self.missionobject.properties(), placement, snap)
+    }
+
+    // get the volume of a trigger or effect, to be resized by its faces
+    pub fn display_volume(&self, uuid: Uuid) -> Option<Volume> {
+        volumes::volume(self.objects.get(&uuid)?)
+    }
+
+    // get the property and datafile values that would give an object a volume,
+    // once one of its faces has been dragged
+    pub fn display_resized(&self, uuid: Uuid, volume: &Volume, face: Face) -> Option<Resized> {
+        Some(volumes::values(self.objects.get(&uuid)?, volume, face))
+    }
+
+    // get which objects are shown and pickable in the viewport
+    pub fn display_layers(&self) -> &Layers {
+        &self.layers
+    }
+
+    // get uuids of objects hidden from the viewport
+    pub fn display_hidden(&self) -> Vec<Uuid> {
+        self.objects.iter().filter(|(uuid, o)| self.layers.of(**uuid, o).hidden).map(|(uuid, _)| *uuid).collect()
+    }
+
+    // get uuids of objects that can't be picked or moved in the viewport
+    pub fn display_locked(&self) -> Vec<Uuid> {
+        self.objects.iter().filter(|(uuid, o)| self.layers.of(**uuid, o).locked).map(|(uuid, _)| *uuid).collect()
+    }
+
+    // get every type of object in the mission, sorted
+    pub fn display_types(&self) -> Vec<&'static str> {
+        let mut types: Vec<&'static str> = self.objects.values().map(Object::r#type).collect();
+        types.sort();
+        types.dedup();
+        types
+    }
+
+    // get rules as links between their cause and effect entities
+    pub fn display_links(&self) -> Vec<Link> {
+        links::links(&self.objects)
+    }
+
+    // get icons for the top-down map
+    pub fn display_map(&self) -> Vec<Icon> {
+        let plates = BlankingPlate::of(self.missionobject.properties()).unwrap_or_default();
+        map::icons(&self.objects, &plates)
+    }
+
+    // get the sides of every tile that have doors, sorted by uuid
+    pub fn display_doors(&self) -> Vec<(Uuid, Vec<Side>)> {
+        let plates = BlankingPlate::of(self.missionobject.properties()).unwrap_or_default();
+        let mut tiles: Vec<(Uuid, BoundingBox)> = self.objects.iter()
+            .filter(|(_, o)| o.r#type() == "LOCATION")
+            .filter_map(|(uuid, o)| Some((*uuid, BoundingBox::of(o.properties())?)))
+            .collect();
+        tiles.sort_by_key(|(uuid, _)| *uuid);
+        tiles.iter()
+            .map(|(uuid, bbox)| {
+                let others: Vec<BoundingBox> = tiles.iter().filter(|(other, _)| other != uuid).map(|(_, bbox)| *bbox).collect();
+                (*uuid, door_sides(bbox, &others, &plates))
+            })
+            .collect()
+    }
+
+    // get the lightmaps each tile is previewed with
+    pub fn display_lightmaps(&self, preview: Preview) -> Vec<(Uuid, Vec<String>)> {
+        lighting::tiles(&self.objects, preview, self.missionobject.files())
+    }
+
+    // get a file not owned by any object, i.e. a lightmap
+    pub fn display_mission_file(&self, key: &str) -> Option<&[u8]> {
+        self.missionobject.files().get(key).map(Vec::as_slice)
+    }
+
     // return status string
     pub fn display_status(&self) -> Option<&str> {
         self.status.as_deref()
     }
 
+    // return warnings from loading
+    pub fn display_warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
     // renders all objects to three.js scene
     pub fn render(&mut self, scene: &mut Scene) {
-        self.objects.values_mut().for_each(|object| { object.render(scene); })
+        self.scene = Some(scene.clone());
+        self.objects.values_mut().for_each(|object| { object.render(scene); });
+        self.render_doors();
+    }
+
+    // draws the doors of every tile, which depend on the tiles around them
+    fn render_doors(&self) {
+        let Some(scene) = &self.scene else { return };
+        for (uuid, sides) in self.display_doors() {
+            let Some(bbox) = BoundingBox::of(self.objects[&uuid].properties()) else { continue };
+            if let Some(floor) = scene.get_object_by_name(&uuid.to_string()) {
+                render_doors(&floor, &bbox, &sides);
+            }
+        }
     }
 
 }
 
+// finds the keys present in every set of properties, sorted by name, with
+// their value if it is the same in all of them
+fn shared<'a>(all: &[&'a Properties]) -> Vec<(String, Option<&'a Value>)> {
+
+    let Some((first, rest)) = all.split_first() else { return vec![] };
+
+    let mut shared: Vec<(String, Option<&Value>)> = first.iter()
+        .filter(|(k, _)| rest.iter().all(|p| p.contains_key(*k)))
+        .map(|(k, v)| {
+            let v = v.value();
+            let same = rest.iter().all(|p| p.get_value(k).is_ok_and(|other| other == v));
+            (k.clone(), same.then_some(v))
+        })
+        .collect();
+    shared.sort_by(|(a, _), (b, _)| a.cmp(b));
+    shared
+
+}
+
+// whether changing a key of an object moves the doors drawn on tiles
+fn moves_doors(object: &Object, key: &str) -> bool {
+    object.r#type() == "LOCATION" && Location::LAYOUT_KEYS.contains(&key)
+}
+
+// names of files in a snapshot zip
+const SNAPSHOT_MISSION: &str = "mission.playmission";
+const SNAPSHOT_HISTORY: &str = "history.json";
+const SNAPSHOT_LAYERS: &str = "layers.json";
+
+// undo history as stored in a snapshot, with the uuids of objects
+// in the order they are saved so they can be matched up again on load
+#[derive(Serialize, Deserialize)]
+struct History {
+    order: Vec<Uuid>,
+    undo: Vec<Edit>,
+    redo: Vec<Edit>,
+    #[serde(default)]
+    journal: Journal,
+}
+
+// things the user can do in the editor
 pub enum Event {
     Save,
+    ExportJournal,
     Keypress{e: web_sys::KeyboardEvent},
-    UpdateProperty{uuid: Uuid, key: String, value: String},
-    UpdateDatafile{uuid: Uuid, key: String, value: String},
-    UpdateFile{uuid: Uuid, key: String, buffer: Vec<u8>},
+    Edit(Edit),
+    ImportJournal{buffer: Vec<u8>},
+    Replace{replace: Replace},
+    Paste{text: String},
+    Merge{buffer: Vec<u8>},
+    RunScript{source: String},
+    // shows, hides, locks or unlocks objects in the viewport, which can't be undone
+    SetLayer{layer: Layer, flags: Flags},
     Undo,
     Redo,
 }
 
-struct InverseEvent(Event);
+// changes to the mission itself, which can be undone and recorded
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum Edit {
+    UpdateProperty{uuid: Uuid, key: String, value: String},
+    UpdateDatafile{uuid: Uuid, key: String, value: String},
+    // undoes setting a key that wasn't there before
+    RemoveProperty{uuid: Uuid, key: String},
+    RemoveDatafile{uuid: Uuid, key: String},
+    UpdateFile{uuid: Uuid, key: String, #[serde(with = "base64_bytes")] buffer: Vec<u8>},
+    UpdateMission{key: String, value: String},
+    RemoveMission{key: String},
+    // files not owned by any object. none removes the file
+    UpdateMissionFile{key: String, #[serde(with = "base64_option")] buffer: Option<Vec<u8>>},
+    // lenient inserts keep objects that fail to load as their markup
+    InsertObject{uuid: Uuid, markup: String, #[serde(with = "base64_files")] files: BTreeMap<String, Vec<u8>>, lenient: bool},
+    DeleteObject{uuid: Uuid},
+    Batch{edits: Vec<Edit>},
+}
+
+impl Edit {
+
+    // get uuids of every object the edit touches
+    pub fn uuids(&self) -> Vec<Uuid> {
+        match self {
+            Edit::UpdateProperty{uuid, ..} | Edit::UpdateDatafile{uuid, ..} | Edit::UpdateFile{uuid, ..} => vec![*uuid],
+            Edit::RemoveProperty{uuid, ..} | Edit::RemoveDatafile{uuid, ..} => vec![*uuid],
+            Edit::InsertObject{uuid, ..} | Edit::DeleteObject{uuid} => vec![*uuid],
+            Edit::Batch{edits} => edits.iter().flat_map(Edit::uuids).collect(),
+            Edit::UpdateMission{..} | Edit::RemoveMission{..} | Edit::UpdateMissionFile{..} => vec![],
+        }
+    }
+
+    // get uuids of objects the edit inserts
+    pub fn inserted(&self) -> Vec<Uuid> {
+        match self {
+            Edit::InsertObject{uuid, ..} => vec![*uuid],
+            Edit::Batch{edits} => edits.iter().flat_map(Edit::inserted).collect(),
+            _ => vec![],
+        }
+    }
+
+    // replaces the uuids of objects the edit touches
+    pub fn map_uuids(self, f: &impl Fn(Uuid) -> Option<Uuid>) -> Option<Edit> {
+        Some(match self {
+            Edit::UpdateProperty{uuid, key, value} => Edit::UpdateProperty{uuid: f(uuid)?, key, value},
+            Edit::UpdateDatafile{uuid, key, value} => Edit::UpdateDatafile{uuid: f(uuid)?, key, value},
+            Edit::RemoveProperty{uuid, key} => Edit::RemoveProperty{uuid: f(uuid)?, key},
+            Edit::RemoveDatafile{uuid, key} => Edit::RemoveDatafile{uuid: f(uuid)?, key},
+            Edit::UpdateFile{uuid, key, buffer} => Edit::UpdateFile{uuid: f(uuid)?, key, buffer},
+            Edit::UpdateMission{key, value} => Edit::UpdateMission{key, value},
+            Edit::RemoveMission{key} => Edit::RemoveMission{key},
+            Edit::UpdateMissionFile{key, buffer} => Edit::UpdateMissionFile{key, buffer},
+            Edit::InsertObject{uuid, markup, files, lenient} => Edit::InsertObject{uuid: f(uuid)?, markup, files, lenient},
+            Edit::DeleteObject{uuid} => Edit::DeleteObject{uuid: f(uuid)?},
+            Edit::Batch{edits} => Edit::Batch{edits: edits.into_iter().map(|e| e.map_uuids(f)).collect::<Option<_>>()?},
+        })
+    }
+
+}
+
+// file buffers are written as base64 rather than arrays of numbers
+mod base64_bytes {
+    use base64::prelude::*;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(buffer: &[u8], s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&BASE64_STANDARD.encode(buffer))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<u8>, D::Error> {
+        let encoded = String::deserialize(d)?;
+        BASE64_STANDARD.decode(encoded).map_err(serde::de::Error::custom)
+    }
+}
+
+// sama, for files that may be removed
+mod base64_option {
+    use base64::prelude::*;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(buffer: &Option<Vec<u8>>, s: S) -> Result<S::Ok, S::Error> {
+        match buffer {
+            Some(buffer) => s.serialize_some(&BASE64_STANDARD.encode(buffer)),
+            None => s.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Vec<u8>>, D::Error> {
+        Option::<String>::deserialize(d)?
+            .map(|encoded| BASE64_STANDARD.decode(encoded).map_err(serde::de::Error::custom))
+            .transpose()
+    }
+}
+
+// sama, for the files of inserted objects
+mod base64_files {
+    use std::collections::BTreeMap;
+    use base64::prelude::*;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(files: &BTreeMap<String, Vec<u8>>, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_map(files.iter().map(|(k, v)| (k, BASE64_STANDARD.encode(v))))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<BTreeMap<String, Vec<u8>>, D::Error> {
+        BTreeMap::<String, String>::deserialize(d)?
+            .into_iter()
+            .map(|(k, v)| Ok((k, BASE64_STANDARD.decode(v).map_err(serde::de::Error::custom)?)))
+            .collect()
+    }
+}
+
+struct InverseEvent(Edit);
 
 impl InverseEvent {
-    fn unwrap(self) -> Event {
+    fn unwrap(self) -> Edit {
         self.0
     }
 }
 
+// offers a buffer to the user as a file download
+fn download(buf: &[u8], mime: &str, file_name: &str) -> Result<(), TeaError> {
+    let blob = Blob::new_with_options(buf, Some(mime));
+    let object_url = ObjectUrl::from(blob);
+    let window = web_sys::window().expect("missing window");
+    let document = window.document().expect("missing document");
+    let link = document.create_element("a").map_err(|_| TeaError::FailedLinkCreation)?;
+    link.set_attribute("download", file_name).unwrap();
+    link.set_attribute("href", &object_url).unwrap();
+    let link_as_html: HtmlElement = link.dyn_into().unwrap();
+    link_as_html.click();
+    link_as_html.remove();
+    Ok(())
+}
+
 use thiserror::Error;
 
 pub type UpdateResult = Result<Option<InverseEvent>, TeaError>;
@@ -280,11 +1028,230 @@ pub enum TeaError {
     NoRedo,
     #[error("nothing to undo")]
     NoUndo,
+    #[error("cannot undo or redo while a transaction is open")]
+    OpenTransaction,
+    #[error("attempted to insert object with already-claimed uuid {0}")]
+    TakenUuid(Uuid),
     #[error("operated on a uuid {0} with no associated object")]
     NoUuid(Uuid),
+    #[error("failed reading or writing snapshot")]
+    Io {
+        #[from]
+        source: std::io::Error,
+    },
+    #[error("failed reading or writing snapshot history")]
+    Json {
+        #[from]
+        source: serde_json::Error,
+    },
+    #[error("failed handling snapshot as zip")]
+    Zip {
+        #[from]
+        source: zip::result::ZipError,
+    },
+    #[error("journal error")]
+    Journal {
+        #[from]
+        source: JournalError,
+    },
+    #[error("script error")]
+    Script {
+        #[from]
+        source: ScriptError,
+    },
+    #[error("failed to paste")]
+    Clipboard {
+        #[from]
+        source: ClipboardError,
+    },
+    #[error("failed to merge")]
+    Merge {
+        #[from]
+        source: MergeError,
+    },
+    #[error("failed to replace")]
+    Replace {
+        #[from]
+        source: ReplaceError,
+    },
+    #[error("invalid search")]
+    Query {
+        #[from]
+        source: QueryError,
+    },
     #[error("playmission error")]
     Playmission {
         #[from]
         source: PlaymissionError, 
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{testcase, uuid_of};
+
+    fn load() -> (TeaHandler, Uuid) {
+        let th = testcase();
+        let uuid = uuid_of(&th, "Wheeled Suitcase");
+        (th, uuid)
+    }
+
+    fn value(th: &TeaHandler, uuid: Uuid, k: &str) -> String {
+        th.display_properties(uuid).unwrap().into_iter().find(|(name, _)| name == k).unwrap().1.to_string()
+    }
+
+    fn edit(uuid: Uuid, k: &str, v: &str) -> Edit {
+        Edit::UpdateProperty { uuid, key: k.into(), value: v.into() }
+    }
+
+    fn set(uuid: Uuid, k: &str, v: &str) -> Event {
+        Event::Edit(edit(uuid, k, v))
+    }
+
+    fn batch(edits: Vec<Edit>) -> Event {
+        Event::Edit(Edit::Batch { edits })
+    }
+
+    #[test]
+    fn shared_properties_mark_mixed() {
+        let (mut th, uuid) = load();
+        let other = uuid_of(&th, "Television");
+        th.event(batch(vec![edit(uuid, "Position X", "1.0"), edit(other, "Position X", "1.0")]));
+
+        let shared = th.display_shared_properties(&[uuid, other]).unwrap();
+        let get = |k: &str| shared.iter().find(|(name, _)| name == k).map(|(_, v)| *v);
+        assert_eq!(Some(Some(&Value::Float(1.0))), get("Position X"));
+        assert_eq!(Some(None), get("Name"));
+        assert!(shared.windows(2).all(|w| w[0].0 < w[1].0));
+
+        let all = th.display_properties(uuid).unwrap();
+        assert_eq!(all.len(), th.display_shared_properties(&[uuid]).unwrap().len());
+    }
+
+    #[test]
+    fn batch_undoes_as_one_step() {
+        let (mut th, uuid) = load();
+        let before = (value(&th, uuid, "Position X"), value(&th, uuid, "Position Z"));
+
+        th.event(batch(vec![edit(uuid, "Position X", "1.0"), edit(uuid, "Position Z", "2.0")]));
+        assert_eq!(("1.0".into(), "2.0".into()), (value(&th, uuid, "Position X"), value(&th, uuid, "Position Z")));
+
+        th.event(Event::Undo);
+        assert_eq!(before, (value(&th, uuid, "Position X"), value(&th, uuid, "Position Z")));
+        th.event(Event::Undo);
+        assert_eq!(Some("nothing to undo"), th.display_status());
+
+        th.event(Event::Redo);
+        assert_eq!(("1.0".into(), "2.0".into()), (value(&th, uuid, "Position X"), value(&th, uuid, "Position Z")));
+    }
+
+    #[test]
+    fn new_mission_key_is_removed_on_undo() {
+        let (mut th, _) = load();
+        th.event(Event::Edit(Edit::UpdateMission { key: "Author".into(), value: "Bob".into() }));
+        assert_eq!(Some("Bob".into()), th.missionobject.properties().get_value("Author").ok().map(ToString::to_string));
+
+        th.event(Event::Undo);
+        assert!(th.missionobject.properties().get_value("Author").is_err());
+        th.event(Event::Redo);
+        assert!(th.missionobject.properties().get_value("Author").is_ok());
+    }
+
+    #[test]
+    fn failed_batch_rolls_back() {
+        let (mut th, uuid) = load();
+        let before = value(&th, uuid, "Position X");
+
+        th.event(batch(vec![edit(uuid, "Position X", "1.0"), edit(uuid, "Position Z", "abc")]));
+        assert!(th.display_status().is_some());
+        assert_eq!(before, value(&th, uuid, "Position X"));
+
+        th.event(Event::Undo);
+        assert_eq!(Some("nothing to undo"), th.display_status());
+    }
+
+    #[test]
+    fn transaction_commits_one_step() {
+        let (mut th, uuid) = load();
+        let before = value(&th, uuid, "Position X");
+
+        th.begin();
+        th.event(set(uuid, "Position X", "1.0"));
+        th.event(set(uuid, "Position X", "2.0"));
+        th.event(Event::Undo);
+        assert_eq!(Some("cannot undo or redo while a transaction is open"), th.display_status());
+        th.commit();
+
+        assert_eq!("2.0", value(&th, uuid, "Position X"));
+        th.event(Event::Undo);
+        assert_eq!(before, value(&th, uuid, "Position X"));
+    }
+
+    #[test]
+    fn transaction_rolls_back() {
+        let (mut th, uuid) = load();
+        let before = value(&th, uuid, "Position X");
+
+        th.begin();
+        th.event(set(uuid, "Position X", "1.0"));
+        th.event(set(uuid, "Position X", "2.0"));
+        th.rollback();
+
+        assert_eq!(before, value(&th, uuid, "Position X"));
+        th.event(Event::Undo);
+        assert_eq!(Some("nothing to undo"), th.display_status());
+    }
+
+    #[test]
+    fn snapshot_keeps_history() {
+        let (mut th, uuid) = load();
+        let before = value(&th, uuid, "Position X");
+        assert_eq!(0, th.display_revision());
+
+        th.event(set(uuid, "Position X", "1.0"));
+        th.event(set(uuid, "Position X", "2.0"));
+        th.event(Event::Undo);
+        assert_eq!(3, th.display_revision());
+
+        let mut restored = TeaHandler::from_snapshot(th.snapshot().unwrap()).unwrap();
+        assert_eq!(th.display_warnings().len(), restored.display_warnings().len());
+        assert_eq!("1.0", value(&restored, uuid, "Position X"));
+
+        restored.event(Event::Redo);
+        assert_eq!("2.0", value(&restored, uuid, "Position X"));
+        restored.event(Event::Undo);
+        restored.event(Event::Undo);
+        assert_eq!(before, value(&restored, uuid, "Position X"));
+    }
+
+    #[test]
+    fn untouched_datafiles_are_saved_as_loaded() {
+        let (mut th, uuid) = load();
+        let original = std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/test_data/testcase/suitcase_wheeled.prop")).unwrap();
+        let saved = |th: &TeaHandler| {
+            let mut zip = ZipArchive::new(Cursor::new(th.serialize().unwrap())).unwrap();
+            let mut buf = vec![];
+            zip.by_name("suitcase_wheeled.prop").unwrap().read_to_end(&mut buf).unwrap();
+            buf
+        };
+        assert_eq!(original, saved(&th));
+
+        th.event(Event::Edit(Edit::UpdateDatafile { uuid, key: "Description".into(), value: "Suitcase".into() }));
+        assert_ne!(original, saved(&th));
+    }
+
+    #[test]
+    fn deleted_object_is_restored_on_undo() {
+        let (mut th, _) = load();
+        let uuid = uuid_of(&th, "Default Game Over Screen");
+        let files = th.display_files(uuid).unwrap();
+
+        th.event(Event::Edit(Edit::DeleteObject { uuid }));
+        assert!(th.display_properties(uuid).is_err());
+        th.event(Event::Undo);
+        assert_eq!(None, th.display_status());
+        assert_eq!("Default Game Over Screen", value(&th, uuid, "Name"));
+        assert_eq!(files, th.display_files(uuid).unwrap());
+    }
+}
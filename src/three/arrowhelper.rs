@@ -0,0 +1,19 @@
+use crate::three::{Object3D, Vector3};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(module = "/node_modules/three/build/three.module.js")]
+extern "C" {
+
+    #[wasm_bindgen(extends = Object3D)]
+    pub type ArrowHelper;
+    // colour as 0xrrggbb
+    #[wasm_bindgen(constructor)]
+    pub fn new(direction: &Vector3, origin: &Vector3, length: f32, color: u32) -> ArrowHelper;
+    #[wasm_bindgen(method, js_name = setDirection)]
+    pub fn set_direction(this: &ArrowHelper, direction: &Vector3);
+    #[wasm_bindgen(method, js_name = setLength)]
+    pub fn set_length(this: &ArrowHelper, length: f32, head_length: f32, head_width: f32);
+    #[wasm_bindgen(method, js_name = setColor)]
+    pub fn set_color(this: &ArrowHelper, color: u32);
+
+}
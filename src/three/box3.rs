@@ -0,0 +1,19 @@
+use crate::three::{Object3D, Vector3};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(module = "/node_modules/three/build/three.module.js")]
+extern "C" {
+
+    pub type Box3;
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Box3;
+    #[wasm_bindgen(method, js_name = expandByObject)]
+    pub fn expand_by_object(this: &Box3, object: &Object3D) -> Box3;
+    #[wasm_bindgen(method, js_name = isEmpty)]
+    pub fn is_empty(this: &Box3) -> bool;
+    #[wasm_bindgen(method, js_name = getCenter)]
+    pub fn get_center(this: &Box3, target: &Vector3) -> Vector3;
+    #[wasm_bindgen(method, js_name = getSize)]
+    pub fn get_size(this: &Box3, target: &Vector3) -> Vector3;
+
+}
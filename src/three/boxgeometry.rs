@@ -1,7 +1,9 @@
+use crate::three::BufferGeometry;
 use wasm_bindgen::prelude::*;
 #[wasm_bindgen(module = "/node_modules/three/build/three.module.js")]
 extern "C" {
 
+    #[wasm_bindgen(extends = BufferGeometry)]
     pub type BoxGeometry;
     #[wasm_bindgen(constructor)]
     pub fn new(x: f32, y: f32, z: f32) -> BoxGeometry;
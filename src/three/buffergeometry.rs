@@ -0,0 +1,18 @@
+use crate::three::Box3;
+use wasm_bindgen::prelude::*;
+
+// any geometry, i.e. to build a mesh from
+#[wasm_bindgen(module = "/node_modules/three/build/three.module.js")]
+extern "C" {
+
+    pub type BufferGeometry;
+    #[wasm_bindgen(method, js_name = getAttribute)]
+    pub fn get_attribute(this: &BufferGeometry, name: &str) -> JsValue;
+    #[wasm_bindgen(method, js_name = setAttribute)]
+    pub fn set_attribute(this: &BufferGeometry, name: &str, attribute: &JsValue);
+    #[wasm_bindgen(method, js_name = computeBoundingBox)]
+    pub fn compute_bounding_box(this: &BufferGeometry);
+    #[wasm_bindgen(method, getter, js_name = boundingBox)]
+    pub fn bounding_box(this: &BufferGeometry) -> Option<Box3>;
+
+}
@@ -0,0 +1,12 @@
+use crate::three::Object3D;
+use wasm_bindgen::prelude::*;
+
+// any camera, i.e. to pick or render from whichever is in use
+#[wasm_bindgen(module = "/node_modules/three/build/three.module.js")]
+extern "C" {
+
+    #[derive(Clone)]
+    #[wasm_bindgen(extends = Object3D)]
+    pub type Camera;
+
+}
@@ -0,0 +1,11 @@
+use wasm_bindgen::prelude::*;
+#[wasm_bindgen(module = "/node_modules/three/build/three.module.js")]
+extern "C" {
+
+    pub type CanvasTexture;
+    #[wasm_bindgen(constructor)]
+    pub fn new(canvas: &web_sys::HtmlCanvasElement) -> CanvasTexture;
+    #[wasm_bindgen(method, setter = needsUpdate)]
+    pub fn set_needs_update(this: &CanvasTexture, needs_update: bool);
+
+}
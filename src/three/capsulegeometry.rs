@@ -0,0 +1,11 @@
+use crate::three::BufferGeometry;
+use wasm_bindgen::prelude::*;
+#[wasm_bindgen(module = "/node_modules/three/build/three.module.js")]
+extern "C" {
+
+    #[wasm_bindgen(extends = BufferGeometry)]
+    pub type CapsuleGeometry;
+    #[wasm_bindgen(constructor)]
+    pub fn new(radius: f32, length: f32, cap_segments: u32, radial_segments: u32) -> CapsuleGeometry;
+
+}
@@ -0,0 +1,12 @@
+use crate::three::BufferGeometry;
+use wasm_bindgen::prelude::*;
+#[wasm_bindgen(module = "/node_modules/three/build/three.module.js")]
+extern "C" {
+
+    // points up y, with its base centred on the origin
+    #[wasm_bindgen(extends = BufferGeometry)]
+    pub type ConeGeometry;
+    #[wasm_bindgen(constructor)]
+    pub fn new(radius: f32, height: f32, radial_segments: u32) -> ConeGeometry;
+
+}
@@ -0,0 +1,15 @@
+use wasm_bindgen::prelude::*;
+#[wasm_bindgen(module = "/node_modules/three/build/three.module.js")]
+extern "C" {
+
+    // a texture from raw rgba pixels
+    pub type DataTexture;
+    #[wasm_bindgen(constructor)]
+    pub fn new(data: &js_sys::Uint8Array, width: u32, height: u32) -> DataTexture;
+    #[wasm_bindgen(method, setter = needsUpdate)]
+    pub fn set_needs_update(this: &DataTexture, needs_update: bool);
+    // which set of uvs the texture is drawn with, i.e. 1 for "uv1"
+    #[wasm_bindgen(method, setter)]
+    pub fn set_channel(this: &DataTexture, channel: u32);
+
+}
@@ -1,4 +1,4 @@
-use crate::three::{BoxGeometry, Euler, MeshBasicMaterial, Vector3};
+use crate::three::{BufferGeometry, Euler, MeshBasicMaterial, Quaternion, Vector3};
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen(module = "/node_modules/three/build/three.module.js")]
@@ -6,10 +6,18 @@ extern "C" {
 
     pub type Mesh;
     #[wasm_bindgen(constructor)]
-    pub fn new(geometry: &BoxGeometry, material: &MeshBasicMaterial) -> Mesh;
+    pub fn new(geometry: &BufferGeometry, material: &MeshBasicMaterial) -> Mesh;
     #[wasm_bindgen(method, getter)]
     pub fn position(geometry: &Mesh) -> Vector3;
     #[wasm_bindgen(method, getter)]
     pub fn rotation(geometry: &Mesh) -> Euler;
+    #[wasm_bindgen(method, getter)]
+    pub fn quaternion(geometry: &Mesh) -> Quaternion;
+    #[wasm_bindgen(method, getter)]
+    pub fn scale(geometry: &Mesh) -> Vector3;
+    #[wasm_bindgen(method, getter)]
+    pub fn geometry(this: &Mesh) -> BufferGeometry;
+    #[wasm_bindgen(method, getter)]
+    pub fn material(this: &Mesh) -> MeshBasicMaterial;
 
 }
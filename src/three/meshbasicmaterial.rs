@@ -1,4 +1,4 @@
-use crate::three::Color;
+use crate::three::{CanvasTexture, Color, DataTexture};
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen(module = "/node_modules/three/build/three.module.js")]
@@ -9,5 +9,20 @@ extern "C" {
     pub fn new() -> MeshBasicMaterial;
     #[wasm_bindgen(method, getter = color)]
     pub fn color(this: &MeshBasicMaterial) -> Color;
+    #[wasm_bindgen(method, setter)]
+    pub fn set_wireframe(this: &MeshBasicMaterial, wireframe: bool);
+    #[wasm_bindgen(method, setter)]
+    pub fn set_map(this: &MeshBasicMaterial, map: &CanvasTexture);
+    #[wasm_bindgen(method, setter)]
+    pub fn set_transparent(this: &MeshBasicMaterial, transparent: bool);
+    #[wasm_bindgen(method, setter)]
+    pub fn set_opacity(this: &MeshBasicMaterial, opacity: f32);
+    #[wasm_bindgen(method, setter = lightMap)]
+    pub fn set_light_map(this: &MeshBasicMaterial, light_map: Option<&DataTexture>);
+    #[wasm_bindgen(method, setter = lightMapIntensity)]
+    pub fn set_light_map_intensity(this: &MeshBasicMaterial, intensity: f32);
+    // needed after adding or removing maps, so the material is rebuilt
+    #[wasm_bindgen(method, setter = needsUpdate)]
+    pub fn set_needs_update(this: &MeshBasicMaterial, needs_update: bool);
 
 }
@@ -1,27 +1,55 @@
+mod arrowhelper;
+mod box3;
 mod boxgeometry;
+mod buffergeometry;
+mod capsulegeometry;
+mod camera;
+mod canvastexture;
 mod color;
+mod conegeometry;
+mod datatexture;
 mod euler;
 mod mesh;
 mod meshbasicmaterial;
 mod object3d;
 mod orbitcontrols;
+mod orthographiccamera;
 mod perspectivecamera;
+mod quaternion;
 mod raycaster;
+mod ringgeometry;
 mod scene;
+mod sprite;
+mod spritematerial;
+mod transformcontrols;
 mod vector2;
 mod vector3;
 mod webglrenderer;
 
+pub use arrowhelper::ArrowHelper;
+pub use box3::Box3;
 pub use boxgeometry::BoxGeometry;
+pub use buffergeometry::BufferGeometry;
+pub use capsulegeometry::CapsuleGeometry;
+pub use camera::Camera;
+pub use canvastexture::CanvasTexture;
 pub use color::Color;
+pub use conegeometry::ConeGeometry;
+pub use datatexture::DataTexture;
 pub use euler::Euler;
 pub use mesh::Mesh;
 pub use meshbasicmaterial::MeshBasicMaterial;
 pub use object3d::Object3D;
 pub use orbitcontrols::OrbitControls;
+pub use orthographiccamera::OrthographicCamera;
 pub use perspectivecamera::PerspectiveCamera;
+pub use quaternion::Quaternion;
 pub use raycaster::Raycaster;
+pub use ringgeometry::RingGeometry;
 pub use scene::Scene;
+pub use sprite::Sprite;
+pub use spritematerial::SpriteMaterial;
+pub use transformcontrols::TransformControls;
 pub use vector2::Vector2;
 pub use vector3::Vector3;
 pub use webglrenderer::WebGLRenderer;
@@ -1,9 +1,10 @@
+use crate::three::{Quaternion, Vector3};
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen(module = "/node_modules/three/build/three.module.js")]
 extern "C" {
 
-    #[derive(Debug)]
+    #[derive(Debug, Clone)]
     pub type Object3D;
 
     #[wasm_bindgen(method, getter)]
@@ -14,4 +15,19 @@ extern "C" {
     #[wasm_bindgen(method, getter)]
     pub fn children(this: &Object3D) -> Vec<JsValue>;
 
+    #[wasm_bindgen(method, setter)]
+    pub fn set_visible(this: &Object3D, visible: bool);
+    #[wasm_bindgen(method, getter)]
+    pub fn parent(this: &Object3D) -> Option<Object3D>;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn position(this: &Object3D) -> Vector3;
+    #[wasm_bindgen(method, getter)]
+    pub fn quaternion(this: &Object3D) -> Quaternion;
+
+    #[wasm_bindgen(method)]
+    pub fn add(this: &Object3D, object: &Object3D);
+    #[wasm_bindgen(method)]
+    pub fn remove(this: &Object3D, object: &Object3D);
+
 }
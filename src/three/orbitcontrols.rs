@@ -1,16 +1,27 @@
-use crate::three::PerspectiveCamera;
+use crate::three::{Camera, Vector3};
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen(module = "/node_modules/three/examples/jsm/controls/OrbitControls-modified.js")]
 extern "C" {
 
+    #[derive(Clone)]
     pub type OrbitControls;
     #[wasm_bindgen(constructor)]
     pub fn new(
-        camera: &PerspectiveCamera,
+        camera: &Camera,
         dom_element: &web_sys::HtmlCanvasElement,
     ) -> OrbitControls;
     #[wasm_bindgen(method)]
     pub fn update(this: &OrbitControls);
+    #[wasm_bindgen(method, setter)]
+    pub fn set_enabled(this: &OrbitControls, enabled: bool);
+    #[wasm_bindgen(method, setter = enableRotate)]
+    pub fn set_enable_rotate(this: &OrbitControls, enabled: bool);
+    #[wasm_bindgen(method, getter)]
+    pub fn target(this: &OrbitControls) -> Vector3;
+
+    // the camera being controlled
+    #[wasm_bindgen(method, setter = object)]
+    pub fn set_object(this: &OrbitControls, camera: &Camera);
 
 }
@@ -0,0 +1,29 @@
+use crate::three::{Camera, Object3D, Vector3};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(module = "/node_modules/three/build/three.module.js")]
+extern "C" {
+
+    #[derive(Clone)]
+    #[wasm_bindgen(extends = Camera, extends = Object3D)]
+    pub type OrthographicCamera;
+    #[wasm_bindgen(constructor)]
+    pub fn new(left: f64, right: f64, top: f64, bottom: f64, near: f64, far: f64) -> OrthographicCamera;
+    #[wasm_bindgen(method, getter = position)]
+    pub fn position(this: &OrthographicCamera) -> Vector3;
+    #[wasm_bindgen(method, getter = up)]
+    pub fn up(this: &OrthographicCamera) -> Vector3;
+    #[wasm_bindgen(method, js_name = lookAt)]
+    pub fn look_at(this: &OrthographicCamera, x: f32, y: f32, z: f32);
+    #[wasm_bindgen(method, getter)]
+    pub fn top(this: &OrthographicCamera) -> f64;
+    #[wasm_bindgen(method, getter)]
+    pub fn right(this: &OrthographicCamera) -> f64;
+    #[wasm_bindgen(method, getter)]
+    pub fn zoom(this: &OrthographicCamera) -> f64;
+    #[wasm_bindgen(method, setter)]
+    pub fn set_zoom(this: &OrthographicCamera, zoom: f64);
+    #[wasm_bindgen(method, js_name = updateProjectionMatrix)]
+    pub fn update_projection_matrix(this: &OrthographicCamera);
+
+}
@@ -1,13 +1,18 @@
-use crate::three::Vector3;
+use crate::three::{Camera, Object3D, Vector3};
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen(module = "/node_modules/three/build/three.module.js")]
 extern "C" {
 
+    #[wasm_bindgen(extends = Camera, extends = Object3D)]
     pub type PerspectiveCamera;
     #[wasm_bindgen(constructor)]
     pub fn new(fov: f64, aspect: f64, near: f64, far: f64) -> PerspectiveCamera;
     #[wasm_bindgen(method, getter = position)]
     pub fn position(this: &PerspectiveCamera) -> Vector3;
+    #[wasm_bindgen(method, setter)]
+    pub fn set_aspect(this: &PerspectiveCamera, aspect: f64);
+    #[wasm_bindgen(method, js_name = updateProjectionMatrix)]
+    pub fn update_projection_matrix(this: &PerspectiveCamera);
 
 }
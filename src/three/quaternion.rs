@@ -0,0 +1,21 @@
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(module = "/node_modules/three/build/three.module.js")]
+extern "C" {
+
+    pub type Quaternion;
+    #[wasm_bindgen(constructor)]
+    pub fn new(x: f32, y: f32, z: f32, w: f32) -> Quaternion;
+    #[wasm_bindgen(method)]
+    pub fn set(this: &Quaternion, x: f32, y: f32, z: f32, w: f32);
+
+    #[wasm_bindgen(method, getter)]
+    pub fn x(this: &Quaternion) -> f32;
+    #[wasm_bindgen(method, getter)]
+    pub fn y(this: &Quaternion) -> f32;
+    #[wasm_bindgen(method, getter)]
+    pub fn z(this: &Quaternion) -> f32;
+    #[wasm_bindgen(method, getter)]
+    pub fn w(this: &Quaternion) -> f32;
+
+}
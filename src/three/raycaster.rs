@@ -1,4 +1,4 @@
-use crate::three::{PerspectiveCamera, Vector2};
+use crate::three::{Camera, Vector2};
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen(module = "/node_modules/three/build/three.module.js")]
@@ -11,7 +11,7 @@ extern "C" {
     pub fn set_from_camera(
         this: &Raycaster,
         normalized_position: &Vector2,
-        camera: &PerspectiveCamera,
+        camera: &Camera,
     );
     #[wasm_bindgen(method, js_name = intersectObjects)]
     pub fn intersect_objects(
@@ -0,0 +1,12 @@
+use crate::three::BufferGeometry;
+use wasm_bindgen::prelude::*;
+#[wasm_bindgen(module = "/node_modules/three/build/three.module.js")]
+extern "C" {
+
+    // flat in the xy plane, around the origin
+    #[wasm_bindgen(extends = BufferGeometry)]
+    pub type RingGeometry;
+    #[wasm_bindgen(constructor)]
+    pub fn new(inner_radius: f32, outer_radius: f32, theta_segments: u32) -> RingGeometry;
+
+}
@@ -1,13 +1,18 @@
-use crate::three::Mesh;
+use crate::three::{Mesh, Object3D};
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen(module = "/node_modules/three/build/three.module.js")]
 extern "C" {
 
+    #[derive(Clone)]
     pub type Scene;
     #[wasm_bindgen(constructor)]
     pub fn new() -> Scene;
     #[wasm_bindgen(method)]
     pub fn add(this: &Scene, mesh: &Mesh);
+    #[wasm_bindgen(method)]
+    pub fn remove(this: &Scene, object: &Object3D);
+    #[wasm_bindgen(method, js_name = getObjectByName)]
+    pub fn get_object_by_name(this: &Scene, name: &str) -> Option<Object3D>;
 
 }
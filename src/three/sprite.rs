@@ -0,0 +1,13 @@
+use crate::three::{Object3D, SpriteMaterial};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(module = "/node_modules/three/build/three.module.js")]
+extern "C" {
+
+    // a flat square that always faces the camera
+    #[wasm_bindgen(extends = Object3D)]
+    pub type Sprite;
+    #[wasm_bindgen(constructor)]
+    pub fn new(material: &SpriteMaterial) -> Sprite;
+
+}
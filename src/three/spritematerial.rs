@@ -0,0 +1,15 @@
+use crate::three::{CanvasTexture, Color};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(module = "/node_modules/three/build/three.module.js")]
+extern "C" {
+
+    pub type SpriteMaterial;
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> SpriteMaterial;
+    #[wasm_bindgen(method, getter = color)]
+    pub fn color(this: &SpriteMaterial) -> Color;
+    #[wasm_bindgen(method, setter)]
+    pub fn set_map(this: &SpriteMaterial, map: &CanvasTexture);
+
+}
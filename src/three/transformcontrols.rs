@@ -0,0 +1,36 @@
+use crate::three::{Camera, Object3D};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(module = "/node_modules/three/examples/jsm/controls/TransformControls.js")]
+extern "C" {
+
+    #[derive(Clone)]
+    pub type TransformControls;
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        camera: &Camera,
+        dom_element: &web_sys::HtmlCanvasElement,
+    ) -> TransformControls;
+
+    #[wasm_bindgen(method)]
+    pub fn attach(this: &TransformControls, object: &Object3D);
+    #[wasm_bindgen(method)]
+    pub fn detach(this: &TransformControls);
+    #[wasm_bindgen(method, getter)]
+    pub fn object(this: &TransformControls) -> Option<Object3D>;
+
+    // "translate", "rotate" or "scale"
+    #[wasm_bindgen(method, js_name = setMode)]
+    pub fn set_mode(this: &TransformControls, mode: &str);
+    #[wasm_bindgen(method, getter)]
+    pub fn dragging(this: &TransformControls) -> bool;
+    #[wasm_bindgen(method, setter)]
+    pub fn set_camera(this: &TransformControls, camera: &Camera);
+    #[wasm_bindgen(method, setter = showY)]
+    pub fn set_show_y(this: &TransformControls, show: bool);
+
+    // i.e. "dragging-changed", with the new state under "value"
+    #[wasm_bindgen(method, js_name = addEventListener)]
+    pub fn add_event_listener(this: &TransformControls, r#type: &str, listener: &js_sys::Function);
+
+}
@@ -1,3 +1,4 @@
+use crate::three::Camera;
 use wasm_bindgen::prelude::*;
 #[wasm_bindgen(module = "/node_modules/three/build/three.module.js")]
 extern "C" {
@@ -9,6 +10,12 @@ extern "C" {
     pub fn set(this: &Vector3, x: f32, y: f32, z: f32);
     #[wasm_bindgen(method)]
     pub fn add(this: &Vector3, v: Vector3);
+    #[wasm_bindgen(method, js_name = clone)]
+    pub fn cloned(this: &Vector3) -> Vector3;
+    #[wasm_bindgen(method)]
+    pub fn project(this: &Vector3, camera: &Camera) -> Vector3;
+    #[wasm_bindgen(method)]
+    pub fn unproject(this: &Vector3, camera: &Camera) -> Vector3;
 
     #[wasm_bindgen(method, getter)]
     pub fn x(this: &Vector3) -> f32;
@@ -1,4 +1,4 @@
-use crate::three::{PerspectiveCamera, Scene};
+use crate::three::{Camera, Scene};
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen(module = "/node_modules/three/build/three.module.js")]
@@ -8,7 +8,7 @@ extern "C" {
     #[wasm_bindgen(constructor)]
     pub fn new() -> WebGLRenderer;
     #[wasm_bindgen(method)]
-    pub fn render(this: &WebGLRenderer, scene: &Scene, camera: &PerspectiveCamera);
+    pub fn render(this: &WebGLRenderer, scene: &Scene, camera: &Camera);
     #[wasm_bindgen(method, getter = domElement)]
     pub fn dom_element(this: &WebGLRenderer) -> web_sys::HtmlCanvasElement;
     #[wasm_bindgen(method, js_name = setSize)]
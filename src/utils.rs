@@ -1,6 +1,14 @@
 use std::fs;
+use std::io::{Cursor, Write};
 use std::str::from_utf8;
 
+use zip::{write::FileOptions, CompressionMethod, ZipWriter};
+
+#[cfg(test)]
+use uuid::Uuid;
+#[cfg(test)]
+use crate::tea::TeaHandler;
+
 pub fn get_test(name: &str) -> Vec<u8> {
     let loc = String::from(env!("CARGO_MANIFEST_DIR")) + "/test_data/" + name;
     fs::read(loc).unwrap()
@@ -11,9 +19,53 @@ pub fn get_test_str(name: &str) -> String {
     from_utf8(&buf).unwrap().to_owned()
 }
 
+pub fn get_test_dir(name: &str) -> Vec<(String, Vec<u8>)> {
+    let loc = String::from(env!("CARGO_MANIFEST_DIR")) + "/test_data/" + name;
+    let mut files: Vec<(String, Vec<u8>)> = fs::read_dir(loc).unwrap()
+        .map(|entry| {
+            let entry = entry.unwrap();
+            (entry.file_name().into_string().unwrap(), fs::read(entry.path()).unwrap())
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+pub fn zip_files(files: Vec<(String, Vec<u8>)>) -> Vec<u8> {
+    let mut zip = ZipWriter::new(Cursor::new(vec![]));
+    for (name, buf) in files {
+        zip.start_file(name, FileOptions::default().compression_method(CompressionMethod::Stored)).unwrap();
+        zip.write_all(&buf).unwrap();
+    }
+    zip.finish().unwrap().into_inner()
+}
+
+// loads the test mission, as most tests start from
+#[cfg(test)]
+pub fn testcase() -> TeaHandler {
+    TeaHandler::from_buffer_lenient(zip_files(get_test_dir("testcase"))).unwrap()
+}
+
+// get the uuid of the object with a name, which tests know is there
+#[cfg(test)]
+pub fn uuid_of(th: &TeaHandler, name: &str) -> Uuid {
+    th.display_objects().into_iter().find(|(_, n)| n == name).unwrap().0
+}
+
 #[macro_export]
 macro_rules! pretty_assert_eq {
     ($i:expr, $j:expr) => {
         assert_eq!($i, $j, "\n\n[expected]\n{:#?}\n\n[found]\n{:#?}\n", $i, $j)
     }
-}
\ No newline at end of file
+}
+
+// runs a future that never waits on anything, i.e. over native storage
+pub fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    let mut future = std::pin::pin!(future);
+    let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+    loop {
+        if let std::task::Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output
+        }
+    }
+}
@@ -0,0 +1,233 @@
+// resizes triggers and effects by dragging their faces. a dragged side moves
+// on its own, so the volume grows or shrinks from the opposite side, which
+// stays put. sizes are kept in datafiles and positions in properties
+
+use crate::placement::position;
+use crate::playmission::{parse_orientation, Face, Object, SpecialEffect, Trigger, Value};
+
+// nothing is dragged smaller than this
+const MIN_SIZE: f32 = 0.1;
+
+// the extent of an object that can be resized
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Volume {
+    // a box turned to an orientation, as w, x, y, z
+    Box { centre: [f32; 3], size: [f32; 3], orientation: [f32; 4] },
+    Sphere { centre: [f32; 3], radius: f32 },
+}
+
+// the property and datafile values that resize an object
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Resized {
+    pub properties: Vec<(&'static str, String)>,
+    pub datafile: Vec<(&'static str, String)>,
+}
+
+impl Volume {
+
+    // get the middle of the volume
+    pub fn centre(&self) -> [f32; 3] {
+        match self {
+            Volume::Box { centre, .. } | Volume::Sphere { centre, .. } => *centre,
+        }
+    }
+
+    // get which way a side faces out, with none for faces that face every way
+    pub fn normal(&self, face: Face) -> Option<[f32; 3]> {
+        let (Volume::Box { orientation, .. }, Face::Side { axis, positive }) = (self, face) else { return None };
+        let mut normal = [0.0; 3];
+        normal[axis] = if positive { 1.0 } else { -1.0 };
+        Some(rotate(*orientation, normal))
+    }
+
+    // get the volume with a face moved to this far out from the centre
+    pub fn resized(&self, face: Face, distance: f32) -> Volume {
+        match (*self, face) {
+            (Volume::Box { centre, mut size, orientation }, Face::Side { axis, .. }) => {
+                let grown = (distance + size[axis] / 2.0).max(MIN_SIZE) - size[axis];
+                size[axis] += grown;
+                let normal = self.normal(face).unwrap();
+                let centre = [0, 1, 2].map(|i| centre[i] + normal[i] * grown / 2.0);
+                Volume::Box { centre, size, orientation }
+            },
+            (Volume::Sphere { centre, .. }, Face::Edge) => Volume::Sphere { centre, radius: distance.max(MIN_SIZE) },
+            (volume, _) => volume,
+        }
+    }
+
+}
+
+// get the volume of a trigger or effect
+pub fn volume(object: &Object) -> Option<Volume> {
+    let centre = position(object)?;
+    match object.r#type() {
+        "TRIGGER" => {
+            let orientation = match object.properties().get_value("Orientation") {
+                Ok(Value::String(s)) => parse_orientation(s),
+                _ => None,
+            };
+            Some(Volume::Box { centre, size: Trigger::size(object.datafile()), orientation: orientation.unwrap_or([1.0, 0.0, 0.0, 0.0]) })
+        },
+        "SPECIAL_EFFECT" => Some(Volume::Sphere { centre, radius: SpecialEffect::radius(object.datafile()) }),
+        _ => None,
+    }
+}
+
+// get the values that give an object a volume once one of its faces has been
+// dragged. sizes are only written where the datafile has them, and as whole
+// numbers where it wants those. without a size to write, the drag would only
+// move the object, so nothing is written at all
+pub fn values(object: &Object, volume: &Volume, face: Face) -> Resized {
+
+    let (k, size) = match (volume, face) {
+        (Volume::Box { size, .. }, Face::Side { axis, .. }) => (Trigger::SIZE_KEYS[axis], size[axis]),
+        (Volume::Sphere { radius, .. }, Face::Edge) => (SpecialEffect::RADIUS_KEY, *radius),
+        _ => return Resized::default(),
+    };
+    let (value, written) = match object.datafile().get_value(k) {
+        Ok(Value::Int(_)) => {
+            let whole = size.round().max(1.0);
+            ((whole as i32).to_string(), whole)
+        },
+        Ok(Value::Float(_)) => (size.to_string(), size),
+        _ => return Resized::default(),
+    };
+
+    // the dragged side takes up any rounding, so the opposite side stays put.
+    // spheres only grow about their centre, which doesn't move
+    let properties = match volume.normal(face) {
+        Some(normal) => {
+            let centre = volume.centre();
+            let centre = [0, 1, 2].map(|i| centre[i] + normal[i] * (written - size) / 2.0);
+            vec![
+                ("Position X", centre[0].to_string()),
+                ("Position Y", centre[1].to_string()),
+                ("Position Z", centre[2].to_string()),
+            ]
+        },
+        None => vec![],
+    };
+    Resized { properties, datafile: vec![(k, value)] }
+
+}
+
+// get how far along a line the point nearest a ray is, with none if they run
+// side by side. directions needn't be normalised, and distances are in
+// lengths of the line's direction
+pub fn along(origin: [f32; 3], direction: [f32; 3], ray_origin: [f32; 3], ray_direction: [f32; 3]) -> Option<f32> {
+    let dot = |a: [f32; 3], b: [f32; 3]| a[0] * b[0] + a[1] * b[1] + a[2] * b[2];
+    let between = [0, 1, 2].map(|i| origin[i] - ray_origin[i]);
+    let (a, b, c) = (dot(direction, direction), dot(direction, ray_direction), dot(ray_direction, ray_direction));
+    let (d, e) = (dot(direction, between), dot(ray_direction, between));
+    let denominator = a * c - b * b;
+    (denominator.abs() > f32::EPSILON).then(|| (b * e - c * d) / denominator)
+}
+
+// get how far across the floor a ray passes from a point, at the point's height
+pub fn across(centre: [f32; 3], ray_origin: [f32; 3], ray_direction: [f32; 3]) -> Option<f32> {
+    if ray_direction[1].abs() < f32::EPSILON { return None }
+    let t = (centre[1] - ray_origin[1]) / ray_direction[1];
+    if t < 0.0 { return None }
+    let (x, z) = (ray_origin[0] + ray_direction[0] * t, ray_origin[2] + ray_direction[2] * t);
+    Some(((x - centre[0]).powi(2) + (z - centre[2]).powi(2)).sqrt())
+}
+
+// turns a vector by a quaternion, as w, x, y, z
+fn rotate([w, x, y, z]: [f32; 4], v: [f32; 3]) -> [f32; 3] {
+    let u = [x, y, z];
+    let cross = |a: [f32; 3], b: [f32; 3]| [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]];
+    let t = cross(u, v).map(|c| 2.0 * c);
+    let turned = cross(u, t);
+    [0, 1, 2].map(|i| v[i] + w * t[i] + turned[i])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use crate::playmission::{MissionObject, Properties};
+    use crate::utils::{get_test, get_test_dir, zip_files};
+
+    fn named(name: &str) -> Object {
+        let (_, objects, _) = MissionObject::deserialize_lenient(Cursor::new(zip_files(get_test_dir("testcase")))).unwrap();
+        objects.into_values().find(|o| o.name().as_deref() == Some(name)).unwrap()
+    }
+
+    fn close(a: [f32; 3], b: [f32; 3]) -> bool {
+        a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-5)
+    }
+
+    #[test]
+    fn sides_move_on_their_own() {
+        // a quarter turn about y, so its x faces along -z
+        let half = std::f32::consts::FRAC_1_SQRT_2;
+        let volume = Volume::Box { centre: [1.0, 1.0, 1.0], size: [2.0, 2.0, 4.0], orientation: [half, 0.0, half, 0.0] };
+        let face = Face::Side { axis: 0, positive: true };
+        assert!(close([0.0, 0.0, -1.0], volume.normal(face).unwrap()));
+
+        let Volume::Box { centre, size, .. } = volume.resized(face, 3.0) else { panic!() };
+        assert!(close([1.0, 1.0, -0.0], centre));
+        assert!(close([4.0, 2.0, 4.0], size));
+        // dragged through the opposite side, it stops short
+        let Volume::Box { size, .. } = volume.resized(face, -5.0) else { panic!() };
+        assert!(close([MIN_SIZE, 2.0, 4.0], size));
+    }
+
+    #[test]
+    fn drags_follow_the_pointer() {
+        // a ray straight down onto the line, two along it
+        assert_eq!(Some(2.0), along([0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [2.0, 5.0, 1.0], [0.0, -1.0, 0.0]));
+        assert_eq!(None, along([0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 5.0, 0.0], [2.0, 0.0, 0.0]));
+        assert_eq!(Some(5.0), across([1.0, 1.0, 1.0], [4.0, 5.0, 5.0], [0.0, -1.0, 0.0]));
+
+        let sphere = Volume::Sphere { centre: [0.0; 3], radius: 1.0 };
+        assert_eq!(Volume::Sphere { centre: [0.0; 3], radius: 2.5 }, sphere.resized(Face::Edge, 2.5));
+        assert_eq!(sphere, sphere.resized(Face::Side { axis: 0, positive: true }, 2.5));
+    }
+
+    #[test]
+    fn whole_sizes_keep_the_opposite_side() {
+        // tiles are sized by the same keys as triggers, but in whole numbers
+        let datafile = Properties::from_datafile_default("baronial_3door.tile", get_test("testcase/baronial_3door.tile"), "Default.tile", get_test("testcase/Default.tile")).unwrap();
+        let mut properties = Properties::new();
+        for k in ["Position X", "Position Y", "Position Z"] {
+            properties.insert_new(k, "0.0", "VTYPE_FLOAT", None).unwrap();
+        }
+        let object = Object::new(Box::new(Trigger::new()), properties, Some(datafile), None, None);
+        let volume = volume(&object).unwrap();
+        assert_eq!(Volume::Box { centre: [0.0; 3], size: [6.0, 6.0, 1.0], orientation: [1.0, 0.0, 0.0, 0.0] }, volume);
+
+        // dragged out by 0.8, then rounded up to a whole 7, with the far side still at -3
+        let face = Face::Side { axis: 0, positive: true };
+        let resized = values(&object, &volume.resized(face, 3.8), face);
+        assert_eq!(vec![("Size X", "7".to_string())], resized.datafile);
+        assert_eq!(("Position X", "0.5".to_string()), resized.properties[0]);
+
+        // there's no Size Z to write, so the trigger isn't moved either
+        let face = Face::Side { axis: 2, positive: true };
+        assert_eq!(Resized::default(), values(&object, &volume.resized(face, 2.0), face));
+    }
+    #[test]
+    fn triggers_resize_by_their_sizes() {
+        let trigger = named("Doorway");
+        let volume = volume(&trigger).unwrap();
+        assert_eq!(Volume::Box { centre: [-33.0, 1.5, 24.0], size: [2.0, 3.0, 0.5], orientation: [1.0, 0.0, 0.0, 0.0] }, volume);
+
+        for axis in 0..3 {
+            let face = Face::Side { axis, positive: false };
+            let resized = values(&trigger, &volume.resized(face, 2.0), face);
+            assert_eq!(Trigger::SIZE_KEYS[axis], resized.datafile[0].0);
+        }
+    }
+
+    #[test]
+    fn effects_resize_by_their_radius() {
+        let effect = named("Sparks");
+        let volume = volume(&effect).unwrap();
+        assert_eq!(Volume::Sphere { centre: [-32.0, 1.0, 26.5], radius: 1.5 }, volume);
+
+        let resized = values(&effect, &volume.resized(Face::Edge, 2.5), Face::Edge);
+        assert_eq!(vec![(SpecialEffect::RADIUS_KEY, "2.5".to_string())], resized.datafile);
+        assert!(resized.properties.is_empty());
+    }
+}